@@ -0,0 +1,490 @@
+use crate::*;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{
+    Rc,
+    Weak
+};
+
+use wasm_bindgen::{
+    JsCast,
+    JsValue
+};
+use wasm_bindgen::closure::Closure;
+
+use wasmuri_core::Region;
+
+use web_sys::{
+    Event,
+    FocusEvent,
+    HtmlCanvasElement,
+    HtmlElement,
+    KeyboardEvent
+};
+
+/// The kind of widget an AccessNode describes, which determines how assistive technology presents it
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum AccessRole {
+
+    Button,
+    Checkbox,
+    RadioGroup,
+
+    /// An option of a RadioGroup. Nodes with this role should be children of a RadioGroup node.
+    Radio,
+    Slider,
+    TextField,
+    Label,
+    ListBox,
+    ComboBox,
+    ProgressBar,
+    Group
+}
+
+impl AccessRole {
+
+    /// Gets the value of the ARIA role attribute of this role
+    pub fn to_aria_role(&self) -> &'static str {
+        match self {
+            AccessRole::Button => "button",
+            AccessRole::Checkbox => "checkbox",
+            AccessRole::RadioGroup => "radiogroup",
+            AccessRole::Radio => "radio",
+            AccessRole::Slider => "slider",
+            AccessRole::TextField => "textbox",
+            AccessRole::Label => "note",
+            AccessRole::ListBox => "listbox",
+            AccessRole::ComboBox => "combobox",
+            AccessRole::ProgressBar => "progressbar",
+            AccessRole::Group => "group"
+        }
+    }
+
+    /// Checks if assistive technology should be able to move the focus to nodes with this role
+    pub fn is_focusable(&self) -> bool {
+        match self {
+            AccessRole::Label | AccessRole::ProgressBar | AccessRole::Group => false,
+            _ => true
+        }
+    }
+
+    /// Checks if the arrow keys should change the value of nodes with this role
+    pub fn uses_arrow_keys(&self) -> bool {
+        match self {
+            AccessRole::Slider | AccessRole::RadioGroup | AccessRole::Radio => true,
+            _ => false
+        }
+    }
+}
+
+/// Describes a component to assistive technology like screen readers. The ContainerManager mirrors the nodes of all components in
+/// hidden DOM elements, because the browser can't look inside the canvas.
+#[derive(Clone,PartialEq)]
+pub struct AccessNode {
+
+    role: AccessRole,
+    name: String,
+
+    value: Option<String>,
+    checked: Option<bool>,
+    disabled: bool,
+
+    region: Region,
+
+    /// The nodes of the parts of the component that assistive technology should present separately, like the options of a
+    /// RadioGroup. Their actions are passed to the component as AccessAction::ActivateChild.
+    children: Vec<AccessNode>
+}
+
+impl AccessNode {
+
+    pub fn new(role: AccessRole, name: &str, region: Region) -> AccessNode {
+        AccessNode {
+            role,
+            name: name.to_string(),
+            value: None,
+            checked: None,
+            disabled: false,
+            region,
+            children: Vec::new()
+        }
+    }
+
+    /// Sets the current value of the node, like the text of a text field or the value of a slider
+    pub fn with_value(mut self, value: &str) -> AccessNode {
+        self.value = Some(value.to_string());
+        self
+    }
+
+    pub fn with_checked(mut self, checked: bool) -> AccessNode {
+        self.checked = Some(checked);
+        self
+    }
+
+    pub fn with_disabled(mut self, disabled: bool) -> AccessNode {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Adds a child node. The region of the child should be inside the region of this node.
+    pub fn with_child(mut self, child: AccessNode) -> AccessNode {
+        self.children.push(child);
+        self
+    }
+
+    pub fn get_role(&self) -> AccessRole {
+        self.role
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_value(&self) -> Option<&str> {
+        self.value.as_ref().map(|value| value.as_str())
+    }
+
+    pub fn get_checked(&self) -> Option<bool> {
+        self.checked
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    pub fn get_region(&self) -> Region {
+        self.region
+    }
+
+    pub fn get_children(&self) -> &[AccessNode] {
+        &self.children
+    }
+}
+
+/// Something assistive technology did with the accessibility node of a component
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum AccessAction {
+
+    /// The node got the focus of the assistive technology
+    Focus,
+
+    /// The node lost the focus of the assistive technology
+    Blur,
+
+    /// The user activated the node, which should do the same as clicking on the component
+    Activate,
+
+    /// The user activated the child node with the given index
+    ActivateChild(usize),
+
+    /// The user pressed the up or right arrow key on a node whose role uses the arrow keys
+    Increment,
+
+    /// The user pressed the down or left arrow key on a node whose role uses the arrow keys
+    Decrement
+}
+
+/// The DOM element that mirrors the accessibility node of one component
+struct MirroredNode {
+
+    element: HtmlElement,
+    node: AccessNode,
+
+    /// The elements of the children of the node, in the same order as the children
+    children: Vec<MirroredChild>,
+
+    // The listeners are removed together with the element, so they only need to live as long as this struct
+    _listeners: Vec<Closure<dyn FnMut(Event)>>
+}
+
+/// The DOM element that mirrors a child of the accessibility node of a component
+struct MirroredChild {
+
+    element: HtmlElement,
+    _listeners: Vec<Closure<dyn FnMut(Event)>>
+}
+
+/// The AccessibilityMirror owns a hidden DOM tree with an element for the AccessNode of every component of the current container.
+/// The elements are placed over the regions of their components, so screen readers can present them in the right place. Focus
+/// changes and activations of these elements are passed back to the behaviors of the components.
+pub struct AccessibilityMirror {
+
+    canvas: HtmlCanvasElement,
+    root: HtmlElement,
+
+    /// The hidden input element of the ImeBridge, which text fields focus when assistive technology focuses them
+    ime_input: HtmlElement,
+
+    /// The bounds of the canvas the root was last placed over
+    root_bounds: (f64, f64, f64, f64),
+
+    nodes: HashMap<ComponentKey, MirroredNode>,
+
+    /// The keys of the nodes in the order of their elements in the root
+    order: Vec<ComponentKey>,
+
+    weak_manager: Option<Weak<RefCell<ContainerManager>>>,
+    pending_actions: Rc<RefCell<Vec<(ComponentKey, AccessAction)>>>
+}
+
+impl AccessibilityMirror {
+
+    pub fn new(canvas: &HtmlCanvasElement, ime: &ImeBridge) -> AccessibilityMirror {
+        let document = web_sys::window().expect("Should have window").document().expect("Window should have document");
+        let root = document.create_element("div").expect("Should be able to create div element")
+                .dyn_into::<HtmlElement>().expect("A div element should be an HtmlElement");
+
+        // The elements should be found by assistive technology, but they shouldn't be visible or get the mouse events of the canvas
+        let css = root.style();
+        css.set_property("position", "fixed").expect("Should be able to set position property");
+        css.set_property("opacity", "0").expect("Should be able to set opacity property");
+        css.set_property("pointer-events", "none").expect("Should be able to set pointer-events property");
+        css.set_property("overflow", "hidden").expect("Should be able to set overflow property");
+
+        document.body().expect("Document should have body").append_child(&root).expect("Should be able to append accessibility root");
+
+        AccessibilityMirror {
+            canvas: canvas.clone(),
+            root,
+            ime_input: ime.get_input().clone().into(),
+            root_bounds: (0.0, 0.0, 0.0, 0.0),
+            nodes: HashMap::new(),
+            order: Vec::new(),
+            weak_manager: None,
+            pending_actions: Rc::new(RefCell::new(Vec::new()))
+        }
+    }
+
+    /// Lets the mirror pass the actions of assistive technology to the given manager as soon as it is not busy
+    pub(crate) fn start_listening(&mut self, manager_cell: &Rc<RefCell<ContainerManager>>) {
+        self.weak_manager = Some(Rc::downgrade(manager_cell));
+    }
+
+    pub(crate) fn take_pending_actions(&self) -> Vec<(ComponentKey, AccessAction)> {
+        std::mem::replace(&mut *self.pending_actions.borrow_mut(), Vec::new())
+    }
+
+    /// Gets the accessibility node of the component with the given key, as it was during the last synchronization
+    pub fn get_node(&self, key: ComponentKey) -> Option<&AccessNode> {
+        self.nodes.get(&key).map(|mirrored| &mirrored.node)
+    }
+
+    /// Gets the number of nodes that are currently mirrored
+    pub fn get_node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Updates the DOM tree such that it contains exactly the given nodes, in the given order. Only the elements of the nodes that
+    /// changed since the last synchronization are touched.
+    pub(crate) fn sync(&mut self, nodes: Vec<(ComponentKey, AccessNode)>) {
+        self.update_root_bounds();
+
+        let new_order: Vec<ComponentKey> = nodes.iter().map(|(key, _node)| *key).collect();
+        let root = &self.root;
+        self.nodes.retain(|key, mirrored| {
+            let keep = new_order.contains(key);
+            if !keep {
+                root.remove_child(&mirrored.element).expect("Should be able to remove accessibility element");
+            }
+            keep
+        });
+
+        for (key, node) in nodes {
+            let changed = match self.nodes.get(&key) {
+                Some(mirrored) => mirrored.node != node,
+                None => true
+            };
+            if changed {
+                let mut mirrored = match self.nodes.remove(&key) {
+                    Some(mirrored) => mirrored,
+                    None => self.create_element(key, node.clone())
+                };
+                Self::update_element(&mirrored.element, &node, Region::entire_viewport());
+                self.sync_children(key, &mut mirrored, &node);
+                mirrored.node = node;
+                self.nodes.insert(key, mirrored);
+            }
+        }
+
+        // Assistive technology uses the order of the elements as reading and tab order
+        if new_order != self.order {
+            for key in &new_order {
+                self.root.append_child(&self.nodes[key].element).expect("Should be able to move accessibility element");
+            }
+            self.order = new_order;
+        }
+    }
+
+    /// Places the root over the canvas, if the canvas moved or changed size since the last call
+    fn update_root_bounds(&mut self) {
+        let bounds = self.canvas.get_bounding_client_rect();
+        let new_bounds = (bounds.left(), bounds.top(), bounds.width(), bounds.height());
+        if new_bounds != self.root_bounds {
+            let css = self.root.style();
+            css.set_property("left", &format!("{}px", new_bounds.0)).expect("Should be able to set left property");
+            css.set_property("top", &format!("{}px", new_bounds.1)).expect("Should be able to set top property");
+            css.set_property("width", &format!("{}px", new_bounds.2)).expect("Should be able to set width property");
+            css.set_property("height", &format!("{}px", new_bounds.3)).expect("Should be able to set height property");
+            self.root_bounds = new_bounds;
+        }
+    }
+
+    fn create_element(&self, key: ComponentKey, node: AccessNode) -> MirroredNode {
+        let document = web_sys::window().expect("Should have window").document().expect("Window should have document");
+        let element = document.create_element("div").expect("Should be able to create div element")
+                .dyn_into::<HtmlElement>().expect("A div element should be an HtmlElement");
+        element.style().set_property("position", "absolute").expect("Should be able to set position property");
+        self.root.append_child(&element).expect("Should be able to append accessibility element");
+
+        let ime_input = self.ime_input.clone();
+        let uses_arrow_keys = node.role.uses_arrow_keys();
+        let listeners = vec![
+            self.listen(&element, "focus", key, |_event| Some(AccessAction::Focus)),
+            self.listen(&element, "blur", key, move |event| {
+
+                // A text field that gets the focus gives it to the hidden input of the ImeBridge, which shouldn't blur it again
+                let ime_focused = event.dyn_ref::<FocusEvent>().and_then(|focus_event| focus_event.related_target())
+                        .map_or(false, |target| AsRef::<JsValue>::as_ref(&target) == AsRef::<JsValue>::as_ref(&ime_input));
+                if ime_focused {
+                    None
+                } else {
+                    Some(AccessAction::Blur)
+                }
+            }),
+
+            // Screen readers fire click events when the user activates an element
+            self.listen(&element, "click", key, |_event| Some(AccessAction::Activate)),
+            self.listen(&element, "keydown", key, move |event| get_key_action(event, AccessAction::Activate, uses_arrow_keys))
+        ];
+
+        MirroredNode {
+            element,
+            node,
+            children: Vec::new(),
+            _listeners: listeners
+        }
+    }
+
+    /// Creates or removes the elements of the children of the given node, such that there is exactly 1 element for each child, and
+    /// updates them. The elements are reused as much as possible, so that the focus of assistive technology stays on the same child.
+    fn sync_children(&self, key: ComponentKey, mirrored: &mut MirroredNode, node: &AccessNode) {
+        while mirrored.children.len() > node.children.len() {
+            let child = mirrored.children.pop().unwrap();
+            mirrored.element.remove_child(&child.element).expect("Should be able to remove accessibility element");
+        }
+
+        let document = web_sys::window().expect("Should have window").document().expect("Window should have document");
+        while mirrored.children.len() < node.children.len() {
+            let index = mirrored.children.len();
+            let element = document.create_element("div").expect("Should be able to create div element")
+                    .dyn_into::<HtmlElement>().expect("A div element should be an HtmlElement");
+            element.style().set_property("position", "absolute").expect("Should be able to set position property");
+            mirrored.element.append_child(&element).expect("Should be able to append accessibility element");
+
+            // The component is told that it got the focus when one of its children gets it. Blurring a child is not passed on,
+            // because the focus often just moves to another child of the same component. The events shouldn't bubble to the
+            // element of the component, which would pass them as actions on the component itself.
+            let uses_arrow_keys = node.children[index].role.uses_arrow_keys();
+            let listeners = vec![
+                self.listen(&element, "focus", key, |_event| Some(AccessAction::Focus)),
+                self.listen(&element, "click", key, move |event| {
+                    event.stop_propagation();
+                    Some(AccessAction::ActivateChild(index))
+                }),
+                self.listen(&element, "keydown", key, move |event| {
+                    let action = get_key_action(event, AccessAction::ActivateChild(index), uses_arrow_keys);
+                    if action.is_some() {
+                        event.stop_propagation();
+                    }
+                    action
+                })
+            ];
+            mirrored.children.push(MirroredChild {
+                element,
+                _listeners: listeners
+            });
+        }
+
+        for (child, child_node) in mirrored.children.iter().zip(&node.children) {
+            Self::update_element(&child.element, child_node, node.region);
+        }
+    }
+
+    /// Sets the attributes of the element to those of the node and places it over the region of the node. The parent region is the
+    /// region that is covered by the parent of the element.
+    fn update_element(element: &HtmlElement, node: &AccessNode, parent_region: Region) {
+        let set = |name: &str, value: &str| element.set_attribute(name, value).expect("Should be able to set accessibility attribute");
+        let remove = |name: &str| element.remove_attribute(name).expect("Should be able to remove accessibility attribute");
+
+        set("role", node.role.to_aria_role());
+        set("aria-label", &node.name);
+        match &node.value {
+            Some(value) => set("aria-valuetext", value),
+            None => remove("aria-valuetext")
+        };
+        match node.checked {
+            Some(checked) => set("aria-checked", if checked { "true" } else { "false" }),
+            None => remove("aria-checked")
+        };
+        set("aria-disabled", if node.disabled { "true" } else { "false" });
+        if node.role.is_focusable() && !node.disabled {
+            set("tabindex", "0");
+        } else {
+            remove("tabindex");
+        }
+
+        // The regions are converted to percentages of the size of the parent element
+        let region = node.region;
+        let parent_width = parent_region.get_max_x() - parent_region.get_min_x();
+        let parent_height = parent_region.get_max_y() - parent_region.get_min_y();
+        let left = (region.get_min_x() - parent_region.get_min_x()) / parent_width * 100.0;
+        let top = (parent_region.get_max_y() - region.get_max_y()) / parent_height * 100.0;
+        let width = (region.get_max_x() - region.get_min_x()) / parent_width * 100.0;
+        let height = (region.get_max_y() - region.get_min_y()) / parent_height * 100.0;
+
+        let css = element.style();
+        css.set_property("left", &format!("{}%", left)).expect("Should be able to set left property");
+        css.set_property("top", &format!("{}%", top)).expect("Should be able to set top property");
+        css.set_property("width", &format!("{}%", width)).expect("Should be able to set width property");
+        css.set_property("height", &format!("{}%", height)).expect("Should be able to set height property");
+    }
+
+    /// Listens to the given DOM event of the element and passes the resulting action (if any) to the manager
+    fn listen<F: Fn(&Event) -> Option<AccessAction> + 'static>(&self, element: &HtmlElement, event_name: &str, key: ComponentKey, 
+            to_action: F) -> Closure<dyn FnMut(Event)> {
+        let weak_manager = self.weak_manager.clone();
+        let pending_actions = Rc::clone(&self.pending_actions);
+
+        let closure = Closure::wrap(Box::new(move |event: Event| {
+            if let Some(action) = to_action(&event) {
+                pending_actions.borrow_mut().push((key, action));
+
+                if let Some(manager_cell) = weak_manager.as_ref().and_then(|weak_manager| weak_manager.upgrade()) {
+                    if let Ok(mut manager) = manager_cell.try_borrow_mut() {
+                        manager.process_pending_access_actions();
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(Event)>);
+
+        element.add_event_listener_with_callback(event_name, closure.as_ref().unchecked_ref())
+                .expect("Should be able to add accessibility listener");
+        closure
+    }
+}
+
+/// Converts the key that was pressed on an element of the mirror to an action. Enter and space activate the element, and the arrow
+/// keys change the value of the element if its role uses them.
+fn get_key_action(event: &Event, activate: AccessAction, uses_arrow_keys: bool) -> Option<AccessAction> {
+    let key_event = event.dyn_ref::<KeyboardEvent>()?;
+
+    let action = match key_event.key().as_str() {
+        "Enter" | " " => activate,
+        "ArrowUp" | "ArrowRight" if uses_arrow_keys => AccessAction::Increment,
+        "ArrowDown" | "ArrowLeft" if uses_arrow_keys => AccessAction::Decrement,
+        _ => return None
+    };
+    event.prevent_default();
+    Some(action)
+}
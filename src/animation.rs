@@ -0,0 +1,508 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use wasmuri_core::{
+    Color,
+    Region
+};
+
+/// Determines how the progress of a Tween changes over time. All easing functions map 0 to 0 and 1 to 1.
+#[derive(Clone,Copy)]
+pub enum Easing {
+
+    Linear,
+
+    /// Starts slow and ends fast
+    EaseIn,
+
+    /// Starts fast and ends slow
+    EaseOut,
+
+    /// Starts slow, is fast halfway and ends slow
+    EaseInOut,
+
+    Custom(fn(f32) -> f32)
+}
+
+impl Easing {
+
+    /// Maps the linear progress (between 0 and 1) to the eased progress
+    pub fn apply(&self, progress: f32) -> f32 {
+        match self {
+            Easing::Linear => progress,
+            Easing::EaseIn => progress * progress * progress,
+            Easing::EaseOut => {
+                let inverse = 1.0 - progress;
+                1.0 - inverse * inverse * inverse
+            }, Easing::EaseInOut => {
+                if progress < 0.5 {
+                    4.0 * progress * progress * progress
+                } else {
+                    let inverse = 2.0 - 2.0 * progress;
+                    1.0 - inverse * inverse * inverse * 0.5
+                }
+            }, Easing::Custom(function) => function(progress)
+        }
+    }
+}
+
+/// A value that can be interpolated by a Tween
+pub trait Lerp : Copy {
+
+    /// Gets the value that is the given fraction of the way from self to other. The fraction is usually between 0 and 1, but
+    /// custom easing functions can overshoot.
+    fn lerp(&self, other: &Self, fraction: f32) -> Self;
+}
+
+impl Lerp for f32 {
+
+    fn lerp(&self, other: &f32, fraction: f32) -> f32 {
+        self + (other - self) * fraction
+    }
+}
+
+impl Lerp for f64 {
+
+    fn lerp(&self, other: &f64, fraction: f32) -> f64 {
+        self + (other - self) * fraction as f64
+    }
+}
+
+impl Lerp for (f32, f32) {
+
+    fn lerp(&self, other: &(f32, f32), fraction: f32) -> (f32, f32) {
+        (self.0.lerp(&other.0, fraction), self.1.lerp(&other.1, fraction))
+    }
+}
+
+impl Lerp for Color {
+
+    fn lerp(&self, other: &Color, fraction: f32) -> Color {
+        let channel = |from: u8, to: u8| (from as f32).lerp(&(to as f32), fraction).round().max(0.0).min(255.0) as u8;
+        Color::from_rgba(
+            channel(self.get_red(), other.get_red()),
+            channel(self.get_green(), other.get_green()),
+            channel(self.get_blue(), other.get_blue()),
+            channel(self.get_alpha(), other.get_alpha())
+        )
+    }
+}
+
+impl Lerp for Region {
+
+    fn lerp(&self, other: &Region, fraction: f32) -> Region {
+        Region::new(
+            self.get_min_x().lerp(&other.get_min_x(), fraction),
+            self.get_min_y().lerp(&other.get_min_y(), fraction),
+            self.get_max_x().lerp(&other.get_max_x(), fraction),
+            self.get_max_y().lerp(&other.get_max_y(), fraction)
+        )
+    }
+}
+
+/// A value that is shared between a behavior and the animations that change it. Cloning an Animated gives another reference to the
+/// same value.
+pub struct Animated<T: Copy> {
+
+    value: Rc<Cell<T>>
+}
+
+impl<T: Copy> Animated<T> {
+
+    pub fn new(value: T) -> Animated<T> {
+        Animated {
+            value: Rc::new(Cell::new(value))
+        }
+    }
+
+    pub fn get(&self) -> T {
+        self.value.get()
+    }
+
+    pub fn set(&self, value: T) {
+        self.value.set(value);
+    }
+}
+
+impl<T: Copy> Clone for Animated<T> {
+
+    fn clone(&self) -> Animated<T> {
+        Animated {
+            value: Rc::clone(&self.value)
+        }
+    }
+}
+
+/// Something that changes over time, like a Tween or a group of other animations
+pub trait Animation {
+
+    /// Moves this animation forward by the given time (in milliseconds) and returns the part of that time that was left when
+    /// this animation finished, or None if it hasn't finished yet
+    fn advance(&mut self, delta: f64) -> Option<f64>;
+}
+
+/// Changes an Animated value from one value to another in the given duration
+pub struct Tween<T: Lerp> {
+
+    target: Animated<T>,
+
+    /// The value to start from, or None to start from the value the target has when the tween starts
+    from: Option<T>,
+    to: T,
+
+    duration: f64,
+    elapsed: f64,
+    easing: Easing
+}
+
+impl<T: Lerp> Tween<T> {
+
+    /// Creates a Tween that changes the target from its current value (at the time the tween starts) to the given value in the
+    /// given duration (in milliseconds)
+    pub fn new(target: &Animated<T>, to: T, duration: f64) -> Tween<T> {
+        Tween {
+            target: target.clone(),
+            from: None,
+            to,
+            duration,
+            elapsed: 0.0,
+            easing: Easing::EaseInOut
+        }
+    }
+
+    pub fn from(mut self, from: T) -> Tween<T> {
+        self.from = Some(from);
+        self
+    }
+
+    /// Sets the easing of this tween. The default easing is EaseInOut.
+    pub fn with_easing(mut self, easing: Easing) -> Tween<T> {
+        self.easing = easing;
+        self
+    }
+
+    pub fn boxed(self) -> Box<dyn Animation> where T: 'static {
+        Box::new(self)
+    }
+}
+
+impl<T: Lerp> Animation for Tween<T> {
+
+    fn advance(&mut self, delta: f64) -> Option<f64> {
+        let from = match self.from {
+            Some(from) => from,
+            None => {
+                let from = self.target.get();
+                self.from = Some(from);
+                from
+            }
+        };
+
+        self.elapsed += delta;
+        if self.elapsed >= self.duration {
+            self.target.set(self.to);
+            Some(self.elapsed - self.duration)
+        } else {
+            let progress = (self.elapsed / self.duration) as f32;
+            self.target.set(from.lerp(&self.to, self.easing.apply(progress)));
+            None
+        }
+    }
+}
+
+/// An animation that does nothing for the given duration (in milliseconds), which is useful in a Sequence
+pub struct Delay {
+
+    remaining: f64
+}
+
+impl Delay {
+
+    pub fn new(duration: f64) -> Delay {
+        Delay {
+            remaining: duration
+        }
+    }
+
+    pub fn boxed(self) -> Box<dyn Animation> {
+        Box::new(self)
+    }
+}
+
+impl Animation for Delay {
+
+    fn advance(&mut self, delta: f64) -> Option<f64> {
+        self.remaining -= delta;
+        if self.remaining <= 0.0 {
+            Some(-self.remaining)
+        } else {
+            None
+        }
+    }
+}
+
+/// Plays the given animations one after another
+pub struct Sequence {
+
+    animations: Vec<Box<dyn Animation>>,
+    current_index: usize
+}
+
+impl Sequence {
+
+    pub fn new(animations: Vec<Box<dyn Animation>>) -> Sequence {
+        Sequence {
+            animations,
+            current_index: 0
+        }
+    }
+
+    pub fn boxed(self) -> Box<dyn Animation> {
+        Box::new(self)
+    }
+}
+
+impl Animation for Sequence {
+
+    fn advance(&mut self, delta: f64) -> Option<f64> {
+        let mut remaining = delta;
+        while self.current_index < self.animations.len() {
+            match self.animations[self.current_index].advance(remaining) {
+                Some(leftover) => {
+                    remaining = leftover;
+                    self.current_index += 1;
+                }, None => return None
+            };
+        }
+        Some(remaining)
+    }
+}
+
+/// Plays the given animations at the same time. It finishes when all of them are finished.
+pub struct Parallel {
+
+    animations: Vec<Box<dyn Animation>>,
+
+    /// The leftover time of each animation that has finished
+    finished: Vec<Option<f64>>
+}
+
+impl Parallel {
+
+    pub fn new(animations: Vec<Box<dyn Animation>>) -> Parallel {
+        let finished = vec![None; animations.len()];
+        Parallel {
+            animations,
+            finished
+        }
+    }
+
+    pub fn boxed(self) -> Box<dyn Animation> {
+        Box::new(self)
+    }
+}
+
+impl Animation for Parallel {
+
+    fn advance(&mut self, delta: f64) -> Option<f64> {
+        let mut all_finished = true;
+        for (index, animation) in self.animations.iter_mut().enumerate() {
+            self.finished[index] = match self.finished[index] {
+                Some(leftover) => Some(leftover + delta),
+                None => animation.advance(delta)
+            };
+            all_finished &= self.finished[index].is_some();
+        }
+
+        if all_finished {
+
+            // The group finished when the longest animation finished, which is the one with the least time left over
+            Some(self.finished.iter().map(|leftover| leftover.unwrap()).fold(delta, f64::min))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum AnimationStatus {
+
+    Running,
+    Finished,
+    Cancelled
+}
+
+/// Can be used to check whether an animation that was started with ComponentAgent::animate is still running, and to cancel it
+#[derive(Clone)]
+pub struct AnimationHandle {
+
+    status: Rc<Cell<AnimationStatus>>
+}
+
+impl AnimationHandle {
+
+    fn new() -> AnimationHandle {
+        AnimationHandle {
+            status: Rc::new(Cell::new(AnimationStatus::Running))
+        }
+    }
+
+    pub fn get_status(&self) -> AnimationStatus {
+        self.status.get()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.get_status() == AnimationStatus::Running
+    }
+
+    /// Stops the animation before its next step. The animated values keep their current values and the completion callback
+    /// won't be called.
+    pub fn cancel(&self) {
+        if self.is_running() {
+            self.status.set(AnimationStatus::Cancelled);
+        }
+    }
+}
+
+/// An animation that was started by a component, together with its handle and completion callback
+pub(crate) struct RunningAnimation {
+
+    animation: Box<dyn Animation>,
+    handle: AnimationHandle,
+    on_complete: Option<Box<dyn FnOnce()>>
+}
+
+impl RunningAnimation {
+
+    pub(crate) fn new(animation: Box<dyn Animation>, on_complete: Option<Box<dyn FnOnce()>>) -> (RunningAnimation, AnimationHandle) {
+        let handle = AnimationHandle::new();
+        (RunningAnimation {
+            animation,
+            handle: handle.clone(),
+            on_complete
+        }, handle)
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.handle.get_status() == AnimationStatus::Cancelled
+    }
+
+    /// Cancels this animation, unless it already finished
+    pub(crate) fn cancel(&self) {
+        self.handle.cancel();
+    }
+
+    /// Advances the animation and returns true if it finished
+    pub(crate) fn advance(&mut self, delta: f64) -> bool {
+        let finished = self.animation.advance(delta).is_some();
+        if finished {
+            self.handle.status.set(AnimationStatus::Finished);
+        }
+        finished
+    }
+
+    pub(crate) fn take_on_complete(&mut self) -> Option<Box<dyn FnOnce()>> {
+        self.on_complete.take()
+    }
+}
+
+impl Drop for RunningAnimation {
+
+    fn drop(&mut self) {
+
+        // This happens when the agent of the component is dropped while the animation is still running
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_easing() {
+        for easing in &[Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(0.0, easing.apply(0.0));
+            assert_eq!(1.0, easing.apply(1.0));
+        }
+
+        assert_eq!(0.5, Easing::Linear.apply(0.5));
+        assert_eq!(0.125, Easing::EaseIn.apply(0.5));
+        assert_eq!(0.875, Easing::EaseOut.apply(0.5));
+        assert_eq!(0.5, Easing::EaseInOut.apply(0.5));
+        assert!(Easing::EaseInOut.apply(0.25) < 0.25);
+        assert!(Easing::EaseInOut.apply(0.75) > 0.75);
+
+        fn double(progress: f32) -> f32 {
+            progress * 2.0
+        }
+        assert_eq!(1.0, Easing::Custom(double).apply(0.5));
+    }
+
+    #[test]
+    fn test_tween() {
+        let value = Animated::new(0.0f32);
+        let mut tween = Tween::new(&value, 1.0, 100.0).with_easing(Easing::Linear);
+
+        assert_eq!(None, tween.advance(25.0));
+        assert_eq!(0.25, value.get());
+        assert_eq!(Some(25.0), tween.advance(100.0));
+        assert_eq!(1.0, value.get());
+
+        // The start value is taken when the tween starts rather than when it is created
+        value.set(0.5);
+        let mut tween = Tween::new(&value, 1.0, 100.0).with_easing(Easing::Linear);
+        assert_eq!(None, tween.advance(50.0));
+        assert_eq!(0.75, value.get());
+    }
+
+    #[test]
+    fn test_sequence() {
+        let mut sequence = Sequence::new(vec![Delay::new(100.0).boxed(), Delay::new(50.0).boxed()]);
+        assert_eq!(None, sequence.advance(120.0));
+        assert_eq!(Some(10.0), sequence.advance(40.0));
+
+        assert_eq!(Some(5.0), Sequence::new(Vec::new()).advance(5.0));
+    }
+
+    #[test]
+    fn test_sequence_of_tweens() {
+        let value = Animated::new(0.0f32);
+        let mut sequence = Sequence::new(vec![
+            Tween::new(&value, 1.0, 100.0).with_easing(Easing::Linear).boxed(),
+            Tween::new(&value, 0.0, 100.0).with_easing(Easing::Linear).boxed()
+        ]);
+
+        // The time that is left after the first tween should be used by the second tween
+        assert_eq!(None, sequence.advance(150.0));
+        assert_eq!(0.5, value.get());
+        assert_eq!(Some(0.0), sequence.advance(50.0));
+        assert_eq!(0.0, value.get());
+    }
+
+    #[test]
+    fn test_parallel() {
+        let mut parallel = Parallel::new(vec![Delay::new(100.0).boxed(), Delay::new(50.0).boxed()]);
+        assert_eq!(None, parallel.advance(60.0));
+        assert_eq!(Some(10.0), parallel.advance(50.0));
+    }
+
+    #[test]
+    fn test_handle() {
+        let (mut running, handle) = RunningAnimation::new(Delay::new(10.0).boxed(), None);
+        assert!(handle.is_running());
+        assert!(!running.advance(5.0));
+        assert!(running.advance(5.0));
+        assert_eq!(AnimationStatus::Finished, handle.get_status());
+
+        // Cancelling or dropping a finished animation shouldn't change its status
+        handle.cancel();
+        drop(running);
+        assert_eq!(AnimationStatus::Finished, handle.get_status());
+
+        let (running, handle) = RunningAnimation::new(Delay::new(10.0).boxed(), None);
+        drop(running);
+        assert_eq!(AnimationStatus::Cancelled, handle.get_status());
+    }
+}
@@ -0,0 +1,110 @@
+use std::cell::Cell;
+
+/// A source of monotonic time, in milliseconds. The ContainerManager uses a PerformanceClock by default, but tests and headless
+/// runners can give it a TestClock to control time deterministically.
+pub trait Clock {
+
+    fn now(&self) -> f64;
+}
+
+/// The default Clock, which uses performance.now() of the browser
+pub struct PerformanceClock {}
+
+impl PerformanceClock {
+
+    pub fn new() -> PerformanceClock {
+        PerformanceClock {}
+    }
+}
+
+impl Clock for PerformanceClock {
+
+    fn now(&self) -> f64 {
+        web_sys::window().expect("Should have window").performance().expect("Window should have performance").now()
+    }
+}
+
+/// A Clock that only moves when it is told to
+pub struct TestClock {
+
+    time: Cell<f64>
+}
+
+impl TestClock {
+
+    pub fn new(start_time: f64) -> TestClock {
+        TestClock {
+            time: Cell::new(start_time)
+        }
+    }
+
+    /// Moves the time of this clock forward by the given number of milliseconds
+    pub fn advance(&self, milliseconds: f64) {
+        self.time.set(self.time.get() + milliseconds);
+    }
+
+    /// Sets the time of this clock. It should not be set to an earlier time, because clocks are supposed to be monotonic.
+    pub fn set(&self, time: f64) {
+        self.time.set(time);
+    }
+}
+
+impl Clock for TestClock {
+
+    fn now(&self) -> f64 {
+        self.time.get()
+    }
+}
+
+/// Information about the current update or render frame
+#[derive(Clone,Copy,Debug)]
+pub struct FrameInfo {
+
+    time: f64,
+    delta: f64,
+    frame_index: u64,
+    target_frame_rate: f64
+}
+
+impl FrameInfo {
+
+    /// Creates the FrameInfo of the first frame, which starts at the given time
+    pub fn first(time: f64, target_frame_rate: f64) -> FrameInfo {
+        FrameInfo {
+            time,
+            delta: 0.0,
+            frame_index: 0,
+            target_frame_rate
+        }
+    }
+
+    /// Computes the FrameInfo of the frame after this one, which starts at the given time
+    pub fn next(&self, time: f64, target_frame_rate: f64) -> FrameInfo {
+        FrameInfo {
+            time,
+            delta: time - self.time,
+            frame_index: self.frame_index + 1,
+            target_frame_rate
+        }
+    }
+
+    /// Gets the time (in milliseconds, according to the Clock of the manager) at which this frame started
+    pub fn get_time(&self) -> f64 {
+        self.time
+    }
+
+    /// Gets the time (in milliseconds) between the start of the previous frame and the start of this frame
+    pub fn get_delta(&self) -> f64 {
+        self.delta
+    }
+
+    /// Gets the number of this frame. The first frame has index 0.
+    pub fn get_frame_index(&self) -> u64 {
+        self.frame_index
+    }
+
+    /// Gets the frame rate (in frames per second) the manager is aiming for
+    pub fn get_target_frame_rate(&self) -> f64 {
+        self.target_frame_rate
+    }
+}
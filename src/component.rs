@@ -63,6 +63,9 @@ pub trait ComponentBehavior {
         false
     }
 
+    /// Called when the user completed the key chord of a shortcut that was claimed by this behavior
+    fn shortcut(&mut self, _params: &mut ShortcutParams) {}
+
     /// Called when the user clicked on this component
     fn mouse_click_inside(&mut self, _params: &mut MouseClickParams) {}
 
@@ -71,6 +74,15 @@ pub trait ComponentBehavior {
 
     fn mouse_click_anywhere(&mut self, _params: &mut MouseClickAnyParams) {}
 
+    /// Called when the user right-clicked on the mouse click space of this component. The returned menu will be opened at the
+    /// mouse position. Returning None lets the layers behind this layer open their menu instead.
+    fn context_menu(&mut self, _params: &mut ContextMenuParams) -> Option<ContextMenu> {
+        None
+    }
+
+    /// Called when the user activated an item of the context menu that was returned by the context_menu method
+    fn context_menu_action(&mut self, _params: &mut MenuActionParams) {}
+
     /// Called when a mouse click occurred, no matter where
     fn mouse_move(&mut self, _params: &mut MouseMoveParams){}
 
@@ -79,6 +91,16 @@ pub trait ComponentBehavior {
         false
     }
 
+    /// Returns true if the composition event should be consumed: then it will not be passed to other composition listeners.
+    /// Only the behavior that currently has the text input focus should consume composition events.
+    fn composition(&mut self, _params: &mut CompositionParams) -> bool {
+        false
+    }
+
+    /// Called during the capture phase of every event if this behavior is a capture listener. Use params.context.stop_propagation()
+    /// to prevent the event from reaching the other listeners.
+    fn capture(&mut self, _params: &mut CaptureParams) {}
+
     fn on_copy(&mut self) -> Option<ClipboardData> {
         None
     }
@@ -103,4 +125,13 @@ pub trait ComponentBehavior {
     }
 
     fn update(&mut self, _params: &mut UpdateParams){}
+
+    /// Describes this component to assistive technology like screen readers. Components that return None are invisible to them.
+    /// If a component has multiple behaviors, the node of the first behavior that returns one is used.
+    fn get_access_node(&self) -> Option<AccessNode> {
+        None
+    }
+
+    /// Called when assistive technology focused or activated the accessibility node of this component
+    fn access_action(&mut self, _params: &mut AccessActionParams) {}
 }
\ No newline at end of file
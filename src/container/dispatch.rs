@@ -0,0 +1,96 @@
+use crate::*;
+
+/// Determines how a LayeredContainer passes events to its layers
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum DispatchMode {
+
+    /// The events are passed from the front layer to the background layer: key and scroll events stop at the first layer that
+    /// consumes them, clicks are passed to on_mouse_click_outside of the layers after the first consumer and mouse moves pass None
+    /// to the layers after the first consumer. There is no capture phase. This is the default.
+    Legacy,
+
+    /// Every event goes through a capture phase and a bubble phase:
+    ///
+    /// First, the on_capture method of every layer is called, starting with the background layer and ending with the front layer.
+    /// Then, the on_bubble method of every layer is called, starting with the front layer and ending with the background layer.
+    /// Within a SimpleLayer, the capture listeners are called in order of descending priority during the capture phase and the
+    /// normal listeners are called in their usual order during the bubble phase.
+    ///
+    /// Calling stop_propagation() on the DispatchContext (or consuming the event during the bubble phase) prevents all remaining
+    /// layers from receiving the event. The only exceptions are mouse moves and clicks: the remaining layers will get a mouse
+    /// position of None or on_mouse_click_outside, so that they can update their hover state and lose their focus.
+    CaptureBubble
+}
+
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum DispatchPhase {
+
+    Capture,
+    Bubble
+}
+
+/// An event that is being dispatched by a LayeredContainer in DispatchMode::CaptureBubble
+#[derive(Clone,Copy)]
+pub enum DispatchEvent<'a> {
+
+    KeyDown(&'a KeyInfo),
+    KeyUp(&'a KeyInfo),
+
+    MouseClick(ClickInfo),
+
+    /// The new mouse position, in OpenGL coordinates
+    MouseMove(Option<(f32, f32)>),
+
+    /// The vertical scroll delta
+    MouseScroll(f64),
+
+    Composition(&'a CompositionInfo),
+    ContextMenu
+}
+
+/// Keeps track of the state of an event while it is being dispatched to the layers of a container
+pub struct DispatchContext {
+
+    phase: DispatchPhase,
+
+    propagation_stopped: bool,
+    default_prevented: bool
+}
+
+impl DispatchContext {
+
+    pub fn new() -> DispatchContext {
+        DispatchContext {
+            phase: DispatchPhase::Capture,
+            propagation_stopped: false,
+            default_prevented: false
+        }
+    }
+
+    pub fn get_phase(&self) -> DispatchPhase {
+        self.phase
+    }
+
+    pub(crate) fn set_phase(&mut self, phase: DispatchPhase) {
+        self.phase = phase;
+    }
+
+    /// Prevents the remaining layers (and the remaining listeners of the current layer) from receiving this event
+    pub fn stop_propagation(&mut self) {
+        self.propagation_stopped = true;
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation_stopped
+    }
+
+    /// Prevents the default action of the browser for the underlying DOM event, for instance scrolling the page when a key is pressed.
+    /// This doesn't affect the propagation of the event to the other layers.
+    pub fn prevent_default(&mut self) {
+        self.default_prevented = true;
+    }
+
+    pub fn is_default_prevented(&self) -> bool {
+        self.default_prevented
+    }
+}
@@ -23,6 +23,14 @@ impl FlatContainer {
     pub fn celled(layer: Box<dyn Layer>) -> Rc<RefCell<FlatContainer>> {
         Rc::new(RefCell::new(Self::new(layer)))
     }
+
+    pub fn get_layer(&self) -> &dyn Layer {
+        &*self.layer
+    }
+
+    pub fn get_layer_mut(&mut self) -> &mut dyn Layer {
+        &mut *self.layer
+    }
 }
 
 impl std::fmt::Debug for FlatContainer {
@@ -43,7 +51,7 @@ impl Container for FlatContainer {
     }
 
     fn on_mouse_click(&mut self, click: ClickInfo, manager: &ContainerManager) -> EventResult {
-        self.layer.on_mouse_click(click, manager)
+        self.layer.on_mouse_click(click, manager).as_normal_result()
     }
 
     fn on_mouse_move(&mut self, event: &MouseMoveEvent, manager: &ContainerManager) -> EventResult {
@@ -54,6 +62,14 @@ impl Container for FlatContainer {
         self.layer.on_mouse_scroll(event.mouse_event.delta_y(), manager).as_normal_result()
     }
 
+    fn on_composition(&mut self, composition: &CompositionInfo, manager: &ContainerManager) -> EventResult {
+        self.layer.on_composition(composition, manager).as_normal_result()
+    }
+
+    fn on_context_menu(&mut self, manager: &ContainerManager) -> EventResult {
+        self.layer.on_context_menu(manager).as_normal_result()
+    }
+
     fn on_copy(&mut self) -> Option<ClipboardData> {
         self.layer.on_copy()
     }
@@ -71,7 +87,10 @@ impl Container for FlatContainer {
     }
 
     fn render(&mut self, gl: &WebGlRenderingContext, manager: &ContainerManager) -> ContainerRenderResult {
-        let maybe_cursor = self.layer.on_render(gl, manager).get_cursor();
+        let layer = &mut self.layer;
+        let maybe_cursor = manager.profile("Layer::on_render", ProfileCategory::LayerRender, ProfileSubject::Layer(0), || {
+            layer.on_render(gl, manager)
+        }).get_cursor();
         match maybe_cursor {
             Some(cursor) => cursor,
             None => Cursor::DEFAULT
@@ -81,4 +100,24 @@ impl Container for FlatContainer {
     fn force_render(&mut self){
         self.layer.force_render();
     }
+
+    fn snapshot(&mut self) -> ContainerSnapshot {
+        ContainerSnapshot::new("FlatContainer", vec![self.layer.snapshot()])
+    }
+
+    fn collect_access_nodes(&mut self, nodes: &mut Vec<(ComponentKey, AccessNode)>) {
+        self.layer.collect_access_nodes(nodes);
+    }
+
+    fn on_access_action(&mut self, key: ComponentKey, action: AccessAction, manager: &ContainerManager) -> EventResult {
+        self.layer.on_access_action(key, action, manager).as_normal_result()
+    }
+
+    fn wants_update(&mut self) -> bool {
+        self.layer.wants_update()
+    }
+
+    fn wants_render(&mut self) -> bool {
+        self.layer.wants_render()
+    }
 }
\ No newline at end of file
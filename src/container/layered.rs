@@ -3,6 +3,7 @@ use crate::*;
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use wasmuri_core::Region;
 use wasmuri_events::*;
 
 use web_sys::WebGlRenderingContext;
@@ -14,20 +15,117 @@ pub struct LayeredContainer {
     /// 
     /// The background layer will render first and the front layer will render last (so the front layer will draw over the background layer).
     /// The other events (like clicking and pressing keys), will be processed first by the front layer and last by the background layer.
-    layers: Vec<Box<dyn Layer>>
+    /// See DispatchMode for the exact order.
+    layers: Vec<Box<dyn Layer>>,
+
+    dispatch_mode: DispatchMode
 }
 
 impl LayeredContainer {
 
     pub fn new(layers: Vec<Box<dyn Layer>>) -> LayeredContainer {
         LayeredContainer {
-            layers
+            layers,
+            dispatch_mode: DispatchMode::Legacy
         }
     }
 
     pub fn celled(layers: Vec<Box<dyn Layer>>) -> Rc<RefCell<LayeredContainer>> {
         Rc::new(RefCell::new(Self::new(layers)))
     }
+
+    pub fn get_layer(&self, index: usize) -> &dyn Layer {
+        &*self.layers[index]
+    }
+
+    pub fn get_layer_mut(&mut self, index: usize) -> &mut dyn Layer {
+        &mut *self.layers[index]
+    }
+
+    pub fn get_num_layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn get_dispatch_mode(&self) -> DispatchMode {
+        self.dispatch_mode
+    }
+
+    /// Changes the way events are passed to the layers. See the documentation of DispatchMode for the possible orders.
+    pub fn set_dispatch_mode(&mut self, mode: DispatchMode) {
+        self.dispatch_mode = mode;
+    }
+
+    /// Passes the event through the capture phase (back to front) and the bubble phase (front to back) of the layers
+    fn dispatch(&mut self, event: &DispatchEvent, manager: &ContainerManager) -> EventResult {
+        let mut context = DispatchContext::new();
+        let mut next_container = None;
+
+        for layer in &mut self.layers {
+            next_container = layer.on_capture(event, &mut context, manager);
+            if next_container.is_some() || context.is_propagation_stopped() {
+                break;
+            }
+        }
+
+        if next_container.is_none() {
+            context.set_phase(DispatchPhase::Bubble);
+            for layer in &mut self.layers.iter_mut().rev() {
+                next_container = if context.is_propagation_stopped() {
+                    match event {
+
+                        // The layers behind the consumer should still be able to update their hover state
+                        DispatchEvent::MouseMove(_) => layer.on_mouse_move(None, manager).as_normal_result(),
+
+                        // The layers behind the consumer should still know that the click happened outside their components
+                        DispatchEvent::MouseClick(click) => layer.on_mouse_click_outside(*click, manager),
+                        _ => break
+                    }
+                } else {
+                    layer.on_bubble(event, &mut context, manager)
+                };
+
+                if next_container.is_some() {
+                    break;
+                }
+            }
+        }
+
+        if context.is_default_prevented() {
+            manager.prevent_default();
+        }
+
+        next_container
+    }
+
+    /// Collects the shortcut bindings of all layers, starting with the front layer. This is handy for help screens.
+    pub fn get_shortcut_bindings(&self) -> Vec<ShortcutBinding> {
+        let mut bindings = Vec::new();
+        for layer in self.layers.iter().rev() {
+            if let Some(registry) = layer.get_shortcuts() {
+                bindings.extend_from_slice(registry.get_bindings());
+            }
+        }
+        bindings
+    }
+
+    /// Gives the inspections of the layers to the InspectorState of the manager, unless none of the layers planned to render
+    /// anything. Frames in which only the InspectorLayer itself rendered would otherwise replace the interesting frames.
+    fn record_inspection(&mut self, planned_regions: Vec<Vec<Region>>, manager: &ContainerManager) {
+        let mut activity = false;
+        let mut inspections = Vec::with_capacity(self.layers.len());
+        for (layer, planned_regions) in self.layers.iter_mut().zip(planned_regions) {
+            if let Some(mut inspection) = layer.inspect() {
+                activity |= !planned_regions.is_empty();
+                inspection.set_planned_regions(planned_regions);
+                inspections.push(inspection);
+            }
+        }
+
+        let mut inspector = manager.get_inspector().borrow_mut();
+        if activity || inspector.get_layers().is_empty() {
+            inspector.set_layers(inspections);
+        }
+    }
 }
 
 impl std::fmt::Debug for LayeredContainer {
@@ -40,6 +138,10 @@ impl std::fmt::Debug for LayeredContainer {
 impl Container for LayeredContainer {
 
     fn on_key_down(&mut self, keys: &KeyInfo, manager: &ContainerManager) -> EventResult {
+        if self.dispatch_mode == DispatchMode::CaptureBubble {
+            return self.dispatch(&DispatchEvent::KeyDown(keys), manager);
+        }
+
         for layer in &mut self.layers.iter_mut().rev() {
             let key_down_result = layer.on_key_down(keys, manager);
             
@@ -57,6 +159,10 @@ impl Container for LayeredContainer {
     }
 
     fn on_key_up(&mut self, keys: &KeyInfo, manager: &ContainerManager) -> EventResult {
+        if self.dispatch_mode == DispatchMode::CaptureBubble {
+            return self.dispatch(&DispatchEvent::KeyUp(keys), manager);
+        }
+
         for layer in &mut self.layers.iter_mut().rev() {
             let key_up_result = layer.on_key_up(keys, manager);
 
@@ -74,22 +180,39 @@ impl Container for LayeredContainer {
     }
 
     fn on_mouse_click(&mut self, click: ClickInfo, manager: &ContainerManager) -> EventResult {
+        if self.dispatch_mode == DispatchMode::CaptureBubble {
+            return self.dispatch(&DispatchEvent::MouseClick(click), manager);
+        }
 
-        let mut next_container = None;
+        let mut consumed = false;
+        for layer in &mut self.layers.iter_mut().rev() {
 
-        for layer in &mut self.layers {
+            // The layers behind the consumer should still know that the click happened outside their components
+            if consumed {
+                let requested_container = layer.on_mouse_click_outside(click, manager);
+                if requested_container.is_some() {
+                    return requested_container;
+                }
+                continue;
+            }
 
-            // If multiple layers request a container change, layers in the front will get priority
             let click_result = layer.on_mouse_click(click, manager);
-            if click_result.is_some() {
-                next_container = click_result;
+
+            // If a container change was requested, it is guaranteed that the event was consumed
+            if click_result.requested_container_change() {
+                return Some(click_result.get_next_container());
             }
+
+            consumed = click_result.is_consumed();
         }
 
-        next_container
+        None
     }
 
     fn on_mouse_move(&mut self, event: &MouseMoveEvent, manager: &ContainerManager) -> EventResult {
+        if self.dispatch_mode == DispatchMode::CaptureBubble {
+            return self.dispatch(&DispatchEvent::MouseMove(Some(manager.to_gl_coords(event.get_new_position()))), manager);
+        }
 
         // TODO This sometimes doesn't work properly, requires investigation...
         let mut next_container = None;
@@ -112,6 +235,10 @@ impl Container for LayeredContainer {
     }
 
     fn on_mouse_scroll(&mut self, event: &MouseScrollEvent, manager: &ContainerManager) -> EventResult {
+        if self.dispatch_mode == DispatchMode::CaptureBubble {
+            return self.dispatch(&DispatchEvent::MouseScroll(event.mouse_event.delta_y()), manager);
+        }
+
         for layer in &mut self.layers.iter_mut().rev() {
             let event_result = layer.on_mouse_scroll(event.mouse_event.delta_y(), manager);
 
@@ -127,6 +254,46 @@ impl Container for LayeredContainer {
         None
     }
 
+    fn on_composition(&mut self, composition: &CompositionInfo, manager: &ContainerManager) -> EventResult {
+        if self.dispatch_mode == DispatchMode::CaptureBubble {
+            return self.dispatch(&DispatchEvent::Composition(composition), manager);
+        }
+
+        for layer in &mut self.layers.iter_mut().rev() {
+            let event_result = layer.on_composition(composition, manager);
+
+            if event_result.requested_container_change() {
+                return Some(event_result.get_next_container());
+            }
+
+            if event_result.is_consumed() {
+                return None;
+            }
+        }
+
+        None
+    }
+
+    fn on_context_menu(&mut self, manager: &ContainerManager) -> EventResult {
+        if self.dispatch_mode == DispatchMode::CaptureBubble {
+            return self.dispatch(&DispatchEvent::ContextMenu, manager);
+        }
+
+        for layer in &mut self.layers.iter_mut().rev() {
+            let event_result = layer.on_context_menu(manager);
+
+            if event_result.requested_container_change() {
+                return Some(event_result.get_next_container());
+            }
+
+            if event_result.is_consumed() {
+                return None;
+            }
+        }
+
+        None
+    }
+
     fn on_copy(&mut self) -> Option<ClipboardData> {
         for layer in &mut self.layers.iter_mut().rev() {
             let maybe_data = layer.on_copy();
@@ -182,6 +349,14 @@ impl Container for LayeredContainer {
             rerender_actions.push(layer.predict_render());
         }
 
+        // The inspector shows what the layers planned themselves, not what the other layers forced them to render
+        let inspecting = manager.get_inspector().borrow().is_enabled();
+        let planned_regions: Vec<Vec<Region>> = if inspecting {
+            rerender_actions.iter().map(|actions| actions.iter().map(|action| action.get_region()).collect()).collect()
+        } else {
+            Vec::new()
+        };
+
         // These are needed to keep track of the progress of propagating renders between layers
         let mut rerender_indices = vec![0; self.layers.len()];
 
@@ -192,34 +367,40 @@ impl Container for LayeredContainer {
         2. If the render action in the region is partially transparent, that region must also be re-rendered in all layers behind that layer.
 
         The interesting part is that, in order to re-render a certain region in a certain layer, all components intersecting with that region
-        need to be re-rendered, but it is very well possible that some components can lay partially outside that region. When that is the case,
-        step 1 and 2 also need to be executed for the region of that component as well.
+        need to be re-rendered. Components that lay partially outside that region are clipped to it, so they only report the parts that they
+        will actually redraw, and step 1 and 2 need to be executed for those parts as well.
         */
         let mut current_layer_index = 0;
         while current_layer_index < self.layers.len() {
+            manager.get_profiler().borrow_mut().begin_span("LayeredContainer::propagate", ProfileCategory::Propagation,
+                    ProfileSubject::Layer(current_layer_index));
             let all_actions_of_layer = &rerender_actions[current_layer_index];
             let current_action_index = rerender_indices[current_layer_index];
 
             let num_actions_of_layer = all_actions_of_layer.len();
 
+            // The layer to continue with after this one: the next layer, unless a layer behind this one gets new render actions
+            let mut next_layer_index = current_layer_index + 1;
+
             // Only do stuff if there are actually unprocessed Region's
             if current_action_index != num_actions_of_layer {
                 let actions_to_process = &all_actions_of_layer[current_action_index..all_actions_of_layer.len()];
 
                 // Obtain the regions of the actions to process
                 let mut regions_to_process_front = Vec::with_capacity(actions_to_process.len());
-                let mut regions_to_process_back = Vec::with_capacity(actions_to_process.len());
+                let mut regions_to_process_back = RegionSet::new();
                 for action in actions_to_process {
                     regions_to_process_front.push(action.get_region());
 
-                    let opacity = action.get_opacity();
-
                     // If the opacity of the render action is fully solid or static solid, no need to re-render the stuff behind it
-                    if opacity != RenderOpacity::Solid && opacity != RenderOpacity::StaticSolidOrNothing {
-                        regions_to_process_back.push(action.get_region());
+                    if !is_solid(action.get_opacity()) {
+                        regions_to_process_back.add(action.get_region());
                     }
                 }
 
+                // Mark the regions we are about to process as 'completed'
+                rerender_indices[current_layer_index] = num_actions_of_layer;
+
                 // Force the layers in front of the current layer to re-render those regions as well
                 for front_layer_index in current_layer_index + 1 .. self.layers.len() {
                     let front_layer = &mut self.layers[front_layer_index];
@@ -230,43 +411,54 @@ impl Container for LayeredContainer {
                 }
 
                 // Force the layers behind the current layer to re-render the regions behind the transparent render actions
-                if current_layer_index > 0 {
-                    for back_layer_index in (0 .. current_layer_index).rev() {
-                        
-                        let back_layer = &mut self.layers[back_layer_index];
-                        let mut new_actions_to_process = back_layer.force_partial_render(&regions_to_process_back);
-                        let num_new_actions = new_actions_to_process.len();
-
-                        // Append all new render actions for the back layer
-                        rerender_actions[back_layer_index].append(&mut new_actions_to_process);
-
-                        // TODO For performance, remove the regions in regions_to_process_back that are fully covered by a solid render action
-                        // in new_actions_to_process
-
-                        // The new render actions of this back layer will also have to be processed...
-                        if num_new_actions > 0 {
-                            current_layer_index = back_layer_index;
+                for back_layer_index in (0 .. current_layer_index).rev() {
+
+                    // Once the remaining regions are fully covered by solid render actions, the layers further back are invisible
+                    if regions_to_process_back.is_empty() {
+                        break;
+                    }
+
+                    let back_layer = &mut self.layers[back_layer_index];
+                    let mut new_actions_to_process = back_layer.force_partial_render(regions_to_process_back.get_regions());
+                    let num_new_actions = new_actions_to_process.len();
+
+                    // Components with holes in them can't hide the layers behind them, even if the holes are static
+                    for new_action in &new_actions_to_process {
+                        if new_action.get_opacity() == RenderOpacity::Solid {
+                            regions_to_process_back.subtract(new_action.get_region());
                         }
                     }
-                }
 
-                // Mark the regions we just processed as 'completed'
-                rerender_indices[current_layer_index] = num_actions_of_layer;
+                    // Append all new render actions for the back layer
+                    rerender_actions[back_layer_index].append(&mut new_actions_to_process);
+
+                    // The new render actions of this back layer will also have to be processed...
+                    if num_new_actions > 0 {
+                        next_layer_index = back_layer_index;
+                    }
+                }
             }
 
-            current_layer_index += 1;
+            manager.get_profiler().borrow_mut().end_span();
+            current_layer_index = next_layer_index;
         }
 
         // Now that all layers know exactly which components to render, the real render can finally begin
         let mut maybe_cursor = None;
 
-        for layer in &mut self.layers {
-            let requested_cursor = layer.on_render(gl, manager).get_cursor();
+        for (index, layer) in self.layers.iter_mut().enumerate() {
+            let requested_cursor = manager.profile("Layer::on_render", ProfileCategory::LayerRender, ProfileSubject::Layer(index), || {
+                layer.on_render(gl, manager)
+            }).get_cursor();
 
             if maybe_cursor.is_none() && requested_cursor.is_some() {
                 maybe_cursor = requested_cursor;
             }
         }
+
+        if inspecting {
+            self.record_inspection(planned_regions, manager);
+        }
         
         match maybe_cursor {
             Some(cursor) => cursor,
@@ -279,4 +471,43 @@ impl Container for LayeredContainer {
             layer.force_render();
         }
     }
-}
\ No newline at end of file
+
+    fn snapshot(&mut self) -> ContainerSnapshot {
+        ContainerSnapshot::new("LayeredContainer", self.layers.iter_mut().map(|layer| layer.snapshot()).collect())
+    }
+
+    fn collect_access_nodes(&mut self, nodes: &mut Vec<(ComponentKey, AccessNode)>) {
+        for layer in &mut self.layers {
+            layer.collect_access_nodes(nodes);
+        }
+    }
+
+    fn on_access_action(&mut self, key: ComponentKey, action: AccessAction, manager: &ContainerManager) -> EventResult {
+        for layer in &mut self.layers.iter_mut().rev() {
+            let event_result = layer.on_access_action(key, action, manager);
+
+            if event_result.requested_container_change() {
+                return Some(event_result.get_next_container());
+            }
+
+            if event_result.is_consumed() {
+                return None;
+            }
+        }
+
+        None
+    }
+
+    fn wants_update(&mut self) -> bool {
+        self.layers.iter_mut().any(|layer| layer.wants_update())
+    }
+
+    fn wants_render(&mut self) -> bool {
+        self.layers.iter_mut().any(|layer| layer.wants_render())
+    }
+}
+
+/// Checks if render actions with the given opacity hide everything behind them
+fn is_solid(opacity: RenderOpacity) -> bool {
+    opacity == RenderOpacity::Solid || opacity == RenderOpacity::StaticSolidOrNothing
+}
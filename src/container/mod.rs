@@ -4,9 +4,11 @@ use wasmuri_events::*;
 
 use web_sys::WebGlRenderingContext;
 
+mod dispatch;
 mod flat;
 mod layered;
 
+pub use dispatch::*;
 pub use flat::*;
 pub use layered::*;
 
@@ -22,6 +24,11 @@ pub trait Container : std::fmt::Debug {
 
     fn on_mouse_scroll(&mut self, event: &MouseScrollEvent, manager: &ContainerManager) -> EventResult;
 
+    fn on_composition(&mut self, composition: &CompositionInfo, manager: &ContainerManager) -> EventResult;
+
+    /// Called when the user requests a context menu (usually by right-clicking) at the last known mouse position
+    fn on_context_menu(&mut self, manager: &ContainerManager) -> EventResult;
+
     fn on_copy(&mut self) -> Option<ClipboardData>;
 
     fn on_paste(&mut self, clipboard: &ClipboardData) -> bool;
@@ -34,4 +41,28 @@ pub trait Container : std::fmt::Debug {
 
     /// When this method has been called, the Container should re-render everything the next time render is called.
     fn force_render(&mut self);
+
+    /// Creates a read-only description of this container and its layers, for debugging and tests
+    fn snapshot(&mut self) -> ContainerSnapshot {
+        ContainerSnapshot::new("Container", Vec::new())
+    }
+
+    /// Adds the accessibility nodes of all components in this container to nodes, from the back layer to the front layer
+    fn collect_access_nodes(&mut self, _nodes: &mut Vec<(ComponentKey, AccessNode)>) {}
+
+    /// Passes the action of assistive technology to the component with the given key
+    fn on_access_action(&mut self, _key: ComponentKey, _action: AccessAction, _manager: &ContainerManager) -> EventResult {
+        None
+    }
+
+    /// Checks if this container needs to be updated even though no input arrived since the last update, for instance because
+    /// an animation is running. This is only used when the manager uses LoopMode::OnDemand.
+    fn wants_update(&mut self) -> bool {
+        true
+    }
+
+    /// Checks if something in this container needs to be rendered. This is only used when the manager uses LoopMode::OnDemand.
+    fn wants_render(&mut self) -> bool {
+        true
+    }
 }
\ No newline at end of file
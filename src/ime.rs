@@ -0,0 +1,192 @@
+use crate::*;
+
+use std::cell::{
+    Cell,
+    RefCell
+};
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+
+use wasmuri_core::Region;
+
+use web_sys::{
+    CompositionEvent,
+    HtmlCanvasElement,
+    HtmlInputElement
+};
+
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum CompositionPhase {
+
+    /// The user started composing text with an input method editor
+    Start,
+
+    /// The composed text changed, but the composition is not finished yet
+    Update,
+
+    /// The composition is finished and the data is the text that should be inserted
+    End
+}
+
+#[derive(Clone,PartialEq,Eq,Debug)]
+pub struct CompositionInfo {
+
+    phase: CompositionPhase,
+    data: String
+}
+
+impl CompositionInfo {
+
+    pub fn new(phase: CompositionPhase, data: String) -> CompositionInfo {
+        CompositionInfo {
+            phase,
+            data
+        }
+    }
+
+    pub fn get_phase(&self) -> CompositionPhase {
+        self.phase
+    }
+
+    /// Gets the text that is currently being composed. When the phase is End, this is the text that should be inserted.
+    pub fn get_data(&self) -> &str {
+        &self.data
+    }
+}
+
+/// The ImeBridge owns a hidden input element that receives the composition events of the input method editor of the browser.
+/// Since everything is drawn into a canvas, the browser doesn't know where text is being typed. Text input behaviors should call
+/// focus() with the region of their caret so that the browser shows the candidate window near the caret.
+pub struct ImeBridge {
+
+    canvas: HtmlCanvasElement,
+    input: HtmlInputElement,
+
+    /// The component that has the focus of the hidden input element, if any
+    owner: Cell<Option<ComponentKey>>,
+
+    pending_events: Rc<RefCell<Vec<CompositionInfo>>>
+}
+
+impl ImeBridge {
+
+    pub fn new(canvas: &HtmlCanvasElement) -> ImeBridge {
+        let document = web_sys::window().expect("Should have window").document().expect("Window should have document");
+        let input = document.create_element("input").expect("Should be able to create input element")
+                .dyn_into::<HtmlInputElement>().expect("An input element should be an HtmlInputElement");
+
+        let css = input.style();
+        css.set_property("position", "fixed").expect("Should be able to set position property");
+        css.set_property("opacity", "0").expect("Should be able to set opacity property");
+        css.set_property("pointer-events", "none").expect("Should be able to set pointer-events property");
+        css.set_property("left", "0px").expect("Should be able to set left property");
+        css.set_property("top", "0px").expect("Should be able to set top property");
+        css.set_property("width", "1px").expect("Should be able to set width property");
+        input.set_attribute("aria-hidden", "true").expect("Should be able to set aria-hidden attribute");
+
+        document.body().expect("Document should have body").append_child(&input).expect("Should be able to append input element");
+
+        ImeBridge {
+            canvas: canvas.clone(),
+            input,
+
+            owner: Cell::new(None),
+
+            pending_events: Rc::new(RefCell::new(Vec::new()))
+        }
+    }
+
+    /// Starts listening to the composition events of the hidden input element. The events will be passed to the manager as soon as
+    /// it is not busy, so composition events that are fired while the manager is processing another event won't get lost.
+    pub(crate) fn start_listening(&self, manager_cell: &Rc<RefCell<ContainerManager>>) {
+        self.listen(manager_cell, "compositionstart", CompositionPhase::Start);
+        self.listen(manager_cell, "compositionupdate", CompositionPhase::Update);
+        self.listen(manager_cell, "compositionend", CompositionPhase::End);
+    }
+
+    fn listen(&self, manager_cell: &Rc<RefCell<ContainerManager>>, event_name: &str, phase: CompositionPhase) {
+        let weak_manager = Rc::downgrade(manager_cell);
+        let pending_events = Rc::clone(&self.pending_events);
+        let input = self.input.clone();
+
+        let closure = Closure::wrap(Box::new(move |event: CompositionEvent| {
+            pending_events.borrow_mut().push(CompositionInfo::new(phase, event.data().unwrap_or_default()));
+
+            // The composed text has been passed to the behaviors, so it shouldn't stay in the hidden input
+            if phase == CompositionPhase::End {
+                input.set_value("");
+            }
+
+            if let Some(manager_cell) = weak_manager.upgrade() {
+                if let Ok(mut manager) = manager_cell.try_borrow_mut() {
+                    manager.process_pending_compositions();
+                }
+            }
+        }) as Box<dyn FnMut(CompositionEvent)>);
+
+        self.input.add_event_listener_with_callback(event_name, closure.as_ref().unchecked_ref())
+                .expect("Should be able to add composition listener");
+
+        // The closure needs to live as long as the input element
+        closure.forget();
+    }
+
+    pub(crate) fn take_pending_events(&self) -> Vec<CompositionInfo> {
+        std::mem::replace(&mut *self.pending_events.borrow_mut(), Vec::new())
+    }
+
+    /// Focuses the hidden input element on behalf of the given component and moves it to the given caret region (in OpenGL
+    /// coordinates), so that the candidate window of the input method editor will appear near the caret. If another component had
+    /// the focus, the given component takes it over.
+    pub fn focus(&self, owner: ComponentKey, caret: Region) {
+        self.set_caret(caret);
+        if self.owner.get().is_none() {
+            self.input.focus().expect("Should be able to focus hidden input element");
+        }
+        self.owner.set(Some(owner));
+    }
+
+    /// Moves the hidden input element to the given caret region without changing the focus
+    pub fn set_caret(&self, caret: Region) {
+        let bounds = self.canvas.get_bounding_client_rect();
+        let width = bounds.width() as f32;
+        let height = bounds.height() as f32;
+
+        let left = bounds.left() as f32 + (caret.get_min_x() + 1.0) * 0.5 * width;
+        let top = bounds.top() as f32 + (1.0 - caret.get_max_y()) * 0.5 * height;
+        let caret_height = (caret.get_max_y() - caret.get_min_y()) * 0.5 * height;
+
+        let css = self.input.style();
+        css.set_property("left", &format!("{}px", left)).expect("Should be able to set left property");
+        css.set_property("top", &format!("{}px", top)).expect("Should be able to set top property");
+        css.set_property("height", &format!("{}px", caret_height)).expect("Should be able to set height property");
+        css.set_property("font-size", &format!("{}px", caret_height)).expect("Should be able to set font-size property");
+    }
+
+    /// Removes the focus from the hidden input element if the given component has it. Text input behaviors should call this when
+    /// they lose focus. Since the order in which behaviors receive a click is not defined, the focus is left alone if another
+    /// component already took it over.
+    pub fn blur(&self, owner: ComponentKey) {
+        if self.owner.get() == Some(owner) {
+            self.input.blur().expect("Should be able to blur hidden input element");
+            self.input.set_value("");
+            self.owner.set(None);
+        }
+    }
+
+    /// Gets the hidden input element that receives the composition events
+    pub(crate) fn get_input(&self) -> &HtmlInputElement {
+        &self.input
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.owner.get().is_some()
+    }
+
+    /// Gets the key of the component that has the focus of the hidden input element
+    pub fn get_owner(&self) -> Option<ComponentKey> {
+        self.owner.get()
+    }
+}
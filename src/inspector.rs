@@ -0,0 +1,135 @@
+use crate::*;
+
+use wasmuri_core::Region;
+
+/// The space a component claimed in one of the managers of a layer
+#[derive(Clone,Copy,PartialEq)]
+pub struct ClaimOutline {
+
+    kind: ClaimKind,
+    region: Region,
+
+    /// The component that made the claim, if it is still alive
+    component: Option<ComponentKey>
+}
+
+impl ClaimOutline {
+
+    pub fn new(kind: ClaimKind, region: Region, component: Option<ComponentKey>) -> ClaimOutline {
+        ClaimOutline {
+            kind,
+            region,
+            component
+        }
+    }
+
+    pub fn get_kind(&self) -> ClaimKind {
+        self.kind
+    }
+
+    pub fn get_region(&self) -> Region {
+        self.region
+    }
+
+    pub fn get_component(&self) -> Option<ComponentKey> {
+        self.component
+    }
+}
+
+/// What the inspector knows about a single layer: the claims of its components and what it rendered during the last frame in which
+/// something changed
+#[derive(Clone,PartialEq)]
+pub struct LayerInspection {
+
+    claims: Vec<ClaimOutline>,
+
+    /// The regions the layer predicted to render before the render actions were propagated between the layers
+    planned_regions: Vec<Region>,
+
+    /// The regions the components of the layer reported to have drawn
+    passed_regions: Vec<Region>
+}
+
+impl LayerInspection {
+
+    pub fn new(claims: Vec<ClaimOutline>, passed_regions: Vec<Region>) -> LayerInspection {
+        LayerInspection {
+            claims,
+            planned_regions: Vec::new(),
+            passed_regions
+        }
+    }
+
+    pub fn get_claims(&self) -> &[ClaimOutline] {
+        &self.claims
+    }
+
+    pub fn get_planned_regions(&self) -> &[Region] {
+        &self.planned_regions
+    }
+
+    pub fn set_planned_regions(&mut self, planned_regions: Vec<Region>) {
+        self.planned_regions = planned_regions;
+    }
+
+    pub fn get_passed_regions(&self) -> &[Region] {
+        &self.passed_regions
+    }
+}
+
+/// The state of the debug inspector of a ContainerManager. When it is enabled, the LayeredContainer records a LayerInspection of
+/// each of its layers after every render in which a layer planned to render something, and the InspectorLayer draws them.
+pub struct InspectorState {
+
+    enabled: bool,
+    hotkey: KeyStroke,
+
+    layers: Vec<LayerInspection>
+}
+
+impl InspectorState {
+
+    /// Creates a disabled InspectorState that can be toggled with F12
+    pub fn new() -> InspectorState {
+        InspectorState {
+            enabled: false,
+            hotkey: KeyStroke::new("F12", false, false, false, false),
+            layers: Vec::new()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or disables the inspector. The recorded inspections are discarded when it is disabled.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.layers.clear();
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.set_enabled(!self.enabled);
+    }
+
+    /// Gets the key stroke the InspectorLayer uses to toggle the inspector
+    pub fn get_hotkey(&self) -> &KeyStroke {
+        &self.hotkey
+    }
+
+    pub fn set_hotkey(&mut self, hotkey: KeyStroke) {
+        self.hotkey = hotkey;
+    }
+
+    /// Gets the inspections of the layers, from back to front. Layers that can't be inspected (like the InspectorLayer itself) are
+    /// left out.
+    pub fn get_layers(&self) -> &[LayerInspection] {
+        &self.layers
+    }
+
+    pub(crate) fn set_layers(&mut self, layers: Vec<LayerInspection>) {
+        self.layers = layers;
+    }
+}
@@ -1,12 +1,51 @@
 use crate::*;
 
 use std::cell::RefCell;
+use std::fmt::{
+    Display,
+    Formatter
+};
 use std::rc::Rc;
+use std::sync::atomic::{
+    AtomicU64,
+    Ordering
+};
 
 use wasmuri_core::Region;
 
+static NEXT_COMPONENT_KEY: AtomicU64 = AtomicU64::new(1);
+
+/// A key that uniquely identifies a component, for instance in error messages. Every ComponentAgent gets a new key.
+#[derive(Clone,Copy,PartialEq,Eq,PartialOrd,Ord,Hash,Debug)]
+pub struct ComponentKey {
+
+    value: u64
+}
+
+impl ComponentKey {
+
+    fn next() -> ComponentKey {
+        ComponentKey {
+            value: NEXT_COMPONENT_KEY.fetch_add(1, Ordering::Relaxed)
+        }
+    }
+
+    pub fn get_value(&self) -> u64 {
+        self.value
+    }
+}
+
+impl Display for ComponentKey {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "component #{}", self.value)
+    }
+}
+
 pub struct ComponentAgent {
 
+    key: ComponentKey,
+
     requested_render: bool,
     requested_removal: bool,
 
@@ -14,6 +53,9 @@ pub struct ComponentAgent {
 
     new_container: Option<Rc<RefCell<dyn Container>>>,
 
+    animations: Vec<RunningAnimation>,
+    timers: Vec<ScheduledTimer>,
+
     has_changes: bool
 }
 
@@ -22,6 +64,8 @@ impl ComponentAgent {
     pub fn new() -> ComponentAgent {
         ComponentAgent {
 
+            key: ComponentKey::next(),
+
             // Every (renderable) component should be rendered its first frame
             // The render manager will make sure only the components that can actually render will be rendered
             requested_render: true,
@@ -31,10 +75,18 @@ impl ComponentAgent {
 
             new_container: None,
 
+            animations: Vec::new(),
+            timers: Vec::new(),
+
             has_changes: false
         }
     }
 
+    /// Gets the key of the component of this agent
+    pub fn get_key(&self) -> ComponentKey {
+        self.key
+    }
+
     /// Requests to re-render this component the next frame
     pub fn request_render(&mut self){
         self.requested_render = true;
@@ -62,6 +114,90 @@ impl ComponentAgent {
         self.has_changes = true;
     }
 
+    /// Starts the given animation. The component will re-render every update until the animation is finished or cancelled, and
+    /// the animation is stopped when the component is removed.
+    pub fn animate(&mut self, animation: Box<dyn Animation>) -> AnimationHandle {
+        let (running, handle) = RunningAnimation::new(animation, None);
+        self.animations.push(running);
+        handle
+    }
+
+    /// Starts the given animation like animate() does, and calls on_complete once the animation is finished. The callback is not
+    /// called if the animation is cancelled.
+    pub fn animate_then<F: FnOnce() + 'static>(&mut self, animation: Box<dyn Animation>, on_complete: F) -> AnimationHandle {
+        let (running, handle) = RunningAnimation::new(animation, Some(Box::new(on_complete)));
+        self.animations.push(running);
+        handle
+    }
+
+    /// Checks if this component has animations that are still running
+    pub fn has_animations(&self) -> bool {
+        !self.animations.is_empty()
+    }
+
+    /// Advances the animations of this component by the given time (in milliseconds) and requests a render if any of them was
+    /// running. Returns the completion callbacks of the animations that finished, which should be called once this agent is no
+    /// longer borrowed.
+    pub(super) fn advance_animations(&mut self, delta: f64) -> Vec<Box<dyn FnOnce()>> {
+        let mut callbacks = Vec::new();
+        self.animations.retain(|animation| !animation.is_cancelled());
+        if self.animations.is_empty() {
+            return callbacks;
+        }
+
+        self.requested_render = true;
+        self.animations.drain_filter(|animation| animation.advance(delta)).for_each(|mut finished| {
+            if let Some(callback) = finished.take_on_complete() {
+                callbacks.push(callback);
+            }
+        });
+        callbacks
+    }
+
+    /// Stops all animations of this component, which is done when the component is removed from its layer. The component itself
+    /// may outlive its layer, but its animations will never be advanced again.
+    pub(super) fn cancel_animations(&mut self) {
+        for animation in self.animations.drain(..) {
+            animation.cancel();
+        }
+    }
+
+    /// Calls the given callback once, after the given delay (in milliseconds). The timer is cancelled when the component is removed.
+    pub fn schedule_once<F: FnMut() + 'static>(&mut self, delay: f64, callback: F) -> TimerHandle {
+        let (timer, handle) = ScheduledTimer::new(delay, None, Rc::new(RefCell::new(callback)));
+        self.timers.push(timer);
+        handle
+    }
+
+    /// Calls the given callback every interval (in milliseconds), starting after the first interval. The timer keeps repeating
+    /// until it is cancelled or the component is removed.
+    pub fn schedule_repeating<F: FnMut() + 'static>(&mut self, interval: f64, callback: F) -> TimerHandle {
+        let (timer, handle) = ScheduledTimer::new(interval, Some(interval), Rc::new(RefCell::new(callback)));
+        self.timers.push(timer);
+        handle
+    }
+
+    /// Cancels all timers of this component, which is done when the component is removed from its layer
+    pub(super) fn cancel_timers(&mut self) {
+        for timer in self.timers.drain(..) {
+            timer.cancel();
+        }
+    }
+
+    /// Checks if this component has timers that haven't been cancelled or finished
+    pub fn has_timers(&self) -> bool {
+        self.timers.iter().any(|timer| !timer.is_cancelled())
+    }
+
+    /// Advances the timers of this component by the given time (in milliseconds) and returns the callbacks of the timers that
+    /// fired, which should be called once this agent is no longer borrowed
+    pub(super) fn advance_timers(&mut self, delta: f64) -> Vec<Rc<RefCell<dyn FnMut()>>> {
+        self.timers.retain(|timer| !timer.is_cancelled());
+        let callbacks = self.timers.iter_mut().filter_map(|timer| timer.advance(delta)).collect();
+        self.timers.retain(|timer| !timer.is_finished());
+        callbacks
+    }
+
     /// Checks if the request_render() method of this agent has been called
     pub fn did_request_render(&self) -> bool {
         self.requested_render
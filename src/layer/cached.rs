@@ -0,0 +1,314 @@
+use crate::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasmuri_core::*;
+
+use web_sys::{
+    WebGlFramebuffer,
+    WebGlRenderingContext
+};
+
+struct RenderCache {
+
+    framebuffer: WebGlFramebuffer,
+    texture: Rc<SpriteTexture>,
+
+    width: i32,
+    height: i32
+}
+
+impl RenderCache {
+
+    fn new(gl: &WebGlRenderingContext, width: i32, height: i32) -> RenderCache {
+        let texture = gl.create_texture().expect("Should be able to create cache texture");
+        gl.bind_texture(WebGlRenderingContext::TEXTURE_2D, Some(&texture));
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            WebGlRenderingContext::TEXTURE_2D, 0, WebGlRenderingContext::RGBA as i32, width, height, 0,
+            WebGlRenderingContext::RGBA, WebGlRenderingContext::UNSIGNED_BYTE, None
+        ).expect("Should be able to allocate cache texture");
+
+        // The size of the canvas is usually not a power of 2, which WebGL 1 only allows with these parameters
+        gl.tex_parameteri(WebGlRenderingContext::TEXTURE_2D, WebGlRenderingContext::TEXTURE_MIN_FILTER, WebGlRenderingContext::NEAREST as i32);
+        gl.tex_parameteri(WebGlRenderingContext::TEXTURE_2D, WebGlRenderingContext::TEXTURE_MAG_FILTER, WebGlRenderingContext::NEAREST as i32);
+        gl.tex_parameteri(WebGlRenderingContext::TEXTURE_2D, WebGlRenderingContext::TEXTURE_WRAP_S, WebGlRenderingContext::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(WebGlRenderingContext::TEXTURE_2D, WebGlRenderingContext::TEXTURE_WRAP_T, WebGlRenderingContext::CLAMP_TO_EDGE as i32);
+
+        let framebuffer = gl.create_framebuffer().expect("Should be able to create cache framebuffer");
+        gl.bind_framebuffer(WebGlRenderingContext::FRAMEBUFFER, Some(&framebuffer));
+        gl.framebuffer_texture_2d(WebGlRenderingContext::FRAMEBUFFER, WebGlRenderingContext::COLOR_ATTACHMENT0,
+                WebGlRenderingContext::TEXTURE_2D, Some(&texture), 0);
+        gl.bind_framebuffer(WebGlRenderingContext::FRAMEBUFFER, None);
+
+        RenderCache {
+            framebuffer,
+            texture: Rc::new(SpriteTexture::flipped(texture)),
+            width,
+            height
+        }
+    }
+
+    fn delete(&self, gl: &WebGlRenderingContext) {
+        gl.delete_framebuffer(Some(&self.framebuffer));
+        gl.delete_texture(Some(self.texture.get_texture()));
+    }
+}
+
+/// A layer that renders another layer into a texture and draws that texture onto the canvas. The wrapped layer only renders when one
+/// of its components requests a render; when the layers around this layer need the region of this layer to be drawn again, the
+/// texture is drawn instead. This makes large layers that rarely change almost free to draw behind layers that change often.
+///
+/// The texture is drawn with alpha blending, so semi-transparent pixels of the wrapped layer will look slightly different than
+/// they would without the cache. Layers that are fully opaque, like a SimpleLayer with a background color, look exactly the same.
+pub struct CachedLayer {
+
+    layer: Box<dyn Layer>,
+
+    /// The opacity of the texture, which is reported to the other layers when they force this layer to draw a region
+    opacity: RenderOpacity,
+
+    cache: Option<RenderCache>,
+
+    /// True if the wrapped layer predicted that it would render
+    layer_needs_render: bool,
+
+    /// The regions of the texture that need to be cleared before the wrapped layer renders
+    clear_regions: RegionSet,
+
+    /// The regions of the texture that need to be drawn onto the canvas during the next render
+    composite_regions: RegionSet,
+
+    /// The cursor the wrapped layer requested the last time it rendered
+    last_cursor: Option<Cursor>
+}
+
+impl CachedLayer {
+
+    /// Wraps the given layer. The opacity should be Solid if the wrapped layer covers the entire viewport with opaque pixels.
+    pub fn new(layer: Box<dyn Layer>, opacity: RenderOpacity) -> CachedLayer {
+        CachedLayer {
+            layer,
+            opacity,
+            cache: None,
+            layer_needs_render: true,
+            clear_regions: RegionSet::from_region(Region::entire_viewport()),
+            composite_regions: RegionSet::from_region(Region::entire_viewport()),
+            last_cursor: None
+        }
+    }
+
+    pub fn get_layer(&self) -> &dyn Layer {
+        &*self.layer
+    }
+
+    pub fn get_layer_mut(&mut self) -> &mut dyn Layer {
+        &mut *self.layer
+    }
+
+    /// Computes the part of the cache texture that corresponds to the given region, with (0, 0) as the top left corner
+    fn to_texture_region(region: Region) -> Region {
+        Region::new((region.get_min_x() + 1.0) * 0.5, (1.0 - region.get_max_y()) * 0.5, (region.get_max_x() + 1.0) * 0.5, (1.0 - region.get_min_y()) * 0.5)
+    }
+
+    /// Lets the wrapped layer render into the cache texture
+    fn render_to_cache(&mut self, gl: &WebGlRenderingContext, manager: &ContainerManager) {
+        let cache = self.cache.as_ref().expect("The cache should have been created before rendering to it");
+
+        // Everything that was drawn before should end up on the canvas rather than in the cache, and the other way around
+        manager.switch_render_phase(gl, None);
+        manager.get_render_context().borrow_mut().flush_and_invalidate();
+        gl.bind_framebuffer(WebGlRenderingContext::FRAMEBUFFER, Some(&cache.framebuffer));
+
+        // Nothing is behind the wrapped layer, so the regions it will redraw must be transparent before it draws over them
+        gl.clear_color(0.0, 0.0, 0.0, 0.0);
+        let mut render_context = manager.get_render_context().borrow_mut();
+        for region in self.clear_regions.get_regions() {
+            render_context.set_clip(Some(*region));
+            render_context.flush_and_invalidate();
+            gl.clear(WebGlRenderingContext::COLOR_BUFFER_BIT);
+        }
+        render_context.set_clip(None);
+
+        // The wrapped layer marks the regions it draws into the texture as dirty, but only the regions that are composited onto the
+        // canvas below are really redrawn
+        let canvas_dirty_regions = render_context.take_dirty_regions();
+        drop(render_context);
+        self.clear_regions.clear();
+
+        self.last_cursor = self.layer.on_render(gl, manager).get_cursor();
+
+        manager.switch_render_phase(gl, None);
+        let mut render_context = manager.get_render_context().borrow_mut();
+        render_context.flush_and_invalidate();
+        render_context.restore_dirty_regions(canvas_dirty_regions);
+        drop(render_context);
+        gl.bind_framebuffer(WebGlRenderingContext::FRAMEBUFFER, None);
+
+        self.layer_needs_render = false;
+    }
+}
+
+impl Layer for CachedLayer {
+
+    fn on_mouse_move(&mut self, new_pos: Option<(f32, f32)>, manager: &ContainerManager) -> ConsumableEventResult {
+        self.layer.on_mouse_move(new_pos, manager)
+    }
+
+    fn on_mouse_click(&mut self, click: ClickInfo, manager: &ContainerManager) -> ConsumableEventResult {
+        self.layer.on_mouse_click(click, manager)
+    }
+
+    fn on_mouse_click_outside(&mut self, click: ClickInfo, manager: &ContainerManager) -> EventResult {
+        self.layer.on_mouse_click_outside(click, manager)
+    }
+
+    fn on_mouse_scroll(&mut self, delta: f64, manager: &ContainerManager) -> ConsumableEventResult {
+        self.layer.on_mouse_scroll(delta, manager)
+    }
+
+    fn on_key_down(&mut self, keys: &KeyInfo, manager: &ContainerManager) -> ConsumableEventResult {
+        self.layer.on_key_down(keys, manager)
+    }
+
+    fn on_key_up(&mut self, keys: &KeyInfo, manager: &ContainerManager) -> ConsumableEventResult {
+        self.layer.on_key_up(keys, manager)
+    }
+
+    fn on_composition(&mut self, composition: &CompositionInfo, manager: &ContainerManager) -> ConsumableEventResult {
+        self.layer.on_composition(composition, manager)
+    }
+
+    fn on_context_menu(&mut self, manager: &ContainerManager) -> ConsumableEventResult {
+        self.layer.on_context_menu(manager)
+    }
+
+    fn on_copy(&mut self) -> Option<ClipboardData> {
+        self.layer.on_copy()
+    }
+
+    fn on_paste(&mut self, clipboard: &ClipboardData) -> bool {
+        self.layer.on_paste(clipboard)
+    }
+
+    fn on_cut(&mut self) -> Option<ClipboardData> {
+        self.layer.on_cut()
+    }
+
+    fn on_update(&mut self, manager: &ContainerManager) -> EventResult {
+        self.layer.on_update(manager)
+    }
+
+    fn predict_render(&mut self) -> Vec<PlannedRenderAction> {
+        let actions = self.layer.predict_render();
+        if !actions.is_empty() {
+            self.layer_needs_render = true;
+            for action in &actions {
+                if action.get_opacity() != RenderOpacity::Solid {
+                    self.clear_regions.add(action.get_region());
+                }
+                self.composite_regions.add(action.get_region());
+            }
+        }
+        actions
+    }
+
+    fn force_partial_render(&mut self, regions: &[Region]) -> Vec<PlannedRenderAction> {
+
+        // The wrapped layer doesn't need to render: the regions can simply be copied from the cache
+        let mut caused_render_actions = Vec::new();
+        for region in regions {
+            for uncovered in self.composite_regions.get_uncovered(*region).get_regions() {
+                caused_render_actions.push(PlannedRenderAction::new(*uncovered, self.opacity));
+            }
+            self.composite_regions.add(*region);
+        }
+        caused_render_actions
+    }
+
+    fn on_render(&mut self, gl: &WebGlRenderingContext, manager: &ContainerManager) -> RenderResult {
+        let width = gl.drawing_buffer_width();
+        let height = gl.drawing_buffer_height();
+
+        let outdated = match &self.cache {
+            Some(cache) => cache.width != width || cache.height != height,
+            None => true
+        };
+        if outdated {
+            manager.get_render_context().borrow_mut().flush_and_invalidate();
+            if let Some(old_cache) = self.cache.take() {
+                old_cache.delete(gl);
+            }
+            self.cache = Some(RenderCache::new(gl, width, height));
+            self.force_render();
+        }
+
+        if self.layer_needs_render {
+            self.render_to_cache(gl, manager);
+        }
+
+        let texture = Rc::clone(&self.cache.as_ref().expect("The cache should have been created").texture);
+        let white = Color::from_rgb(255, 255, 255);
+        let mut render_context = manager.get_render_context().borrow_mut();
+        for region in self.composite_regions.get_regions() {
+            render_context.draw_sprite(*region, &texture, Self::to_texture_region(*region), white);
+            render_context.add_dirty_region(*region);
+        }
+        drop(render_context);
+        self.composite_regions.clear();
+
+        RenderResult::new(self.last_cursor.clone())
+    }
+
+    fn force_render(&mut self) {
+        self.layer.force_render();
+        self.layer_needs_render = true;
+        self.clear_regions = RegionSet::from_region(Region::entire_viewport());
+        self.composite_regions = RegionSet::from_region(Region::entire_viewport());
+    }
+
+    fn wants_update(&mut self) -> bool {
+        self.layer.wants_update()
+    }
+
+    fn wants_render(&mut self) -> bool {
+        self.layer_needs_render || !self.composite_regions.is_empty() || self.layer.wants_render()
+    }
+
+    fn add_component(&mut self, component: Rc<RefCell<dyn Component>>) {
+        self.layer.add_component(component);
+        self.layer_needs_render = true;
+    }
+
+    fn snapshot(&mut self) -> LayerSnapshot {
+        LayerSnapshot::new("CachedLayer").with_inner(self.layer.snapshot())
+    }
+
+    fn inspect(&mut self) -> Option<LayerInspection> {
+        self.layer.inspect()
+    }
+
+    fn collect_access_nodes(&mut self, nodes: &mut Vec<(ComponentKey, AccessNode)>) {
+        self.layer.collect_access_nodes(nodes);
+    }
+
+    fn on_access_action(&mut self, key: ComponentKey, action: AccessAction, manager: &ContainerManager) -> ConsumableEventResult {
+        self.layer.on_access_action(key, action, manager)
+    }
+
+    fn get_shortcuts(&self) -> Option<&ShortcutRegistry> {
+        self.layer.get_shortcuts()
+    }
+
+    fn on_capture(&mut self, event: &DispatchEvent, context: &mut DispatchContext, manager: &ContainerManager) -> EventResult {
+        self.layer.on_capture(event, context, manager)
+    }
+
+    fn on_bubble(&mut self, event: &DispatchEvent, context: &mut DispatchContext, manager: &ContainerManager) -> EventResult {
+        self.layer.on_bubble(event, context, manager)
+    }
+
+    fn get_shortcuts_mut(&mut self) -> Option<&mut ShortcutRegistry> {
+        self.layer.get_shortcuts_mut()
+    }
+}
@@ -0,0 +1,99 @@
+use crate::*;
+
+use std::fmt::{
+    Debug,
+    Display,
+    Formatter
+};
+
+use wasmuri_core::Region;
+
+/// The kind of space a claim tried to obtain, which is also the kind of manager that rejected it
+#[derive(Clone,Copy,PartialEq,Eq,Hash,Debug)]
+pub enum ClaimKind {
+
+    Render,
+    KeyDown,
+    KeyUp,
+    MouseClick,
+    MouseScroll
+}
+
+/// The reason a claim of a LayerAgent failed
+#[derive(Clone)]
+pub enum ClaimError {
+
+    /// The claimed space overlaps with space of the same kind that was claimed earlier
+    Occupied {
+        kind: ClaimKind,
+
+        /// The bounds of the space that was claimed
+        region: Region,
+
+        /// The bounds of the space of the earlier claim
+        conflicting_region: Region,
+
+        /// The component that made the earlier claim, if it is still alive
+        conflicting_component: Option<ComponentKey>
+    },
+
+    /// The shortcut is already bound or its chord conflicts with another shortcut of the layer
+    Shortcut {
+        action: String,
+        error: ShortcutError
+    }
+}
+
+impl ClaimError {
+
+    pub fn occupied(kind: ClaimKind, region: Region, conflicting_region: Region, conflicting_component: Option<ComponentKey>) -> ClaimError {
+        ClaimError::Occupied {
+            kind,
+            region,
+            conflicting_region,
+            conflicting_component
+        }
+    }
+
+    /// Gets the kind of the rejected claim, or None if it was a shortcut claim
+    pub fn get_kind(&self) -> Option<ClaimKind> {
+        match self {
+            ClaimError::Occupied { kind, .. } => Some(*kind),
+            ClaimError::Shortcut { .. } => None
+        }
+    }
+
+    pub fn get_conflicting_component(&self) -> Option<ComponentKey> {
+        match self {
+            ClaimError::Occupied { conflicting_component, .. } => *conflicting_component,
+            ClaimError::Shortcut { .. } => None
+        }
+    }
+}
+
+fn format_region(region: Region) -> String {
+    format!("({}, {}) to ({}, {})", region.get_min_x(), region.get_min_y(), region.get_max_x(), region.get_max_y())
+}
+
+impl Display for ClaimError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClaimError::Occupied { kind, region, conflicting_region, conflicting_component } => {
+                write!(f, "Can't claim {:?} space {} because it overlaps with {}", kind, format_region(*region), format_region(*conflicting_region))?;
+                match conflicting_component {
+                    Some(key) => write!(f, " of {}", key),
+                    None => write!(f, " of a dropped component")
+                }
+            }, ClaimError::Shortcut { action, error } => write!(f, "Can't claim shortcut for action '{}': {}", action, error)
+        }
+    }
+}
+
+// Region doesn't implement Debug, so the Display implementation is used instead
+impl Debug for ClaimError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,355 @@
+use crate::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasmuri_core::*;
+
+use web_sys::WebGlRenderingContext;
+
+struct PanelLayout {
+
+    region: Region,
+
+    /// The region of each entry of the panel, in the same order as the entries
+    entry_regions: Vec<Region>
+}
+
+/// A popup layer that draws the context menu of the ContextMenuState of the manager and handles its mouse and keyboard input.
+/// It should be the front layer of a LayeredContainer. While a menu is open, this layer consumes all key events and the mouse
+/// events above the menu.
+pub struct ContextMenuLayer {
+
+    panels: Vec<PanelLayout>,
+    rendered_version: u64,
+
+    /// The regions of the panels before the last change, which need to be drawn again by the layers behind this layer
+    previous_regions: Vec<Region>,
+
+    mouse_pos: Option<(f32, f32)>,
+    needs_render: bool
+}
+
+impl ContextMenuLayer {
+
+    pub fn new() -> ContextMenuLayer {
+        ContextMenuLayer {
+            panels: Vec::new(),
+            rendered_version: 0,
+            previous_regions: Vec::new(),
+            mouse_pos: None,
+            needs_render: false
+        }
+    }
+
+    fn get_row_height(theme: &Theme) -> f32 {
+        theme.fonts.body_height + 2.0 * theme.spacing.padding
+    }
+
+    fn get_entry_height(entry: &MenuEntry, theme: &Theme) -> f32 {
+        match entry {
+            MenuEntry::Separator => 2.0 * theme.spacing.padding,
+            _ => Self::get_row_height(theme)
+        }
+    }
+
+    /// Computes the layout of a panel whose top left corner should be at the given position. The panel will be moved if it would
+    /// otherwise leave the viewport.
+    fn layout_panel(entries: &[MenuEntry], top_left: (f32, f32), theme: &Theme) -> PanelLayout {
+        let char_width = theme.fonts.body_height * 0.5;
+        let mut max_chars = 0;
+        for entry in entries {
+            let chars = match entry {
+                MenuEntry::Item { label, shortcut, .. } => {
+                    label.chars().count() + shortcut.as_ref().map(|chord| chord.to_string().chars().count() + 4).unwrap_or(0)
+                }, MenuEntry::Submenu { label, .. } => label.chars().count() + 3,
+                MenuEntry::Separator => 0
+            };
+            max_chars = max_chars.max(chars);
+        }
+
+        let width = max_chars as f32 * char_width + 4.0 * theme.spacing.padding;
+        let height: f32 = entries.iter().map(|entry| Self::get_entry_height(entry, theme)).sum();
+
+        let min_x = top_left.0.min(1.0 - width).max(-1.0);
+        let max_y = top_left.1.max(-1.0 + height).min(1.0);
+
+        let mut entry_regions = Vec::with_capacity(entries.len());
+        let mut current_y = max_y;
+        for entry in entries {
+            let entry_height = Self::get_entry_height(entry, theme);
+            entry_regions.push(Region::new(min_x, current_y - entry_height, min_x + width, current_y));
+            current_y -= entry_height;
+        }
+
+        PanelLayout {
+            region: Region::new(min_x, max_y - height, min_x + width, max_y),
+            entry_regions
+        }
+    }
+
+    fn update_layout(&mut self, state: &ContextMenuState, theme: &Theme) {
+        self.panels.clear();
+        if !state.is_open() {
+            return;
+        }
+
+        let mut top_left = state.get_position().unwrap();
+        for panel in 0 .. state.get_num_panels() {
+            let layout = Self::layout_panel(state.get_panel_entries(panel), top_left, theme);
+
+            // The next panel is the submenu of the highlighted entry, so it should be next to that entry
+            if let Some(highlight) = state.get_highlight(panel) {
+                let entry_region = layout.entry_regions[highlight];
+                top_left = (entry_region.get_max_x(), entry_region.get_max_y());
+            }
+            self.panels.push(layout);
+        }
+    }
+
+    /// Finds the panel and entry at the given position, if any. The later panels are drawn on top of the earlier panels.
+    fn find_entry(&self, position: (f32, f32)) -> Option<(usize, Option<usize>)> {
+        for (panel_index, panel) in self.panels.iter().enumerate().rev() {
+            if panel.region.is_float_inside(position) {
+                let entry = panel.entry_regions.iter().position(|region| region.is_float_inside(position));
+                return Some((panel_index, entry));
+            }
+        }
+
+        None
+    }
+
+    fn activate(&mut self, manager: &ContainerManager) {
+        let activated = manager.get_context_menu().borrow_mut().activate();
+
+        // The state is no longer borrowed, so the owner is free to open another menu
+        if let Some((owner, action)) = activated {
+            if let Some(owner) = owner.upgrade() {
+                owner.borrow_mut().context_menu_action(&mut MenuActionParams::new(&action, manager));
+            }
+        }
+    }
+
+    fn render_panel(params: &mut RenderParams, entries: &[MenuEntry], layout: &PanelLayout, highlight: Option<usize>, theme: &Theme) {
+        let padding = theme.spacing.padding;
+        fill_region(params, layout.region, theme.palette.surface);
+
+        for (index, entry) in entries.iter().enumerate() {
+            let entry_region = layout.entry_regions[index];
+            let width = entry_region.get_max_x() - entry_region.get_min_x();
+
+            if highlight == Some(index) {
+                fill_region(params, entry_region, theme.get_style(WidgetState::Hover).background);
+            }
+
+            let text_color = if entry.is_selectable() { theme.palette.text } else { theme.get_style(WidgetState::Disabled).text };
+            let text_region = center_text_region(inset_region(entry_region, 2.0 * padding, padding), theme.fonts.body_height);
+            match entry {
+                MenuEntry::Item { label, shortcut, .. } => {
+                    draw_text(params, label, text_region, text_color);
+                    if let Some(chord) = shortcut {
+                        let shortcut_region = Region::new(text_region.get_min_x() + width * 0.6, text_region.get_min_y(),
+                                text_region.get_max_x(), text_region.get_max_y());
+                        draw_text(params, &chord.to_string(), shortcut_region, theme.palette.secondary_text);
+                    }
+                }, MenuEntry::Submenu { label, .. } => {
+                    draw_text(params, label, text_region, text_color);
+                    let arrow_region = Region::new(text_region.get_max_x() - theme.fonts.body_height, text_region.get_min_y(),
+                            text_region.get_max_x(), text_region.get_max_y());
+                    draw_text(params, ">", arrow_region, text_color);
+                }, MenuEntry::Separator => {
+                    let center_y = (entry_region.get_min_y() + entry_region.get_max_y()) * 0.5;
+                    let line = Region::new(entry_region.get_min_x() + padding, center_y - padding * 0.1, entry_region.get_max_x() - padding, center_y + padding * 0.1);
+                    fill_region(params, line, theme.palette.border);
+                }
+            };
+        }
+
+        draw_outline(params, layout.region, theme.palette.border);
+    }
+}
+
+impl Layer for ContextMenuLayer {
+
+    fn on_mouse_move(&mut self, new_pos: Option<(f32, f32)>, manager: &ContainerManager) -> ConsumableEventResult {
+        self.mouse_pos = new_pos;
+
+        let hovered = new_pos.and_then(|position| self.find_entry(position));
+        match hovered {
+            Some((panel, entry)) => {
+                if entry.is_some() {
+                    manager.get_context_menu().borrow_mut().highlight(panel, entry);
+                }
+                ConsumableEventResult::do_consume()
+            }, None => ConsumableEventResult::dont_consume()
+        }
+    }
+
+    fn on_mouse_click(&mut self, _click: ClickInfo, manager: &ContainerManager) -> ConsumableEventResult {
+        if !manager.get_context_menu().borrow().is_open() {
+            return ConsumableEventResult::dont_consume();
+        }
+
+        let clicked = self.mouse_pos.and_then(|position| self.find_entry(position));
+        match clicked {
+            Some((panel, Some(entry))) => {
+                manager.get_context_menu().borrow_mut().highlight(panel, Some(entry));
+                self.activate(manager);
+            }, Some((_panel, None)) => {},
+            None => manager.get_context_menu().borrow_mut().close()
+        };
+
+        // Clicks on the menu shouldn't reach the components behind it
+        ConsumableEventResult::consume(clicked.is_some())
+    }
+
+    fn on_mouse_scroll(&mut self, _delta: f64, _manager: &ContainerManager) -> ConsumableEventResult {
+        let over_menu = self.mouse_pos.and_then(|position| self.find_entry(position)).is_some();
+        ConsumableEventResult::consume(over_menu)
+    }
+
+    fn on_key_down(&mut self, keys: &KeyInfo, manager: &ContainerManager) -> ConsumableEventResult {
+        if !manager.get_context_menu().borrow().is_open() {
+            return ConsumableEventResult::dont_consume();
+        }
+
+        match keys.get_key() {
+            "ArrowDown" => manager.get_context_menu().borrow_mut().move_highlight(true),
+            "ArrowUp" => manager.get_context_menu().borrow_mut().move_highlight(false),
+            "ArrowRight" => manager.get_context_menu().borrow_mut().enter_submenu(),
+            "ArrowLeft" => manager.get_context_menu().borrow_mut().leave_submenu(),
+            "Escape" => manager.get_context_menu().borrow_mut().close(),
+            "Enter" | " " => self.activate(manager),
+            _ => {}
+        };
+
+        // The menu is modal for the keyboard while it is open
+        ConsumableEventResult::do_consume()
+    }
+
+    fn on_key_up(&mut self, _keys: &KeyInfo, manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::consume(manager.get_context_menu().borrow().is_open())
+    }
+
+    fn on_composition(&mut self, _composition: &CompositionInfo, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_context_menu(&mut self, _manager: &ContainerManager) -> ConsumableEventResult {
+
+        // Right clicking on the menu itself shouldn't open another menu
+        let over_menu = self.mouse_pos.and_then(|position| self.find_entry(position)).is_some();
+        ConsumableEventResult::consume(over_menu)
+    }
+
+    fn on_copy(&mut self) -> Option<ClipboardData> {
+        None
+    }
+
+    fn on_paste(&mut self, _clipboard: &ClipboardData) -> bool {
+        false
+    }
+
+    fn on_cut(&mut self) -> Option<ClipboardData> {
+        None
+    }
+
+    fn on_update(&mut self, manager: &ContainerManager) -> EventResult {
+        let state = manager.get_context_menu().borrow();
+        if state.get_version() != self.rendered_version {
+            self.rendered_version = state.get_version();
+
+            for panel in &self.panels {
+                self.previous_regions.push(panel.region);
+            }
+            self.update_layout(&state, &manager.get_theme());
+            self.needs_render = !self.panels.is_empty();
+        }
+
+        None
+    }
+
+    fn predict_render(&mut self) -> Vec<PlannedRenderAction> {
+        let mut actions = Vec::new();
+
+        // This layer might draw nothing in the previous regions, so the layers behind it need to draw them again
+        for previous_region in self.previous_regions.drain(..) {
+            actions.push(PlannedRenderAction::new(previous_region, RenderOpacity::Mixed));
+        }
+
+        if self.needs_render {
+            for panel in &self.panels {
+                actions.push(PlannedRenderAction::new(panel.region, RenderOpacity::Solid));
+            }
+        }
+
+        actions
+    }
+
+    fn force_partial_render(&mut self, regions: &[Region]) -> Vec<PlannedRenderAction> {
+        if self.needs_render {
+            return Vec::new();
+        }
+
+        let intersects = self.panels.iter().any(|panel| regions.iter().any(|region| region.intersects_with(panel.region)));
+        if intersects {
+            self.needs_render = true;
+            self.panels.iter().map(|panel| PlannedRenderAction::new(panel.region, RenderOpacity::Solid)).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn on_render(&mut self, gl: &WebGlRenderingContext, manager: &ContainerManager) -> RenderResult {
+        if self.needs_render {
+            self.needs_render = false;
+
+            let state = manager.get_context_menu().borrow();
+            let theme = manager.get_theme();
+
+            // The menu is drawn outside of any render phase
+            manager.switch_render_phase(gl, None);
+            let mut params = RenderParams::new(gl, manager);
+
+            // The layout can be outdated if the menu changed after the last update, but it will be rendered again after the next update
+            if state.get_num_panels() == self.panels.len() {
+                for (panel_index, layout) in self.panels.iter().enumerate() {
+                    Self::render_panel(&mut params, state.get_panel_entries(panel_index), layout, state.get_highlight(panel_index), &theme);
+                }
+            }
+
+            let mut render_context = manager.get_render_context().borrow_mut();
+            for layout in &self.panels {
+                render_context.add_dirty_region(layout.region);
+            }
+            render_context.flush();
+        }
+
+        if self.mouse_pos.and_then(|position| self.find_entry(position)).is_some() {
+            RenderResult::with_cursor(Cursor::DEFAULT)
+        } else {
+            RenderResult::without_cursor()
+        }
+    }
+
+    fn force_render(&mut self) {
+        self.needs_render = !self.panels.is_empty();
+    }
+
+    // The context menu only changes in response to input
+    fn wants_update(&mut self) -> bool {
+        false
+    }
+
+    fn wants_render(&mut self) -> bool {
+        self.needs_render || !self.previous_regions.is_empty()
+    }
+
+    fn snapshot(&mut self) -> LayerSnapshot {
+        LayerSnapshot::new("ContextMenuLayer")
+    }
+
+    fn add_component(&mut self, _component: Rc<RefCell<dyn Component>>) {
+        panic!("A ContextMenuLayer can't have components");
+    }
+}
@@ -0,0 +1,213 @@
+use crate::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasmuri_core::*;
+
+use web_sys::WebGlRenderingContext;
+
+/// An overlay layer that draws the durations of the frames recorded by the Profiler of the manager as a bar graph. Update frames
+/// are drawn in blue, render frames in orange and frames that took longer than the frame budget in red. The line in the middle of
+/// the graph marks the frame budget. The graph is only shown while the profiler is enabled. It should be the front layer of a
+/// LayeredContainer, and it never consumes events.
+pub struct FrameGraphLayer {
+
+    region: Region,
+
+    /// Whether the profiler was enabled during the last update
+    visible: bool,
+
+    /// The kinds and durations of the frames that are currently drawn
+    frames: Vec<(FrameKind, f64)>,
+    capacity: usize,
+
+    /// The recorded frame count of the profiler when the frames were last copied
+    recorded_frames: u64,
+
+    /// The duration (in milliseconds) of a single frame at the target frame rate of the manager
+    budget: f64,
+
+    needs_render: bool,
+
+    /// True if the graph was hidden since the last render, so the layers behind it need to draw its region again
+    needs_clear: bool
+}
+
+impl FrameGraphLayer {
+
+    /// Creates a FrameGraphLayer that draws the graph in the given region
+    pub fn new(region: Region) -> FrameGraphLayer {
+        FrameGraphLayer {
+            region,
+            visible: false,
+            frames: Vec::new(),
+            capacity: 0,
+            recorded_frames: 0,
+            budget: 1000.0 / 60.0,
+            needs_render: false,
+            needs_clear: false
+        }
+    }
+
+    /// Creates a FrameGraphLayer that draws the graph in the top right corner of the canvas
+    pub fn top_right() -> FrameGraphLayer {
+        Self::new(Region::new(0.4, 0.6, 1.0, 1.0))
+    }
+
+    fn draw(&self, params: &mut RenderParams) {
+        let region = self.region;
+        let height = region.get_max_y() - region.get_min_y();
+        fill_region(params, region, Color::from_rgb(20, 20, 20));
+
+        // Frames that take twice the budget fill the entire height, and longer frames are cut off
+        let bar_width = (region.get_max_x() - region.get_min_x()) / self.capacity.max(1) as f32;
+        for (index, (kind, duration)) in self.frames.iter().enumerate() {
+            let color = if *duration > self.budget {
+                Color::from_rgb(230, 40, 40)
+            } else {
+                match kind {
+                    FrameKind::Update => Color::from_rgb(60, 140, 255),
+                    FrameKind::Render => Color::from_rgb(255, 160, 40)
+                }
+            };
+            let bar_height = (*duration / (2.0 * self.budget)).min(1.0) as f32 * height;
+            let min_x = region.get_min_x() + index as f32 * bar_width;
+            fill_region(params, Region::new(min_x, region.get_min_y(), min_x + bar_width, region.get_min_y() + bar_height), color);
+        }
+
+        let pixel_height = 2.0 / params.manager.get_canvas().height() as f32;
+        let budget_y = region.get_min_y() + 0.5 * height;
+        fill_region(params, Region::new(region.get_min_x(), budget_y, region.get_max_x(), budget_y + pixel_height), Color::from_rgb(200, 200, 200));
+
+        let last_frame = |wanted_kind| self.frames.iter().rev().find(|(kind, _duration)| *kind == wanted_kind).map(|(_kind, duration)| *duration);
+        let label = format!("update {:.1} ms   render {:.1} ms", last_frame(FrameKind::Update).unwrap_or(0.0), last_frame(FrameKind::Render).unwrap_or(0.0));
+        let text_height = params.get_theme().fonts.small_height;
+        let label_region = Region::new(region.get_min_x(), region.get_max_y() - text_height, region.get_max_x(), region.get_max_y());
+        draw_text(params, &label, label_region, Color::from_rgb(255, 255, 255));
+    }
+}
+
+impl Layer for FrameGraphLayer {
+
+    fn on_mouse_move(&mut self, _new_pos: Option<(f32, f32)>, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_mouse_click(&mut self, _click: ClickInfo, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_mouse_scroll(&mut self, _delta: f64, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_key_down(&mut self, _keys: &KeyInfo, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_key_up(&mut self, _keys: &KeyInfo, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_composition(&mut self, _composition: &CompositionInfo, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_context_menu(&mut self, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_copy(&mut self) -> Option<ClipboardData> {
+        None
+    }
+
+    fn on_paste(&mut self, _clipboard: &ClipboardData) -> bool {
+        false
+    }
+
+    fn on_cut(&mut self) -> Option<ClipboardData> {
+        None
+    }
+
+    // The graph is only refreshed during updates. Render frames never cause an update, so drawing the graph can't keep the manager
+    // busy in LoopMode::OnDemand.
+    fn on_update(&mut self, manager: &ContainerManager) -> EventResult {
+        let profiler = manager.get_profiler().borrow();
+        if profiler.is_enabled() != self.visible {
+            self.visible = profiler.is_enabled();
+            self.needs_render = self.visible;
+            self.needs_clear = !self.visible;
+        }
+
+        if self.visible && profiler.get_recorded_frame_count() != self.recorded_frames {
+            self.recorded_frames = profiler.get_recorded_frame_count();
+            self.frames = profiler.get_frames().iter().map(|frame| (frame.get_kind(), frame.get_duration())).collect();
+            self.capacity = profiler.get_capacity();
+            self.budget = 1000.0 / manager.get_update_frame().get_target_frame_rate();
+            self.needs_render = true;
+        }
+
+        None
+    }
+
+    fn predict_render(&mut self) -> Vec<PlannedRenderAction> {
+        if self.needs_clear {
+            self.needs_clear = false;
+            vec![PlannedRenderAction::new(self.region, RenderOpacity::Mixed)]
+        } else if self.needs_render {
+            vec![PlannedRenderAction::new(self.region, RenderOpacity::Solid)]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn force_partial_render(&mut self, regions: &[Region]) -> Vec<PlannedRenderAction> {
+        if self.visible && !self.needs_render && regions.iter().any(|region| region.intersects_with(self.region)) {
+            self.needs_render = true;
+            return vec![PlannedRenderAction::new(self.region, RenderOpacity::Solid)];
+        }
+
+        Vec::new()
+    }
+
+    fn on_render(&mut self, gl: &WebGlRenderingContext, manager: &ContainerManager) -> RenderResult {
+        if self.needs_render {
+            self.needs_render = false;
+
+            if self.visible {
+
+                // The components of the other layers may have left a render phase active
+                manager.switch_render_phase(gl, None);
+                let mut params = RenderParams::new(gl, manager);
+                self.draw(&mut params);
+
+                let mut render_context = manager.get_render_context().borrow_mut();
+                render_context.add_dirty_region(self.region);
+                render_context.flush();
+            }
+        }
+
+        RenderResult::without_cursor()
+    }
+
+    fn force_render(&mut self) {
+        self.needs_render = self.visible;
+    }
+
+    fn wants_update(&mut self) -> bool {
+        false
+    }
+
+    fn wants_render(&mut self) -> bool {
+        self.needs_render || self.needs_clear
+    }
+
+    fn snapshot(&mut self) -> LayerSnapshot {
+        LayerSnapshot::new("FrameGraphLayer")
+    }
+
+    fn add_component(&mut self, _component: Rc<RefCell<dyn Component>>) {
+        panic!("A FrameGraphLayer can't have components");
+    }
+}
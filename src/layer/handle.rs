@@ -7,8 +7,8 @@ pub struct ComponentHandle {
 
     component: Rc<RefCell<dyn Component>>,
 
-    // Currently, the only purpose of this field is to prevent the behaviors from being dropped while the component is alive
-    _behaviors: Vec<Rc<RefCell<dyn ComponentBehavior>>>,
+    // This field also prevents the behaviors from being dropped while the component is alive
+    behaviors: Vec<Rc<RefCell<dyn ComponentBehavior>>>,
 
     agent: Rc<RefCell<ComponentAgent>>
 }
@@ -23,7 +23,7 @@ impl ComponentHandle {
 
         ComponentHandle {
             component,
-            _behaviors: behaviors,
+            behaviors,
             agent
         }
     }
@@ -32,6 +32,10 @@ impl ComponentHandle {
         &self.component
     }
 
+    pub fn get_behaviors(&self) -> &Vec<Rc<RefCell<dyn ComponentBehavior>>> {
+        &self.behaviors
+    }
+
     pub fn get_agent(&mut self) -> RefMut<ComponentAgent> {
         self.agent.borrow_mut()
     }
@@ -0,0 +1,288 @@
+use crate::*;
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use wasmuri_core::*;
+
+use web_sys::WebGlRenderingContext;
+
+/// A debug overlay that draws the claims of the components of the other layers and the regions they planned to render and drew during
+/// the last frame in which something changed. The component under the mouse is highlighted together with its key. It should be the
+/// front layer of a LayeredContainer, and it can be toggled with the hotkey of the InspectorState of the manager (F12 by default).
+/// It never consumes events other than the hotkey.
+pub struct InspectorLayer {
+
+    /// Whether the overlay was enabled during the last update
+    enabled: bool,
+
+    /// The inspections that are currently drawn
+    shown: Vec<LayerInspection>,
+
+    mouse_pos: Option<(f32, f32)>,
+    hovered: Option<ComponentKey>,
+
+    /// The regions that changed since the last render, which need to be drawn again by this layer and the layers behind it
+    changed_regions: RegionSet,
+
+    /// The regions this layer needs to draw during the next render
+    render_regions: RegionSet
+}
+
+impl InspectorLayer {
+
+    pub fn new() -> InspectorLayer {
+        InspectorLayer {
+            enabled: false,
+            shown: Vec::new(),
+            mouse_pos: None,
+            hovered: None,
+            changed_regions: RegionSet::new(),
+            render_regions: RegionSet::new()
+        }
+    }
+
+    fn get_claim_color(kind: ClaimKind) -> Color {
+        match kind {
+            ClaimKind::Render => Color::from_rgb(0, 120, 255),
+            ClaimKind::MouseClick => Color::from_rgb(0, 200, 0),
+            ClaimKind::MouseScroll => Color::from_rgb(255, 140, 0),
+            ClaimKind::KeyDown => Color::from_rgb(170, 0, 255),
+            ClaimKind::KeyUp => Color::from_rgb(255, 0, 200)
+        }
+    }
+
+    /// Finds the component with the smallest claim under the mouse, in the front-most layer that has a claim there
+    fn find_hovered(&self) -> Option<ComponentKey> {
+        let mouse_pos = self.mouse_pos?;
+        let area = |region: Region| (region.get_max_x() - region.get_min_x()) * (region.get_max_y() - region.get_min_y());
+        for layer in self.shown.iter().rev() {
+            let hovered = layer.get_claims().iter()
+                    .filter(|claim| claim.get_component().is_some() && claim.get_region().is_float_inside(mouse_pos))
+                    .min_by(|a, b| area(a.get_region()).partial_cmp(&area(b.get_region())).unwrap_or(Ordering::Equal));
+            if let Some(claim) = hovered {
+                return claim.get_component();
+            }
+        }
+
+        None
+    }
+
+    /// Marks the regions of all claims of the given component as changed
+    fn mark_component_changed(&mut self, component: Option<ComponentKey>) {
+        if component.is_none() {
+            return;
+        }
+        for layer in &self.shown {
+            for claim in layer.get_claims() {
+                if claim.get_component() == component {
+                    self.changed_regions.add(claim.get_region());
+                }
+            }
+        }
+    }
+
+    fn update_hovered(&mut self) {
+        let hovered = self.find_hovered();
+        if hovered != self.hovered {
+            self.mark_component_changed(self.hovered);
+            self.mark_component_changed(hovered);
+            self.hovered = hovered;
+        }
+    }
+
+    /// Replaces the shown inspections and marks the regions in which they differ as changed
+    fn show(&mut self, next: Vec<LayerInspection>) {
+        let same_claims = self.shown.len() == next.len() && self.shown.iter().zip(&next).all(|(old, new)| old.get_claims() == new.get_claims());
+        if same_claims {
+            for layer in self.shown.iter().chain(&next) {
+                for region in layer.get_planned_regions().iter().chain(layer.get_passed_regions()) {
+                    self.changed_regions.add(*region);
+                }
+            }
+        } else {
+            self.changed_regions = RegionSet::from_region(Region::entire_viewport());
+        }
+
+        self.shown = next;
+    }
+
+    /// Draws all inspections. The clip of the render context determines which part is actually drawn.
+    fn draw(&self, params: &mut RenderParams) {
+        let planned_color = Color::from_rgba(255, 220, 0, 60);
+        let passed_color = Color::from_rgb(255, 0, 0);
+        let hovered_color = Color::from_rgba(0, 150, 255, 70);
+
+        for layer in &self.shown {
+            for region in layer.get_planned_regions() {
+                blend_region(params, *region, planned_color);
+            }
+            for region in layer.get_passed_regions() {
+                draw_outline(params, *region, passed_color);
+            }
+            for claim in layer.get_claims() {
+                draw_outline(params, claim.get_region(), Self::get_claim_color(claim.get_kind()));
+            }
+        }
+
+        if let Some(hovered) = self.hovered {
+            let mut label_region = None;
+            for layer in &self.shown {
+                for claim in layer.get_claims() {
+                    if claim.get_component() == Some(hovered) {
+                        blend_region(params, claim.get_region(), hovered_color);
+                        label_region = label_region.or(Some(claim.get_region()));
+                    }
+                }
+            }
+
+            // The label is drawn inside the claim, so it is redrawn whenever the hovered component changes
+            if let Some(region) = label_region {
+                let text_height = params.get_theme().fonts.small_height;
+                let label = Region::new(region.get_min_x(), region.get_max_y() - text_height, region.get_max_x(), region.get_max_y());
+                blend_region(params, label, Color::from_rgba(0, 0, 0, 180));
+                draw_text(params, &hovered.to_string(), label, Color::from_rgb(255, 255, 255));
+            }
+        }
+    }
+}
+
+impl Layer for InspectorLayer {
+
+    fn on_mouse_move(&mut self, new_pos: Option<(f32, f32)>, _manager: &ContainerManager) -> ConsumableEventResult {
+        self.mouse_pos = new_pos;
+        if self.enabled {
+            self.update_hovered();
+        }
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_mouse_click(&mut self, _click: ClickInfo, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_mouse_scroll(&mut self, _delta: f64, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_key_down(&mut self, keys: &KeyInfo, manager: &ContainerManager) -> ConsumableEventResult {
+        let mut inspector = manager.get_inspector().borrow_mut();
+        if KeyStroke::from_key_info(keys) == *inspector.get_hotkey() {
+            inspector.toggle();
+            manager.prevent_default();
+            ConsumableEventResult::do_consume()
+        } else {
+            ConsumableEventResult::dont_consume()
+        }
+    }
+
+    fn on_key_up(&mut self, _keys: &KeyInfo, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_composition(&mut self, _composition: &CompositionInfo, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_context_menu(&mut self, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_copy(&mut self) -> Option<ClipboardData> {
+        None
+    }
+
+    fn on_paste(&mut self, _clipboard: &ClipboardData) -> bool {
+        false
+    }
+
+    fn on_cut(&mut self) -> Option<ClipboardData> {
+        None
+    }
+
+    fn on_update(&mut self, manager: &ContainerManager) -> EventResult {
+        let inspector = manager.get_inspector().borrow();
+        if inspector.is_enabled() != self.enabled {
+            self.enabled = inspector.is_enabled();
+            self.shown = inspector.get_layers().to_vec();
+            self.changed_regions = RegionSet::from_region(Region::entire_viewport());
+        } else if self.enabled && inspector.get_layers() != &self.shown[..] {
+            self.show(inspector.get_layers().to_vec());
+        }
+        drop(inspector);
+
+        if self.enabled {
+            self.update_hovered();
+        }
+
+        None
+    }
+
+    fn predict_render(&mut self) -> Vec<PlannedRenderAction> {
+
+        // This layer draws transparent outlines, so the layers behind it need to draw the changed regions again
+        let actions = self.changed_regions.get_regions().iter().map(|region| PlannedRenderAction::new(*region, RenderOpacity::Mixed)).collect();
+        self.render_regions.union(&self.changed_regions);
+        self.changed_regions.clear();
+        actions
+    }
+
+    fn force_partial_render(&mut self, regions: &[Region]) -> Vec<PlannedRenderAction> {
+
+        // The outlines in the regions are drawn over by the layers behind this layer, so they need to be drawn again
+        if self.enabled {
+            for region in regions {
+                self.render_regions.add(*region);
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn on_render(&mut self, gl: &WebGlRenderingContext, manager: &ContainerManager) -> RenderResult {
+        if self.enabled && !self.render_regions.is_empty() {
+
+            // The overlays don't belong to the render phase of the component that rendered last
+            manager.switch_render_phase(gl, None);
+            let mut params = RenderParams::new(gl, manager);
+            for region in self.render_regions.get_regions() {
+                manager.get_render_context().borrow_mut().set_clip(Some(*region));
+                self.draw(&mut params);
+            }
+
+            let mut render_context = manager.get_render_context().borrow_mut();
+            render_context.set_clip(None);
+            for region in self.render_regions.get_regions() {
+                render_context.add_dirty_region(*region);
+            }
+            render_context.flush();
+        }
+        self.render_regions.clear();
+
+        RenderResult::without_cursor()
+    }
+
+    fn force_render(&mut self) {
+        if self.enabled {
+            self.changed_regions = RegionSet::from_region(Region::entire_viewport());
+        }
+    }
+
+    // The inspections are recorded during the renders, so they can only be picked up by updating every frame
+    fn wants_update(&mut self) -> bool {
+        self.enabled
+    }
+
+    fn wants_render(&mut self) -> bool {
+        !self.changed_regions.is_empty() || !self.render_regions.is_empty()
+    }
+
+    fn snapshot(&mut self) -> LayerSnapshot {
+        LayerSnapshot::new("InspectorLayer")
+    }
+
+    fn add_component(&mut self, _component: Rc<RefCell<dyn Component>>) {
+        panic!("An InspectorLayer can't have components");
+    }
+}
@@ -1,9 +1,15 @@
 use crate::*;
 
 mod agent;
+mod cached;
+mod claim;
+mod contextmenu;
+mod framegraph;
 mod handle;
+mod inspector;
 mod render;
 mod simple;
+mod tooltip;
 
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -13,9 +19,15 @@ use wasmuri_core::*;
 use web_sys::WebGlRenderingContext;
 
 pub use agent::*;
+pub use cached::*;
+pub use claim::*;
+pub use contextmenu::*;
+pub use framegraph::*;
 pub use handle::*;
+pub use inspector::*;
 pub use render::*;
 pub use simple::*;
+pub use tooltip::*;
 
 
 
@@ -24,7 +36,14 @@ pub trait Layer {
     /// If the event is consumed, the remaining layers will get passed a new_pos of None
     fn on_mouse_move(&mut self, new_pos: Option<(f32, f32)>, manager: &ContainerManager) -> ConsumableEventResult;
 
-    fn on_mouse_click(&mut self, click: ClickInfo, manager: &ContainerManager) -> EventResult;
+    /// If the event is consumed, the layers behind this layer won't receive the click
+    fn on_mouse_click(&mut self, click: ClickInfo, manager: &ContainerManager) -> ConsumableEventResult;
+
+    /// Called instead of on_mouse_click when a layer in front of this layer consumed the click, so that the components of this layer
+    /// can treat it like a click outside of them
+    fn on_mouse_click_outside(&mut self, _click: ClickInfo, _manager: &ContainerManager) -> EventResult {
+        None
+    }
 
     fn on_mouse_scroll(&mut self, delta: f64, manager: &ContainerManager) -> ConsumableEventResult;
 
@@ -32,6 +51,12 @@ pub trait Layer {
 
     fn on_key_up(&mut self, keys: &KeyInfo, manager: &ContainerManager) -> ConsumableEventResult;
 
+    fn on_composition(&mut self, composition: &CompositionInfo, manager: &ContainerManager) -> ConsumableEventResult;
+
+    /// Should open the context menu of the component under the mouse, if any. If the event is consumed, the layers behind this
+    /// layer won't get the chance to open a context menu.
+    fn on_context_menu(&mut self, manager: &ContainerManager) -> ConsumableEventResult;
+
     fn on_copy(&mut self) -> Option<ClipboardData>;
 
     fn on_paste(&mut self, clipboard: &ClipboardData) -> bool;
@@ -49,18 +74,109 @@ pub trait Layer {
     /// Ensures that all components in this layer will render during the next call to on_render()
     fn force_render(&mut self);
 
+    /// Checks if this layer needs to be updated even though no input arrived since the last update. This is only used when the
+    /// manager uses LoopMode::OnDemand. The default implementation always returns true.
+    fn wants_update(&mut self) -> bool {
+        true
+    }
+
+    /// Checks if this layer would draw something if it were rendered now. This is only used when the manager uses
+    /// LoopMode::OnDemand. The default implementation always returns true.
+    fn wants_render(&mut self) -> bool {
+        true
+    }
+
     fn add_component(&mut self, component: Rc<RefCell<dyn Component>>);
+
+    /// Creates a read-only description of this layer and its components, for debugging and tests
+    fn snapshot(&mut self) -> LayerSnapshot {
+        LayerSnapshot::new("Layer")
+    }
+
+    /// Describes the claims of the components of this layer and the regions they drew during the last render, for the inspector.
+    /// Layers that return None are left out of the inspection.
+    fn inspect(&mut self) -> Option<LayerInspection> {
+        None
+    }
+
+    /// Adds the accessibility nodes of the components in this layer to nodes. Layers without components don't need to implement this.
+    fn collect_access_nodes(&mut self, _nodes: &mut Vec<(ComponentKey, AccessNode)>) {}
+
+    /// Passes the action of assistive technology to the component with the given key. The result should be consumed if this layer
+    /// has that component.
+    fn on_access_action(&mut self, _key: ComponentKey, _action: AccessAction, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    /// Gets the shortcuts that are bound in this layer, if this layer supports shortcuts
+    fn get_shortcuts(&self) -> Option<&ShortcutRegistry> {
+        None
+    }
+
+    /// Called during the capture phase of an event when the container uses DispatchMode::CaptureBubble. The layers get the capture
+    /// phase from back to front. Layers that don't care about the capture phase don't need to implement this.
+    fn on_capture(&mut self, _event: &DispatchEvent, _context: &mut DispatchContext, _manager: &ContainerManager) -> EventResult {
+        None
+    }
+
+    /// Called during the bubble phase of an event when the container uses DispatchMode::CaptureBubble. The layers get the bubble
+    /// phase from front to back. The default implementation passes the event to the normal event method of this layer and stops
+    /// the propagation if that method consumed the event.
+    fn on_bubble(&mut self, event: &DispatchEvent, context: &mut DispatchContext, manager: &ContainerManager) -> EventResult {
+        let result = match event {
+            DispatchEvent::KeyDown(keys) => self.on_key_down(keys, manager),
+            DispatchEvent::KeyUp(keys) => self.on_key_up(keys, manager),
+            DispatchEvent::MouseClick(click) => self.on_mouse_click(*click, manager),
+            DispatchEvent::MouseMove(new_pos) => self.on_mouse_move(*new_pos, manager),
+            DispatchEvent::MouseScroll(delta) => self.on_mouse_scroll(*delta, manager),
+            DispatchEvent::Composition(composition) => self.on_composition(composition, manager),
+            DispatchEvent::ContextMenu => self.on_context_menu(manager)
+        };
+
+        if result.is_consumed() {
+            context.stop_propagation();
+        }
+        result.as_normal_result()
+    }
+
+    /// Gets a mutable reference to the shortcuts that are bound in this layer, for instance to rebind them at runtime
+    fn get_shortcuts_mut(&mut self) -> Option<&mut ShortcutRegistry> {
+        None
+    }
 }
 
 pub trait LayerAgent {
 
-    fn claim_render_space(&mut self, region: Region, trigger: RenderTrigger, opacity: RenderOpacity, phase: RenderPhase) -> Result<(),()>;
+    /// Sets the z-order of the claims of the behavior. This only has effect in layers that allow overlapping claims, like a
+    /// z-ordered SimpleLayer: the claims with the highest z-order are on top. The default z-order is 0.
+    fn set_z_order(&mut self, _z: i32) {}
+
+    /// Checks whether the given shape could be claimed for the given kind of space, without claiming it
+    fn check_claim(&self, kind: ClaimKind, shape: &HitShape) -> Result<(),ClaimError>;
+
+    fn is_space_free(&self, kind: ClaimKind, region: Region) -> bool {
+        self.check_claim(kind, &HitShape::Rect(region)).is_ok()
+    }
+
+    fn claim_render_space(&mut self, region: Region, trigger: RenderTrigger, opacity: RenderOpacity, phase: RenderPhase) -> Result<(),ClaimError>;
+
+    fn claim_key_down_shape(&mut self, shape: HitShape) -> Result<(),ClaimError>;
+
+    fn claim_key_up_shape(&mut self, shape: HitShape) -> Result<(),ClaimError>;
 
-    fn claim_key_down_space(&mut self, region: Region) -> Result<(),()>;
+    fn claim_key_listen_shape(&mut self, shape: HitShape) -> Result<(),ClaimError>;
 
-    fn claim_key_up_space(&mut self, region: Region) -> Result<(),()>;
+    fn claim_key_down_space(&mut self, region: Region) -> Result<(),ClaimError> {
+        self.claim_key_down_shape(HitShape::Rect(region))
+    }
 
-    fn claim_key_listen_space(&mut self, region: Region) -> Result<(),()>;
+    fn claim_key_up_space(&mut self, region: Region) -> Result<(),ClaimError> {
+        self.claim_key_up_shape(HitShape::Rect(region))
+    }
+
+    fn claim_key_listen_space(&mut self, region: Region) -> Result<(),ClaimError> {
+        self.claim_key_listen_shape(HitShape::Rect(region))
+    }
 
     fn make_key_down_listener(&mut self, priority: i8);
 
@@ -68,20 +184,44 @@ pub trait LayerAgent {
 
     fn make_key_listener(&mut self, priority: i8);
 
-    fn claim_mouse_click_space(&mut self, region: Region) -> Result<(),()>;
+    /// Claims the mouse click space of the given shape. Claims fail when the bounds of the shape overlap with the bounds of
+    /// the shape of another claim, but only clicks inside the shape itself will be passed to mouse_click_inside.
+    fn claim_mouse_click_shape(&mut self, shape: HitShape) -> Result<(),ClaimError>;
+
+    fn claim_mouse_scroll_shape(&mut self, shape: HitShape) -> Result<(),ClaimError>;
+
+    fn claim_mouse_click_space(&mut self, region: Region) -> Result<(),ClaimError> {
+        self.claim_mouse_click_shape(HitShape::Rect(region))
+    }
 
-    fn claim_mouse_scroll_space(&mut self, region: Region) -> Result<(),()>;
+    fn claim_mouse_scroll_space(&mut self, region: Region) -> Result<(),ClaimError> {
+        self.claim_mouse_scroll_shape(HitShape::Rect(region))
+    }
 
     fn make_mouse_scroll_listener(&mut self, priority: i8);
 
-    fn claim_mouse_move_space(&mut self, region: Region);
+    fn claim_mouse_move_shape(&mut self, shape: HitShape);
+
+    fn claim_mouse_in_out_shape(&mut self, shape: HitShape);
+
+    fn claim_mouse_move_space(&mut self, region: Region) {
+        self.claim_mouse_move_shape(HitShape::Rect(region));
+    }
 
-    fn claim_mouse_in_out_space(&mut self, region: Region);
+    fn claim_mouse_in_out_space(&mut self, region: Region) {
+        self.claim_mouse_in_out_shape(HitShape::Rect(region));
+    }
 
     fn make_mouse_move_listener(&mut self);
 
     fn make_mouse_click_listener(&mut self);
 
+    fn make_composition_listener(&mut self, priority: i8);
+
+    /// Lets the behavior receive the capture phase of all events, before the other listeners of the layer and the layers in front of
+    /// this layer get them. This only has effect when the container uses DispatchMode::CaptureBubble.
+    fn make_capture_listener(&mut self, priority: i8);
+
     fn make_copy_listener(&mut self, priority: i8);
 
     fn make_paste_listener(&mut self, priority: i8);
@@ -89,4 +229,16 @@ pub trait LayerAgent {
     fn make_cut_listener(&mut self, priority: i8);
 
     fn make_update_listener(&mut self);
+
+    /// Like make_update_listener, but the container will also keep updating when nothing else happens if the manager uses
+    /// LoopMode::OnDemand. Behaviors that need to check something every frame should use this.
+    fn make_continuous_update_listener(&mut self);
+
+    /// Shows the given tooltip when the mouse hovers over the given region for a while. The tooltip will be drawn by the TooltipLayer
+    /// of the container, so the container needs to have one.
+    fn claim_tooltip_space(&mut self, region: Region, tooltip: Rc<Tooltip>);
+
+    /// Binds the action to the given chord in this layer. When the user completes the chord, the shortcut method of the behavior
+    /// will be called. This will fail if the action is already bound or the chord conflicts with another shortcut of the layer.
+    fn claim_shortcut(&mut self, action: &str, chord: KeyChord) -> Result<(),ClaimError>;
 }
\ No newline at end of file
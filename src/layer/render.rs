@@ -2,7 +2,7 @@ use crate::*;
 
 use wasmuri_core::Region;
 
-#[derive(Clone,Copy)]
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
 pub enum RenderTrigger {
 
     Request,
@@ -12,12 +12,35 @@ pub enum RenderTrigger {
     Always
 }
 
-#[derive(PartialEq,Eq,PartialOrd,Ord,Clone,Copy)]
-pub enum RenderPhase {
+/// Determines the order in which the components of a layer render and which GL setup they need. Components render in order of
+/// ascending phase order. Besides the built-in phases, custom phases can be created with RenderPhase::new and their setup and
+/// teardown can be registered with the RenderPhaseRegistry of the ContainerManager.
+#[derive(PartialEq,Eq,PartialOrd,Ord,Clone,Copy,Hash,Debug)]
+pub struct RenderPhase {
+
+    order: i32
+}
+
+#[allow(non_upper_case_globals)]
+impl RenderPhase {
+
+    /// For components that don't need any special preparation, like components that only draw quads
+    pub const Start: RenderPhase = RenderPhase::new(0);
 
-    Start,
-    Text,
-    End
+    /// For components that draw text with the TextRenderer of the manager
+    pub const Text: RenderPhase = RenderPhase::new(1000);
+
+    pub const End: RenderPhase = RenderPhase::new(2000);
+
+    pub const fn new(order: i32) -> RenderPhase {
+        RenderPhase {
+            order
+        }
+    }
+
+    pub fn get_order(&self) -> i32 {
+        self.order
+    }
 }
 
 #[derive(Clone,Copy,PartialEq,Eq,Debug)]
@@ -66,20 +89,45 @@ impl PlannedRenderAction {
 #[derive(Clone,Copy,PartialEq,Eq,Debug)]
 pub struct PassedRenderAction {
 
-    region: Region
+    region: Region,
+
+    /// The exact shape of what was drawn, if it is not the entire region
+    shape: Option<RenderShape>
 }
 
 impl PassedRenderAction {
 
     pub fn new(region: Region) -> Self {
         Self {
-            region
+            region,
+            shape: None
+        }
+    }
+
+    /// Creates a render action for a component that only drew inside the given shape. This is useful for components with the
+    /// DynamicSolidOrNothing opacity: the layers behind it will still receive mouse events outside the shape.
+    pub fn with_shape(shape: RenderShape) -> Self {
+        Self {
+            region: shape.get_bounds(),
+            shape: Some(shape)
         }
     }
 
     pub fn get_region(&self) -> Region {
         self.region
     }
+
+    pub fn get_shape(&self) -> Option<RenderShape> {
+        self.shape
+    }
+
+    /// Checks if the given point is inside the shape of this render action, or inside its region if it doesn't have a shape
+    pub fn contains(&self, point: (f32, f32)) -> bool {
+        match &self.shape {
+            Some(shape) => shape.contains(point),
+            None => self.region.is_float_inside(point)
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -0,0 +1,50 @@
+use crate::*;
+
+use std::cell::RefCell;
+use std::rc::Weak;
+
+use wasmuri_core::*;
+
+pub struct CaptureManager {
+
+    listeners: WeakMetaVec<dyn ComponentBehavior, i8>
+}
+
+impl CaptureManager {
+
+    pub fn new() -> CaptureManager {
+        CaptureManager {
+            listeners: WeakMetaVec::new()
+        }
+    }
+
+    pub fn snapshot_listeners(&self, snapshot: &mut LayerSnapshot) {
+        super::snapshot_listeners(&self.listeners, snapshot, |priority| ListenerSnapshot::new("capture").with_priority(*priority));
+    }
+
+    pub fn add_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, priority: i8) {
+        let maybe_index = self.listeners.vec.binary_search_by(|existing| {
+
+            // Intentionally INVERT the order so that the higher priorities come first
+            priority.cmp(&existing.metadata)
+        });
+
+        let index;
+        match maybe_index {
+            Ok(the_index) => index = the_index,
+            Err(the_index) => index = the_index
+        };
+        self.listeners.vec.insert(index, WeakMetaHandle {
+            weak_cell: behavior,
+            metadata: priority
+        });
+    }
+
+    pub fn fire_capture(&mut self, event: &DispatchEvent, context: &mut DispatchContext, manager: &ContainerManager) {
+        self.listeners.for_each_mut(|behavior, _prio| {
+            if !context.is_propagation_stopped() {
+                behavior.capture(&mut CaptureParams::new(event, context, manager));
+            }
+        });
+    }
+}
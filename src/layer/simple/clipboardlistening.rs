@@ -24,6 +24,12 @@ impl ClipboardManager {
         }
     }
 
+    pub fn snapshot_listeners(&self, snapshot: &mut LayerSnapshot) {
+        super::snapshot_listeners(&self.copy_listeners, snapshot, |priority| ListenerSnapshot::new("copy").with_priority(*priority));
+        super::snapshot_listeners(&self.paste_listeners, snapshot, |priority| ListenerSnapshot::new("paste").with_priority(*priority));
+        super::snapshot_listeners(&self.cut_listeners, snapshot, |priority| ListenerSnapshot::new("cut").with_priority(*priority));
+    }
+
     fn add_meta_listener(list: &mut WeakMetaVec<dyn ComponentBehavior, i8>, behavior: Weak<RefCell<dyn ComponentBehavior>>, priority: i8) {
         let maybe_index = list.vec.binary_search_by(|existing| {
 
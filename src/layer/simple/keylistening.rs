@@ -7,8 +7,8 @@ use wasmuri_core::*;
 
 pub struct KeyListenManager {
     
-    hover_down_listeners: WeakMetaVec<dyn ComponentBehavior, Region>,
-    hover_up_listeners: WeakMetaVec<dyn ComponentBehavior, Region>,
+    hover_down_listeners: WeakMetaVec<dyn ComponentBehavior, (HitShape, i32)>,
+    hover_up_listeners: WeakMetaVec<dyn ComponentBehavior, (HitShape, i32)>,
 
     full_down_listeners: WeakMetaVec<dyn ComponentBehavior, i8>,
     full_up_listeners: WeakMetaVec<dyn ComponentBehavior, i8>
@@ -26,34 +26,35 @@ impl KeyListenManager {
         }
     }
 
-    pub fn can_claim_down(&self, region: Region) -> bool {
-        for handle in &self.hover_down_listeners.vec {
-            if handle.metadata.intersects_with(region) {
-                return false;
-            }
-        }
+    pub fn snapshot_listeners(&self, snapshot: &mut LayerSnapshot) {
+        super::snapshot_listeners(&self.hover_down_listeners, snapshot, |meta| ListenerSnapshot::new("key_down").with_region(meta.0.get_bounds()).with_z(meta.1));
+        super::snapshot_listeners(&self.hover_up_listeners, snapshot, |meta| ListenerSnapshot::new("key_up").with_region(meta.0.get_bounds()).with_z(meta.1));
+        super::snapshot_listeners(&self.full_down_listeners, snapshot, |priority| ListenerSnapshot::new("key_down").with_priority(*priority));
+        super::snapshot_listeners(&self.full_up_listeners, snapshot, |priority| ListenerSnapshot::new("key_up").with_priority(*priority));
+    }
 
-        true
+    pub fn check_claim_down(&self, shape: &HitShape) -> Result<(),ClaimError> {
+        super::check_free(&self.hover_down_listeners, ClaimKind::KeyDown, shape, |meta| meta.0.get_bounds())
     }
 
-    pub fn can_claim_up(&self, region: Region) -> bool {
-        for handle in &self.hover_up_listeners.vec {
-            if handle.metadata.intersects_with(region) {
-                return false;
-            }
-        }
+    pub fn check_claim_up(&self, shape: &HitShape) -> Result<(),ClaimError> {
+        super::check_free(&self.hover_up_listeners, ClaimKind::KeyUp, shape, |meta| meta.0.get_bounds())
+    }
 
-        true
+    /// Adds the key down and key up spaces of the listeners to claims
+    pub fn collect_claims(&self, claims: &mut Vec<ClaimOutline>) {
+        super::collect_claims(&self.hover_down_listeners, ClaimKind::KeyDown, claims, |meta| meta.0.get_bounds());
+        super::collect_claims(&self.hover_up_listeners, ClaimKind::KeyUp, claims, |meta| meta.0.get_bounds());
     }
 
-    /// Should only be used after can_claim_down confirmed that the given region is available
-    pub fn add_region_key_down_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, region: Region){
-        self.hover_down_listeners.push(behavior, region);
+    /// Should only be used after check_claim_down confirmed that the given region is available
+    pub fn add_region_key_down_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, shape: HitShape, z: i32){
+        super::insert_by_z(&mut self.hover_down_listeners, behavior, shape, z);
     }
 
-    /// Should only be used after can_claim_up confirmed that the given region is available
-    pub fn add_region_key_up_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, region: Region){
-        self.hover_up_listeners.push(behavior, region);
+    /// Should only be used after check_claim_up confirmed that the given region is available
+    pub fn add_region_key_up_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, shape: HitShape, z: i32){
+        super::insert_by_z(&mut self.hover_up_listeners, behavior, shape, z);
     }
 
     pub fn add_global_key_down_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, priority: i8){
@@ -95,15 +96,15 @@ impl KeyListenManager {
     }
 
     fn fire<F: FnMut(&mut dyn ComponentBehavior, &ContainerManager) -> bool>(
-            hover_listeners: &mut WeakMetaVec<dyn ComponentBehavior, Region>, full_listeners: &mut WeakMetaVec<dyn ComponentBehavior, i8>, 
+            hover_listeners: &mut WeakMetaVec<dyn ComponentBehavior, (HitShape, i32)>, full_listeners: &mut WeakMetaVec<dyn ComponentBehavior, i8>, 
             mut processor: F, manager: &ContainerManager, mouse_pos: Option<(f32, f32)>) -> bool {
 
         // The key listeners with a location have priority over those without bound location
         let mut consumed = false;
 
         if mouse_pos.is_some() {
-            hover_listeners.for_each_mut(|behavior, region| {
-                if !consumed && region.is_float_inside(mouse_pos.unwrap()) {
+            hover_listeners.for_each_mut(|behavior, meta| {
+                if !consumed && meta.0.contains(mouse_pos.unwrap()) {
                     consumed = processor(behavior, manager);
                 } 
             });
@@ -5,12 +5,20 @@ mod render;
 mod keylistening;
 mod mouselistening;
 mod clipboardlistening;
+mod shortcutlistening;
+mod compositionlistening;
+mod capturelistening;
+mod tooltips;
 
 use render::RenderManager;
 use update::UpdateManager;
 use keylistening::KeyListenManager;
 use mouselistening::MouseManager;
 use clipboardlistening::*;
+use shortcutlistening::ShortcutManager;
+use compositionlistening::CompositionManager;
+use capturelistening::CaptureManager;
+use tooltips::TooltipManager;
 
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -19,6 +27,65 @@ use wasmuri_core::*;
 
 use web_sys::WebGlRenderingContext;
 
+/// Checks that the given shape doesn't overlap with the space of any of the listeners. The bounds of the space of a listener are
+/// obtained with get_bounds.
+fn check_free<T, F: Fn(&T) -> Region>(list: &WeakMetaVec<dyn ComponentBehavior, T>, kind: ClaimKind, shape: &HitShape, get_bounds: F) -> Result<(),ClaimError> {
+    let region = shape.get_bounds();
+    for handle in &list.vec {
+        let conflicting_region = get_bounds(&handle.metadata);
+        if conflicting_region.intersects_with(region) {
+            return Err(ClaimError::occupied(kind, region, conflicting_region, get_component_key(&handle.weak_cell)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds the bounds of the space of every listener in the list to claims, for the inspector
+fn collect_claims<T, F: Fn(&T) -> Region>(list: &WeakMetaVec<dyn ComponentBehavior, T>, kind: ClaimKind, claims: &mut Vec<ClaimOutline>, get_bounds: F) {
+    for handle in &list.vec {
+        claims.push(ClaimOutline::new(kind, get_bounds(&handle.metadata), get_component_key(&handle.weak_cell)));
+    }
+}
+
+/// Adds a snapshot of every listener in the list to the layer snapshot, for the introspection API
+fn snapshot_listeners<T, F: Fn(&T) -> ListenerSnapshot>(list: &WeakMetaVec<dyn ComponentBehavior, T>, snapshot: &mut LayerSnapshot, to_snapshot: F) {
+    for handle in &list.vec {
+        snapshot.add_listener(get_component_key(&handle.weak_cell), to_snapshot(&handle.metadata));
+    }
+}
+
+/// Like snapshot_listeners, but for lists of listeners without metadata
+fn snapshot_plain_listeners(list: &mut WeakVec<dyn ComponentBehavior>, snapshot: &mut LayerSnapshot, kind: &'static str) {
+    list.for_each_mut(|behavior| {
+        snapshot.add_listener(get_behavior_key(&*behavior), ListenerSnapshot::new(kind));
+    });
+}
+
+fn get_behavior_key(behavior: &dyn ComponentBehavior) -> Option<ComponentKey> {
+    let agent = behavior.get_agent().upgrade()?;
+    let agent = agent.try_borrow().ok()?;
+    Some(agent.get_key())
+}
+
+fn get_component_key(behavior: &std::rc::Weak<RefCell<dyn ComponentBehavior>>) -> Option<ComponentKey> {
+    let behavior = behavior.upgrade()?;
+    let behavior = behavior.try_borrow().ok()?;
+    let agent = behavior.get_agent().upgrade()?;
+    let key = agent.try_borrow().ok()?.get_key();
+    Some(key)
+}
+
+/// Inserts the listener such that the listeners with the highest z-order come first. Listeners with the same z-order are ordered
+/// by insertion time, with the most recent listener first.
+fn insert_by_z<T>(list: &mut WeakMetaVec<dyn ComponentBehavior, (T, i32)>, behavior: std::rc::Weak<RefCell<dyn ComponentBehavior>>, value: T, z: i32) {
+    let index = list.vec.iter().position(|existing| existing.metadata.1 <= z).unwrap_or(list.vec.len());
+    list.vec.insert(index, WeakMetaHandle {
+        weak_cell: behavior,
+        metadata: (value, z)
+    });
+}
+
 pub struct SimpleLayer {
     
     components: Vec<OuterHandle>,
@@ -26,11 +93,17 @@ pub struct SimpleLayer {
     key_manager: KeyListenManager,
     mouse_manager: MouseManager,
     clipboard_manager: ClipboardManager,
+    shortcut_manager: ShortcutManager,
+    composition_manager: CompositionManager,
+    capture_manager: CaptureManager,
+    tooltip_manager: TooltipManager,
     update_manager: UpdateManager,
     render_manager: RenderManager,
 
     mouse_pos: Option<(f32, f32)>,
-    last_render_actions: Vec<PassedRenderAction>
+    last_render_actions: Vec<PassedRenderAction>,
+
+    z_ordered: bool
 }
 
 impl SimpleLayer {
@@ -42,11 +115,46 @@ impl SimpleLayer {
             render_manager: RenderManager::new(background_color),
             update_manager: UpdateManager::new(),
             clipboard_manager: ClipboardManager::new(),
+            shortcut_manager: ShortcutManager::new(),
+            composition_manager: CompositionManager::new(),
+            capture_manager: CaptureManager::new(),
+            tooltip_manager: TooltipManager::new(),
             key_manager: KeyListenManager::new(),
             mouse_manager: MouseManager::new(),
 
             mouse_pos: None,
-            last_render_actions: Vec::new()
+            last_render_actions: Vec::new(),
+
+            z_ordered: false
+        }
+    }
+
+    /// Creates a SimpleLayer whose background color is the background color of the palette of the current theme
+    pub fn themed() -> SimpleLayer {
+        let mut layer = Self::new(None);
+        layer.render_manager = RenderManager::themed();
+        layer
+    }
+
+    /// Allows the render, mouse and key claims of the components in this layer to overlap. Components can use
+    /// LayerAgent::set_z_order to determine which component is on top: components with a higher z-order render later and the
+    /// topmost component at the mouse position gets the mouse clicks.
+    pub fn z_ordered(mut self) -> SimpleLayer {
+        self.z_ordered = true;
+        self.render_manager.set_z_ordered(true);
+        self
+    }
+
+    pub fn is_z_ordered(&self) -> bool {
+        self.z_ordered
+    }
+
+    /// If we rendered something at the mouse position, we assume that the user is pointing at this layer and therefore not at the
+    /// layers behind it
+    fn is_rendered_at(&self, mouse_pos: Option<(f32, f32)>) -> bool {
+        match mouse_pos {
+            Some(mouse_pos) => self.last_render_actions.iter().any(|action| action.contains(mouse_pos)),
+            None => false
         }
     }
 
@@ -75,6 +183,8 @@ impl SimpleLayer {
                 }
 
                 if agent.did_request_removal() {
+                    agent.cancel_animations();
+                    agent.cancel_timers();
                     return true;
                 }
 
@@ -101,28 +211,26 @@ impl Layer for SimpleLayer {
     fn on_mouse_move(&mut self, next_mouse_pos: Option<(f32, f32)>, manager: &ContainerManager) -> ConsumableEventResult {
         self.mouse_manager.fire_mouse_move(self.mouse_pos, next_mouse_pos, manager);
         self.render_manager.on_mouse_move(self.mouse_pos, next_mouse_pos);
+        self.tooltip_manager.on_mouse_move(next_mouse_pos, manager);
 
         self.mouse_pos = next_mouse_pos;
 
-        // If we rendered something at the mouse position, we assume that the user clicked on this layer and therefore not on the layers behind
-        let mut move_result = false;
-        if next_mouse_pos.is_some() {
-            for render_action in &self.last_render_actions {
-                if render_action.get_region().is_float_inside(next_mouse_pos.unwrap()) {
-                    move_result = true;
-                    break;
-                }
-            }
-        }
-
+        let move_result = self.is_rendered_at(next_mouse_pos);
         self.consumable_result(move_result)
     }
 
-    fn on_mouse_click(&mut self, click: ClickInfo, manager: &ContainerManager) -> EventResult {
+    fn on_mouse_click(&mut self, click: ClickInfo, manager: &ContainerManager) -> ConsumableEventResult {
         match self.mouse_pos {
             Some(mouse_pos) => self.mouse_manager.fire_mouse_click(manager, mouse_pos, click), 
             None => self.mouse_manager.fire_mouse_click_outside(manager, click)
         };
+
+        let click_result = self.is_rendered_at(self.mouse_pos);
+        self.consumable_result(click_result)
+    }
+
+    fn on_mouse_click_outside(&mut self, click: ClickInfo, manager: &ContainerManager) -> EventResult {
+        self.mouse_manager.fire_mouse_click_outside(manager, click);
         self.check_agents()
     }
 
@@ -132,6 +240,12 @@ impl Layer for SimpleLayer {
     }
 
     fn on_key_down(&mut self, keys: &KeyInfo, manager: &ContainerManager) -> ConsumableEventResult {
+
+        // Shortcuts get priority over the normal key listeners, but the keys of an IME composition belong to the text input
+        if !keys.is_composing() && self.shortcut_manager.fire_key_down(keys, manager) {
+            return self.consumable_result(true);
+        }
+
         let key_down_result = self.key_manager.fire_key_down(keys, manager, self.mouse_pos);
         self.consumable_result(key_down_result)
     }
@@ -141,6 +255,30 @@ impl Layer for SimpleLayer {
         self.consumable_result(key_up_result)
     }
 
+    fn on_composition(&mut self, composition: &CompositionInfo, manager: &ContainerManager) -> ConsumableEventResult {
+        let composition_result = self.composition_manager.fire_composition(composition, manager);
+        self.consumable_result(composition_result)
+    }
+
+    fn on_context_menu(&mut self, manager: &ContainerManager) -> ConsumableEventResult {
+        let mouse_pos = match self.mouse_pos {
+            Some(mouse_pos) => mouse_pos,
+            None => return self.consumable_result(false)
+        };
+
+        if let Some(behavior) = self.mouse_manager.find_click_listener(mouse_pos) {
+            let maybe_menu = behavior.borrow_mut().context_menu(&mut ContextMenuParams::new(mouse_pos, manager));
+            if let Some(menu) = maybe_menu {
+                manager.get_context_menu().borrow_mut().open(menu, mouse_pos, Rc::downgrade(&behavior));
+                return self.consumable_result(true);
+            }
+        }
+
+        // The layers behind this layer shouldn't open a menu for something this layer rendered over
+        let rendered_at_mouse = self.is_rendered_at(Some(mouse_pos));
+        self.consumable_result(rendered_at_mouse)
+    }
+
     fn on_copy(&mut self) -> Option<ClipboardData> {
         self.clipboard_manager.fire_copy_event()
     }
@@ -154,6 +292,24 @@ impl Layer for SimpleLayer {
     }
 
     fn on_update(&mut self, manager: &ContainerManager) -> EventResult {
+
+        // The callbacks are called after the loop, because they might want to borrow the agents
+        let delta = manager.get_update_frame().get_delta();
+        let mut animation_callbacks = Vec::new();
+        let mut timer_callbacks = Vec::new();
+        for outer_handle in &self.components {
+            let mut handle = outer_handle.get_rc().borrow_mut();
+            let mut agent = handle.get_agent();
+            timer_callbacks.append(&mut agent.advance_timers(delta));
+            animation_callbacks.append(&mut agent.advance_animations(delta));
+        }
+        for callback in timer_callbacks {
+            (*callback.borrow_mut())();
+        }
+        for callback in animation_callbacks {
+            callback();
+        }
+
         self.update_manager.fire_update(manager);
 
         self.check_agents()
@@ -182,12 +338,104 @@ impl Layer for SimpleLayer {
         self.render_manager.force_full_render();
     }
 
+    fn wants_update(&mut self) -> bool {
+        if self.update_manager.has_continuous_listeners() {
+            return true;
+        }
+
+        // Animations and timers are advanced during the updates
+        self.components.iter().any(|outer_handle| {
+            let mut handle = outer_handle.get_rc().borrow_mut();
+            let agent = handle.get_agent();
+            agent.has_animations() || agent.has_timers()
+        })
+    }
+
+    fn wants_render(&mut self) -> bool {
+        self.render_manager.has_pending_render()
+    }
+
+    fn inspect(&mut self) -> Option<LayerInspection> {
+        let mut claims = Vec::new();
+        self.render_manager.collect_claims(&mut claims);
+        self.mouse_manager.collect_claims(&mut claims);
+        self.key_manager.collect_claims(&mut claims);
+
+        let passed_regions = self.last_render_actions.iter().map(|action| action.get_region()).collect();
+        Some(LayerInspection::new(claims, passed_regions))
+    }
+
+    fn snapshot(&mut self) -> LayerSnapshot {
+        let mut snapshot = LayerSnapshot::new("SimpleLayer");
+        for outer_handle in &self.components {
+            let mut handle = outer_handle.get_rc().borrow_mut();
+            let agent = handle.get_agent();
+            snapshot.add_component(ComponentSnapshot::new(agent.get_key(), agent.did_request_render(), agent.has_animations(), agent.has_timers()));
+        }
+
+        self.render_manager.snapshot_claims(&mut snapshot);
+        self.key_manager.snapshot_listeners(&mut snapshot);
+        self.mouse_manager.snapshot_listeners(&mut snapshot);
+        self.clipboard_manager.snapshot_listeners(&mut snapshot);
+        self.shortcut_manager.snapshot_listeners(&mut snapshot);
+        self.composition_manager.snapshot_listeners(&mut snapshot);
+        self.capture_manager.snapshot_listeners(&mut snapshot);
+        self.tooltip_manager.snapshot_listeners(&mut snapshot);
+        self.update_manager.snapshot_listeners(&mut snapshot);
+        snapshot
+    }
+
+    fn collect_access_nodes(&mut self, nodes: &mut Vec<(ComponentKey, AccessNode)>) {
+        let mut layer_nodes = Vec::new();
+        for outer_handle in &self.components {
+            let mut handle = outer_handle.get_rc().borrow_mut();
+            let maybe_node = handle.get_behaviors().iter().filter_map(|behavior| behavior.borrow().get_access_node()).next();
+            if let Some(node) = maybe_node {
+                layer_nodes.push((handle.get_agent().get_key(), node));
+            }
+        }
+
+        // Screen readers read the nodes in order, so they should be ordered like text: from top to bottom and left to right
+        layer_nodes.sort_by(|(_key_a, a), (_key_b, b)| {
+            let region_a = a.get_region();
+            let region_b = b.get_region();
+            region_b.get_max_y().partial_cmp(&region_a.get_max_y()).unwrap_or(std::cmp::Ordering::Equal)
+                    .then(region_a.get_min_x().partial_cmp(&region_b.get_min_x()).unwrap_or(std::cmp::Ordering::Equal))
+        });
+        nodes.append(&mut layer_nodes);
+    }
+
+    fn on_access_action(&mut self, key: ComponentKey, action: AccessAction, manager: &ContainerManager) -> ConsumableEventResult {
+        let maybe_behaviors = self.components.iter().find_map(|outer_handle| {
+            let mut handle = outer_handle.get_rc().borrow_mut();
+            if handle.get_agent().get_key() == key {
+                Some(handle.get_behaviors().clone())
+            } else {
+                None
+            }
+        });
+
+        match maybe_behaviors {
+            Some(behaviors) => {
+                for behavior in behaviors {
+                    behavior.borrow_mut().access_action(&mut AccessActionParams::new(action, manager));
+                }
+                self.consumable_result(true)
+            }, None => ConsumableEventResult::dont_consume()
+        }
+    }
+
     fn add_component(&mut self, component: Rc<RefCell<dyn Component>>) {
+
+        // The shortcuts of removed components shouldn't prevent the new component from claiming them
+        self.shortcut_manager.prune_dead_listeners();
+
         let behaviors = component.borrow_mut().create_behaviors();
         for behavior in &behaviors {
             let mut agent = SimpleLayerAgent::new(self);
             behavior.borrow_mut().attach(&mut agent);
 
+            let z = agent.z;
             let render_handle = agent.render_handle;
 
             let key_down_space = agent.key_down_space;
@@ -203,27 +451,33 @@ impl Layer for SimpleLayer {
             let mouse_move_in_out_space = agent.mouse_move_in_out_space;
             let mouse_move_global = agent.mouse_move_global;
 
+            let composition_priority = agent.composition_priority;
+            let capture_priority = agent.capture_priority;
             let copy_priority = agent.copy_priority;
             let paste_priority = agent.paste_priority;
             let cut_priority = agent.cut_priority;
 
             let receive_updates = agent.receive_updates;
+            let continuous_updates = agent.continuous_updates;
+
+            let shortcuts = agent.shortcuts;
+            let tooltips = agent.tooltips;
 
             match render_handle {
                 Some(render_handle) => {
-                    self.render_manager.claim_space(render_handle.0, render_handle.1, render_handle.2, render_handle.3, Rc::downgrade(&behavior));
+                    self.render_manager.claim_space(render_handle.0, render_handle.1, render_handle.2, render_handle.3, z, Rc::downgrade(&behavior));
                 }, None => {}
             };
 
             match key_down_space {
                 Some(region) => {
-                    self.key_manager.add_region_key_down_listener(Rc::downgrade(&behavior), region);
+                    self.key_manager.add_region_key_down_listener(Rc::downgrade(&behavior), region, z);
                 }, None => {}
             };
 
             match key_up_space {
                 Some(region) => {
-                    self.key_manager.add_region_key_up_listener(Rc::downgrade(&behavior), region);
+                    self.key_manager.add_region_key_up_listener(Rc::downgrade(&behavior), region, z);
                 }, None => {}
             };
 
@@ -241,7 +495,7 @@ impl Layer for SimpleLayer {
 
             match mouse_click_space {
                 Some(space) => {
-                    self.mouse_manager.add_click_space_listener(Rc::downgrade(&behavior), space);
+                    self.mouse_manager.add_click_space_listener(Rc::downgrade(&behavior), space, z);
                 }, None => {}
             };
             
@@ -251,7 +505,7 @@ impl Layer for SimpleLayer {
 
             match mouse_scroll_space {
                 Some(space) => {
-                    self.mouse_manager.add_scroll_space_listener(Rc::downgrade(&behavior), space);
+                    self.mouse_manager.add_scroll_space_listener(Rc::downgrade(&behavior), space, z);
                 }, None => {}
             };
 
@@ -263,16 +517,24 @@ impl Layer for SimpleLayer {
 
             match mouse_move_space {
                 Some(space) => {
-                    self.mouse_manager.add_move_space_listener(Rc::downgrade(&behavior), space);
+                    self.mouse_manager.add_move_space_listener(Rc::downgrade(&behavior), space, z);
                 }, None => {}
             };
 
             match mouse_move_in_out_space {
                 Some(space) => {
-                    self.mouse_manager.add_in_out_move_listener(Rc::downgrade(&behavior), space);
+                    self.mouse_manager.add_in_out_move_listener(Rc::downgrade(&behavior), space, z);
                 }, None => {}
             };
 
+            if composition_priority.is_some() {
+                self.composition_manager.add_listener(Rc::downgrade(&behavior), composition_priority.unwrap());
+            }
+
+            if capture_priority.is_some() {
+                self.capture_manager.add_listener(Rc::downgrade(&behavior), capture_priority.unwrap());
+            }
+
             if copy_priority.is_some() {
                 self.clipboard_manager.add_copy_listener(Rc::downgrade(&behavior), copy_priority.unwrap());
             }
@@ -289,42 +551,74 @@ impl Layer for SimpleLayer {
                 self.mouse_manager.add_full_move_listener(Rc::downgrade(&behavior));
             }
 
-            if receive_updates {
+            if continuous_updates {
+                self.update_manager.add_continuous_listener(Rc::downgrade(&behavior));
+            } else if receive_updates {
                 self.update_manager.add_listener(Rc::downgrade(&behavior));
             }
+
+            for (region, tooltip) in tooltips {
+                self.tooltip_manager.add_tooltip(Rc::downgrade(&behavior), region, tooltip);
+            }
+
+            for (action, chord) in shortcuts {
+                self.shortcut_manager.add_listener(Rc::downgrade(&behavior), action, chord);
+            }
         }
 
         self.components.push(OuterHandle::new(component, behaviors));
     }
+
+    fn on_capture(&mut self, event: &DispatchEvent, context: &mut DispatchContext, manager: &ContainerManager) -> EventResult {
+        self.capture_manager.fire_capture(event, context, manager);
+        self.check_agents()
+    }
+
+    fn get_shortcuts(&self) -> Option<&ShortcutRegistry> {
+        Some(self.shortcut_manager.get_registry())
+    }
+
+    fn get_shortcuts_mut(&mut self) -> Option<&mut ShortcutRegistry> {
+        Some(self.shortcut_manager.get_registry_mut())
+    }
 }
 
 pub struct SimpleLayerAgent<'a> {
 
     layer: &'a SimpleLayer,
 
+    z: i32,
+
     render_handle: Option<(Region,RenderTrigger,RenderPhase,RenderOpacity)>,
 
-    key_down_space: Option<Region>,
-    key_up_space: Option<Region>,
+    key_down_space: Option<HitShape>,
+    key_up_space: Option<HitShape>,
 
     key_down_priority: Option<i8>,
     key_up_priority: Option<i8>,
 
-    mouse_click_space: Option<Region>,
+    mouse_click_space: Option<HitShape>,
     mouse_click_global: bool,
 
-    mouse_scroll_space: Option<Region>,
+    mouse_scroll_space: Option<HitShape>,
     mouse_scroll_priority: Option<i8>,
 
+    composition_priority: Option<i8>,
+    capture_priority: Option<i8>,
     copy_priority: Option<i8>,
     paste_priority: Option<i8>,
     cut_priority: Option<i8>,
 
-    mouse_move_space: Option<Region>,
-    mouse_move_in_out_space: Option<Region>,
+    mouse_move_space: Option<HitShape>,
+    mouse_move_in_out_space: Option<HitShape>,
     mouse_move_global: bool,
 
-    receive_updates: bool
+    receive_updates: bool,
+    continuous_updates: bool,
+
+    shortcuts: Vec<(String,KeyChord)>,
+
+    tooltips: Vec<(Region,Rc<Tooltip>)>
 }
 
 impl<'a> SimpleLayerAgent<'a> {
@@ -333,6 +627,8 @@ impl<'a> SimpleLayerAgent<'a> {
         SimpleLayerAgent {
             layer,
 
+            z: 0,
+
             render_handle: None,
 
             key_down_space: None,
@@ -351,54 +647,74 @@ impl<'a> SimpleLayerAgent<'a> {
             mouse_move_in_out_space: None,
             mouse_move_global: false,
 
+            composition_priority: None,
+            capture_priority: None,
             copy_priority: None,
             paste_priority: None,
             cut_priority: None,
 
-            receive_updates: false
+            receive_updates: false,
+            continuous_updates: false,
+
+            shortcuts: Vec::new(),
+
+            tooltips: Vec::new()
         }
     }
 }
 
 impl<'a> LayerAgent for SimpleLayerAgent<'a> {
 
-    fn claim_render_space(&mut self, region: Region, trigger: RenderTrigger, opacity: RenderOpacity, phase: RenderPhase) -> Result<(),()> {
+    fn set_z_order(&mut self, z: i32) {
+        self.z = z;
+    }
+
+    fn check_claim(&self, kind: ClaimKind, shape: &HitShape) -> Result<(),ClaimError> {
 
-        if !self.layer.render_manager.can_claim(region) {
-            return Err(());
+        // In z-ordered layers, claims are allowed to overlap
+        if self.layer.z_ordered {
+            return Ok(());
         }
 
+        match kind {
+            ClaimKind::Render => self.layer.render_manager.check_claim(shape),
+            ClaimKind::KeyDown => self.layer.key_manager.check_claim_down(shape),
+            ClaimKind::KeyUp => self.layer.key_manager.check_claim_up(shape),
+            ClaimKind::MouseClick => self.layer.mouse_manager.check_claim_click_space(shape),
+            ClaimKind::MouseScroll => self.layer.mouse_manager.check_claim_scroll_space(shape)
+        }
+    }
+
+    fn claim_render_space(&mut self, region: Region, trigger: RenderTrigger, opacity: RenderOpacity, phase: RenderPhase) -> Result<(),ClaimError> {
+
+        self.check_claim(ClaimKind::Render, &HitShape::Rect(region))?;
+
         self.render_handle = Some((region, trigger, phase, opacity));
         Ok(())
     }
 
-    fn claim_key_down_space(&mut self, region: Region) -> Result<(),()> {
+    fn claim_key_down_shape(&mut self, shape: HitShape) -> Result<(),ClaimError> {
 
-        if !self.layer.key_manager.can_claim_down(region) {
-            return Err(());
-        }
+        self.check_claim(ClaimKind::KeyDown, &shape)?;
 
-        self.key_down_space = Some(region);
+        self.key_down_space = Some(shape);
         Ok(())
     }
 
-    fn claim_key_up_space(&mut self, region: Region) -> Result<(),()> {
+    fn claim_key_up_shape(&mut self, shape: HitShape) -> Result<(),ClaimError> {
 
-        if !self.layer.key_manager.can_claim_up(region) {
-            return Err(());
-        }
+        self.check_claim(ClaimKind::KeyUp, &shape)?;
 
-        self.key_up_space = Some(region);
+        self.key_up_space = Some(shape);
         Ok(())
     }
 
-    fn claim_key_listen_space(&mut self, region: Region) -> Result<(),()> {
-        if !self.layer.key_manager.can_claim_down(region) && !self.layer.key_manager.can_claim_up(region) {
-            return Err(());
-        }
+    fn claim_key_listen_shape(&mut self, shape: HitShape) -> Result<(),ClaimError> {
+        self.check_claim(ClaimKind::KeyDown, &shape)?;
+        self.check_claim(ClaimKind::KeyUp, &shape)?;
 
-        self.key_down_space = Some(region);
-        self.key_up_space = Some(region);
+        self.key_down_space = Some(shape.clone());
+        self.key_up_space = Some(shape);
         Ok(())
     }
 
@@ -415,21 +731,17 @@ impl<'a> LayerAgent for SimpleLayerAgent<'a> {
         self.key_up_priority = Some(priority);
     }
 
-    fn claim_mouse_click_space(&mut self, region: Region) -> Result<(),()> {
-        if !self.layer.mouse_manager.can_claim_click_space(region) {
-            return Err(());
-        }
+    fn claim_mouse_click_shape(&mut self, shape: HitShape) -> Result<(),ClaimError> {
+        self.check_claim(ClaimKind::MouseClick, &shape)?;
 
-        self.mouse_click_space = Some(region);
+        self.mouse_click_space = Some(shape);
         Ok(())
     }
 
-    fn claim_mouse_scroll_space(&mut self, region: Region) -> Result<(),()> {
-        if !self.layer.mouse_manager.can_claim_scroll_space(region) {
-            return Err(());
-        }
+    fn claim_mouse_scroll_shape(&mut self, shape: HitShape) -> Result<(),ClaimError> {
+        self.check_claim(ClaimKind::MouseScroll, &shape)?;
 
-        self.mouse_scroll_space = Some(region);
+        self.mouse_scroll_space = Some(shape);
         Ok(())
     }
 
@@ -437,12 +749,12 @@ impl<'a> LayerAgent for SimpleLayerAgent<'a> {
         self.mouse_scroll_priority = Some(priority);
     }
 
-    fn claim_mouse_move_space(&mut self, region: Region){
-        self.mouse_move_space = Some(region);
+    fn claim_mouse_move_shape(&mut self, shape: HitShape){
+        self.mouse_move_space = Some(shape);
     }
 
-    fn claim_mouse_in_out_space(&mut self, region: Region){
-        self.mouse_move_in_out_space = Some(region);
+    fn claim_mouse_in_out_shape(&mut self, shape: HitShape){
+        self.mouse_move_in_out_space = Some(shape);
     }
 
     fn make_mouse_move_listener(&mut self){
@@ -453,6 +765,14 @@ impl<'a> LayerAgent for SimpleLayerAgent<'a> {
         self.mouse_click_global = true;
     }
 
+    fn make_composition_listener(&mut self, priority: i8) {
+        self.composition_priority = Some(priority);
+    }
+
+    fn make_capture_listener(&mut self, priority: i8) {
+        self.capture_priority = Some(priority);
+    }
+
     fn make_copy_listener(&mut self, priority: i8) {
         self.copy_priority = Some(priority);
     }
@@ -468,4 +788,92 @@ impl<'a> LayerAgent for SimpleLayerAgent<'a> {
     fn make_update_listener(&mut self){
         self.receive_updates = true;
     }
-}
\ No newline at end of file
+
+    fn make_continuous_update_listener(&mut self){
+        self.continuous_updates = true;
+    }
+
+    fn claim_tooltip_space(&mut self, region: Region, tooltip: Rc<Tooltip>) {
+        self.tooltips.push((region, tooltip));
+    }
+
+    fn claim_shortcut(&mut self, action: &str, chord: KeyChord) -> Result<(),ClaimError> {
+        if let Err(error) = self.layer.shortcut_manager.check_bind(action, &chord) {
+            return Err(ClaimError::Shortcut { action: action.to_string(), error });
+        }
+
+        // The behavior might also have claimed other shortcuts during this attach
+        for (other_action, other_chord) in &self.shortcuts {
+            if other_action == action || other_chord.conflicts_with(&chord) {
+                let conflict = ShortcutConflict::new(other_action.clone(), other_chord.clone());
+                return Err(ClaimError::Shortcut { action: action.to_string(), error: ShortcutError::Conflict(conflict) });
+            }
+        }
+
+        self.shortcuts.push((action.to_string(), chord));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use std::rc::Weak;
+
+    struct IdleBehavior {
+
+        agent: Weak<RefCell<ComponentAgent>>
+    }
+
+    impl ComponentBehavior for IdleBehavior {
+
+        fn attach(&mut self, _agent: &mut dyn LayerAgent) {}
+
+        fn set_agent(&mut self, agent: Weak<RefCell<ComponentAgent>>) {
+            self.agent = agent;
+        }
+
+        fn get_agent(&self) -> &Weak<RefCell<ComponentAgent>> {
+            &self.agent
+        }
+    }
+
+    struct IdleComponent {
+
+        behavior: Rc<RefCell<IdleBehavior>>
+    }
+
+    impl Component for IdleComponent {
+
+        fn create_behaviors(&mut self) -> Vec<Rc<RefCell<dyn ComponentBehavior>>> {
+            let behavior: Rc<RefCell<dyn ComponentBehavior>> = self.behavior.clone();
+            vec![behavior]
+        }
+    }
+
+    #[test]
+    fn test_remove_cancels_timers_and_animations() {
+        let behavior = Rc::new(RefCell::new(IdleBehavior { agent: Weak::new() }));
+        let component: Rc<RefCell<dyn Component>> = Rc::new(RefCell::new(IdleComponent { behavior: Rc::clone(&behavior) }));
+
+        let mut layer = SimpleLayer::new(None);
+        layer.add_component(Rc::clone(&component));
+
+        // Keep the component and its agent alive after it is removed, like applications often do
+        let agent = behavior.borrow().get_agent().upgrade().expect("The layer should have given the behavior an agent");
+        let timer = agent.borrow_mut().schedule_repeating(100.0, || {});
+        let animation = agent.borrow_mut().animate(Delay::new(100.0).boxed());
+        assert!(timer.is_pending());
+        assert!(animation.is_running());
+
+        agent.borrow_mut().remove_this_component();
+        assert!(layer.check_agents().is_none());
+
+        assert!(!timer.is_pending());
+        assert_eq!(AnimationStatus::Cancelled, animation.get_status());
+        assert!(!agent.borrow().has_timers());
+        assert!(!agent.borrow().has_animations());
+    }
+}
@@ -1,21 +1,33 @@
 use std::cell::RefCell;
-use std::rc::Weak;
+use std::rc::{
+    Rc,
+    Weak
+};
 
 use crate::*;
 
 use wasmuri_core::*;
 
+/// The move space of a behavior
+struct MoveSpace {
+
+    shape: HitShape,
+
+    /// True if the behavior only wants to know when the mouse enters or leaves the shape
+    in_out: bool
+}
+
 pub struct MouseManager {
 
-    area_click_listeners: WeakMetaVec<dyn ComponentBehavior, Region>,
+    area_click_listeners: WeakMetaVec<dyn ComponentBehavior, (HitShape, i32)>,
     full_click_listeners: WeakVec<dyn ComponentBehavior>,
 
-    area_scroll_listeners: WeakMetaVec<dyn ComponentBehavior, Region>,
+    area_scroll_listeners: WeakMetaVec<dyn ComponentBehavior, (HitShape, i32)>,
     full_scroll_listeners: WeakMetaVec<dyn ComponentBehavior, i8>,
 
-    area_move_listeners: WeakMetaVec<dyn ComponentBehavior, Region>,
-    full_move_listeners: WeakVec<dyn ComponentBehavior>,
-    in_out_move_listeners: WeakMetaVec<dyn ComponentBehavior, Region>
+    /// The area move listeners and the in-out move listeners share a list because they occlude each other
+    area_move_listeners: WeakMetaVec<dyn ComponentBehavior, (MoveSpace, i32)>,
+    full_move_listeners: WeakVec<dyn ComponentBehavior>
 }
 
 impl MouseManager {
@@ -29,43 +41,47 @@ impl MouseManager {
             full_scroll_listeners: WeakMetaVec::new(),
 
             area_move_listeners: WeakMetaVec::new(),
-            full_move_listeners: WeakVec::new(),
-            in_out_move_listeners: WeakMetaVec::new()
+            full_move_listeners: WeakVec::new()
         }
     }
 
-    pub fn can_claim_click_space(&self, region: Region) -> bool {
-        for handle in &self.area_click_listeners.vec {
-            if handle.metadata.intersects_with(region) {
-                return false;
-            }
-        }
+    pub fn snapshot_listeners(&mut self, snapshot: &mut LayerSnapshot) {
+        super::snapshot_listeners(&self.area_click_listeners, snapshot, |meta| ListenerSnapshot::new("mouse_click").with_region(meta.0.get_bounds()).with_z(meta.1));
+        super::snapshot_plain_listeners(&mut self.full_click_listeners, snapshot, "mouse_click");
+        super::snapshot_listeners(&self.area_scroll_listeners, snapshot, |meta| ListenerSnapshot::new("mouse_scroll").with_region(meta.0.get_bounds()).with_z(meta.1));
+        super::snapshot_listeners(&self.full_scroll_listeners, snapshot, |priority| ListenerSnapshot::new("mouse_scroll").with_priority(*priority));
+        super::snapshot_listeners(&self.area_move_listeners, snapshot, |meta| {
+            ListenerSnapshot::new(if meta.0.in_out { "mouse_in_out" } else { "mouse_move" }).with_region(meta.0.shape.get_bounds()).with_z(meta.1)
+        });
+        super::snapshot_plain_listeners(&mut self.full_move_listeners, snapshot, "mouse_move");
+    }
 
-        true
+    pub fn check_claim_click_space(&self, shape: &HitShape) -> Result<(),ClaimError> {
+        super::check_free(&self.area_click_listeners, ClaimKind::MouseClick, shape, |meta| meta.0.get_bounds())
     }
 
-    pub fn can_claim_scroll_space(&self, region: Region) -> bool {
-        for handle in &self.area_scroll_listeners.vec {
-            if handle.metadata.intersects_with(region) {
-                return false;
-            }
-        }
+    pub fn check_claim_scroll_space(&self, shape: &HitShape) -> Result<(),ClaimError> {
+        super::check_free(&self.area_scroll_listeners, ClaimKind::MouseScroll, shape, |meta| meta.0.get_bounds())
+    }
 
-        true
+    /// Adds the click and scroll spaces of the listeners to claims
+    pub fn collect_claims(&self, claims: &mut Vec<ClaimOutline>) {
+        super::collect_claims(&self.area_click_listeners, ClaimKind::MouseClick, claims, |meta| meta.0.get_bounds());
+        super::collect_claims(&self.area_scroll_listeners, ClaimKind::MouseScroll, claims, |meta| meta.0.get_bounds());
     }
 
-    /// Should only be used after can_claim_scroll_space confirmed that this is allowed
-    pub fn add_scroll_space_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, region: Region){
-        self.area_scroll_listeners.push(behavior, region);
+    /// Should only be used after check_claim_scroll_space confirmed that this is allowed
+    pub fn add_scroll_space_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, shape: HitShape, z: i32){
+        super::insert_by_z(&mut self.area_scroll_listeners, behavior, shape, z);
     }
 
-    pub fn add_move_space_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, region: Region){
-        self.area_move_listeners.push(behavior, region);
+    pub fn add_move_space_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, shape: HitShape, z: i32){
+        super::insert_by_z(&mut self.area_move_listeners, behavior, MoveSpace { shape, in_out: false }, z);
     }
 
-    /// Should only be used after can_claim_click_space confirmed that this is allowed
-    pub fn add_click_space_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, region: Region){
-        self.area_click_listeners.push(behavior, region);
+    /// Should only be used after check_claim_click_space confirmed that this is allowed
+    pub fn add_click_space_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, shape: HitShape, z: i32){
+        super::insert_by_z(&mut self.area_click_listeners, behavior, shape, z);
     }
 
     pub fn add_full_click_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>){
@@ -80,8 +96,8 @@ impl MouseManager {
         self.full_move_listeners.push(behavior);
     }
 
-    pub fn add_in_out_move_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, region: Region){
-        self.in_out_move_listeners.push(behavior, region);
+    pub fn add_in_out_move_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, shape: HitShape, z: i32){
+        super::insert_by_z(&mut self.area_move_listeners, behavior, MoveSpace { shape, in_out: true }, z);
     }
 
     fn add_full_listener(list: &mut WeakMetaVec<dyn ComponentBehavior, i8>, behavior: Weak<RefCell<dyn ComponentBehavior>>, priority: i8){
@@ -102,19 +118,30 @@ impl MouseManager {
         });
     }
 
-    fn mouse_inside(region: Region, mouse_pos: Option<(f32, f32)>) -> bool {
-        mouse_pos.is_some() && region.is_float_inside(mouse_pos.unwrap())
+    /// Finds the component with the topmost move space at the given position. The move spaces of the other components at that
+    /// position are covered by it, so the mouse doesn't count as inside them.
+    fn find_hovered(&self, mouse_pos: Option<(f32, f32)>) -> Option<ComponentKey> {
+        let mouse_pos = mouse_pos?;
+        self.area_move_listeners.vec.iter()
+                .filter(|handle| handle.metadata.0.shape.contains(mouse_pos))
+                .find_map(|handle| super::get_component_key(&handle.weak_cell))
+    }
+
+    fn mouse_inside(space: &MoveSpace, mouse_pos: Option<(f32, f32)>, hovered: Option<ComponentKey>, key: Option<ComponentKey>) -> bool {
+        mouse_pos.is_some() && key.is_some() && hovered == key && space.shape.contains(mouse_pos.unwrap())
     }
 
     pub fn fire_mouse_move(&mut self, prev_mouse_pos: Option<(f32,f32)>, next_mouse_pos: Option<(f32, f32)>, manager: &ContainerManager) {
-        self.in_out_move_listeners.for_each_mut(|behavior, region| {
-            if Self::mouse_inside(*region, prev_mouse_pos) != Self::mouse_inside(*region, next_mouse_pos) {
-                behavior.mouse_move(&mut MouseMoveParams::new(prev_mouse_pos, next_mouse_pos, manager));
-            }
-        });
+        let prev_hovered = self.find_hovered(prev_mouse_pos);
+        let next_hovered = self.find_hovered(next_mouse_pos);
 
-        self.area_move_listeners.for_each_mut(|behavior, region| {
-            if Self::mouse_inside(*region, prev_mouse_pos) || Self::mouse_inside(*region, next_mouse_pos) {
+        self.area_move_listeners.for_each_mut(|behavior, meta| {
+            let key = super::get_behavior_key(&*behavior);
+            let prev_inside = Self::mouse_inside(&meta.0, prev_mouse_pos, prev_hovered, key);
+            let next_inside = Self::mouse_inside(&meta.0, next_mouse_pos, next_hovered, key);
+
+            let interested = if meta.0.in_out { prev_inside != next_inside } else { prev_inside || next_inside };
+            if interested {
                 behavior.mouse_move(&mut MouseMoveParams::new(prev_mouse_pos, next_mouse_pos, manager));
             }
         });
@@ -125,8 +152,12 @@ impl MouseManager {
     }
 
     pub fn fire_mouse_click(&mut self, manager: &ContainerManager, mouse_pos: (f32,f32), click: ClickInfo) {
-        self.area_click_listeners.for_each_mut(|behavior, region| {
-            if region.is_float_inside(mouse_pos) {
+
+        // Only the topmost shape at the mouse position gets the click, which only matters when the claims can overlap
+        let mut hit = false;
+        self.area_click_listeners.for_each_mut(|behavior, meta| {
+            if !hit && meta.0.contains(mouse_pos) {
+                hit = true;
                 behavior.mouse_click_inside(&mut MouseClickParams::new(mouse_pos, click, manager));
             } else {
                 behavior.mouse_click_outside(&mut MouseClickOutParams::new(click, manager));
@@ -139,7 +170,7 @@ impl MouseManager {
     }
 
     pub fn fire_mouse_click_outside(&mut self, manager: &ContainerManager, click: ClickInfo) {
-        self.area_click_listeners.for_each_mut(|behavior, _region| {
+        self.area_click_listeners.for_each_mut(|behavior, _shape| {
             behavior.mouse_click_outside(&mut MouseClickOutParams::new(click, manager));
         });
 
@@ -148,13 +179,26 @@ impl MouseManager {
         });
     }
 
+    /// Finds the topmost behavior that claimed the click space at the given position, if any
+    pub fn find_click_listener(&self, mouse_pos: (f32, f32)) -> Option<Rc<RefCell<dyn ComponentBehavior>>> {
+        for handle in &self.area_click_listeners.vec {
+            if handle.metadata.0.contains(mouse_pos) {
+                if let Some(behavior) = handle.weak_cell.upgrade() {
+                    return Some(behavior);
+                }
+            }
+        }
+
+        None
+    }
+
     pub fn fire_mouse_scroll(&mut self, manager: &ContainerManager, mouse_pos: Option<(f32,f32)>, delta: f64) -> bool {
 
         let mut consumed = false;
 
         if mouse_pos.is_some() {
-            self.area_scroll_listeners.for_each_mut(|behavior, region| {
-                if !consumed && region.is_float_inside(mouse_pos.unwrap()){
+            self.area_scroll_listeners.for_each_mut(|behavior, meta| {
+                if !consumed && meta.0.contains(mouse_pos.unwrap()){
                     consumed = behavior.mouse_scroll(&mut MouseScrollParams::new(mouse_pos, delta, manager));
                 }
             });
@@ -14,8 +14,43 @@ struct RenderMeta {
     trigger: RenderTrigger,
     opacity: RenderOpacity,
     phase: RenderPhase,
+    z: i32,
 
-    prev_render_actions: Vec<PassedRenderAction>
+    prev_render_actions: Vec<PassedRenderAction>,
+
+    /// The parts of the region that need to be redrawn, if the component only has to render because parts of it became dirty.
+    /// The component will be clipped to these parts during the next render. If this is None, the entire region will be redrawn.
+    clip_regions: Option<Vec<Region>>
+}
+
+impl RenderMeta {
+
+    /// Gets the parts of the region that will be redrawn when the component renders
+    fn get_render_regions(&self) -> Vec<Region> {
+        match &self.clip_regions {
+            Some(clip_regions) => clip_regions.clone(),
+            None => vec![self.region]
+        }
+    }
+
+    /// Adds the parts of the region that intersect with the given regions to the clip regions and returns the parts that weren't
+    /// part of the clip regions yet. This shouldn't be used for components that will redraw their entire region anyway.
+    fn add_clip_regions(&mut self, regions: &[Region]) -> Vec<Region> {
+        let mut clip = RegionSet::from_regions(self.clip_regions.as_deref().unwrap_or(&[]));
+        let mut new_parts = Vec::new();
+        for region in regions {
+            if let Some(part) = intersect_regions(self.region, *region) {
+                let uncovered = clip.get_uncovered(part);
+                new_parts.extend_from_slice(uncovered.get_regions());
+                clip.union(&uncovered);
+            }
+        }
+
+        if !new_parts.is_empty() {
+            self.clip_regions.get_or_insert_with(Vec::new).extend_from_slice(&new_parts);
+        }
+        new_parts
+    }
 }
 
 pub struct RenderManager {
@@ -23,7 +58,16 @@ pub struct RenderManager {
     render_components: WeakMetaVec<dyn ComponentBehavior, RenderMeta>,
 
     background_color: Option<Color>,
-    render_background: bool
+    render_background: bool,
+
+    /// If true, the background color will be taken from the palette of the current theme
+    theme_background: bool,
+
+    /// If true, the render regions of components can overlap and components that overlap a component that renders will render as well
+    z_ordered: bool,
+
+    /// The parts of the background that need to be redrawn during the next render, if not the entire background needs to be redrawn
+    dirty_background: RegionSet
 }
 
 impl RenderManager {
@@ -32,42 +76,121 @@ impl RenderManager {
         RenderManager {
             render_components: WeakMetaVec::with_capacity(10),
             background_color,
-            render_background: true
+            render_background: true,
+            theme_background: false,
+            z_ordered: false,
+            dirty_background: RegionSet::new()
         }
     }
 
-    /// Should only be called after can_claim confirms that the region can be claimed!
-    pub fn claim_space(&mut self, region: Region, trigger: RenderTrigger, phase: RenderPhase, opacity: RenderOpacity, behavior: Weak<RefCell<dyn ComponentBehavior>>) {
+    pub fn themed() -> RenderManager {
+        RenderManager {
+            render_components: WeakMetaVec::with_capacity(10),
 
-        let maybe_index = self.render_components.vec.binary_search_by(|existing| {
-            existing.metadata.phase.cmp(&phase)
-        });
-        let index;
-        match maybe_index {
-            Ok(the_index) => index = the_index,
-            Err(the_index) => index = the_index
-        };
+            // The color will be replaced by that of the current theme before rendering, but predict_render needs to know
+            // the opacity of the background before that happens
+            background_color: Some(Theme::light().palette.background),
+            render_background: true,
+            theme_background: true,
+            z_ordered: false,
+            dirty_background: RegionSet::new()
+        }
+    }
+
+    pub fn set_z_ordered(&mut self, z_ordered: bool) {
+        self.z_ordered = z_ordered;
+    }
+
+    /// Should only be called after check_claim confirms that the region can be claimed!
+    /// The components are rendered in order of ascending z-order and then by render phase. Components with the same z-order and
+    /// phase are rendered in the order in which they were added, so that the most recent one ends up on top, just like it gets
+    /// the mouse events first.
+    pub fn claim_space(&mut self, region: Region, trigger: RenderTrigger, phase: RenderPhase, opacity: RenderOpacity, z: i32, behavior: Weak<RefCell<dyn ComponentBehavior>>) {
+
+        let index = self.render_components.vec.iter().position(|existing| {
+            (existing.metadata.z, existing.metadata.phase) > (z, phase)
+        }).unwrap_or(self.render_components.vec.len());
         self.render_components.vec.insert(index, 
             WeakMetaHandle {
                 weak_cell: behavior,
-                metadata: RenderMeta {region, trigger, opacity, phase, prev_render_actions: Vec::new()}
+                metadata: RenderMeta {region, trigger, opacity, phase, z, prev_render_actions: Vec::new(), clip_regions: None}
             }
         );
     }
 
-    pub fn can_claim(&self, region: Region) -> bool {
+    pub fn check_claim(&self, shape: &HitShape) -> Result<(),ClaimError> {
+        super::check_free(&self.render_components, ClaimKind::Render, shape, |meta| meta.region)
+    }
 
-        for handle in &self.render_components.vec {
-            if handle.metadata.region.intersects_with(region) {
-                return false;
+    /// When a component renders, the components above it that overlap with it need to render as well (in the overlapping part),
+    /// or they would be drawn over
+    fn propagate_z_renders(&mut self) {
+        let mut rendering_regions: Vec<Region> = Vec::new();
+        self.render_components.for_each_mut(|behavior, meta| {
+            let agent_cell = behavior.get_agent().upgrade().expect("Component agent shouldn't have been dropped");
+            let mut agent = agent_cell.borrow_mut();
+
+            let renders_entirely = agent.did_request_render() && meta.clip_regions.is_none();
+            if !renders_entirely && !meta.add_clip_regions(&rendering_regions).is_empty() {
+                agent.request_render();
             }
+
+            if agent.did_request_render() {
+                rendering_regions.append(&mut meta.get_render_regions());
+            }
+        });
+    }
+
+    /// Marks the background behind the transparent components that will render as dirty, because they would otherwise be drawn over
+    /// their previous render. Components that overlap the dirty background will have to render as well, so this is repeated until
+    /// nothing changes anymore. Returns the render actions of the components that were forced to render by this method.
+    fn spread_dirty_background(&mut self) -> Vec<PlannedRenderAction> {
+        let mut caused_render_actions = Vec::new();
+        if self.background_color.is_none() || self.render_background {
+            return caused_render_actions;
         }
 
-        return true;
+        let dirty_background = &mut self.dirty_background;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            self.render_components.for_each_mut(|behavior, meta| {
+                let agent_cell = behavior.get_agent().upgrade().expect("Component agent shouldn't have been dropped");
+                let mut agent = agent_cell.borrow_mut();
+
+                // Components only need to redraw the parts of them that are on the dirty background
+                let renders_entirely = agent.did_request_render() && meta.clip_regions.is_none();
+                if !renders_entirely {
+                    let new_parts = meta.add_clip_regions(dirty_background.get_regions());
+                    if !new_parts.is_empty() {
+                        agent.request_render();
+                        for part in new_parts {
+                            caused_render_actions.push(PlannedRenderAction::new(part, meta.opacity));
+                        }
+                        changed = true;
+                    }
+                }
+
+                if agent.did_request_render() && meta.opacity != RenderOpacity::Solid {
+                    for part in meta.get_render_regions() {
+                        if !dirty_background.covers(part) {
+                            dirty_background.add(part);
+                            changed = true;
+                        }
+                    }
+                }
+            });
+        }
+
+        caused_render_actions
     }
 
     pub fn predict_render(&mut self) -> Vec<PlannedRenderAction> {
 
+        if self.z_ordered {
+            self.propagate_z_renders();
+        }
+
         let mut render_actions = Vec::new();
 
         if self.render_background {
@@ -86,12 +209,16 @@ impl RenderManager {
             }
         }
 
+        self.spread_dirty_background();
+
         self.render_components.for_each_mut(|behavior, meta| {
             let agent_cell = behavior.get_agent().upgrade().expect("Component agent shouldn't have been dropped");
             let agent = agent_cell.borrow();
 
             if agent.did_request_render() {
-                render_actions.push(PlannedRenderAction::new(meta.region, meta.opacity));
+                for part in meta.get_render_regions() {
+                    render_actions.push(PlannedRenderAction::new(part, meta.opacity));
+                }
             }
         });
 
@@ -103,15 +230,58 @@ impl RenderManager {
 
         let mut render_actions = Vec::new();
 
+        if self.theme_background {
+            self.background_color = Some(manager.get_theme().palette.background);
+        }
+
+        if self.z_ordered {
+            self.propagate_z_renders();
+        }
+
         // Draw the background if necessary
         if self.render_background && self.background_color.is_some() {
             let color = self.background_color.as_ref().unwrap();
+
+            // The clear should cover the entire viewport, so the scissor test must be disabled
+            let mut render_context = manager.get_render_context().borrow_mut();
+            render_context.set_clip(None);
+            render_context.flush_and_invalidate();
+            render_context.add_dirty_region(Region::entire_viewport());
+            drop(render_context);
+
             gl.clear_color(color.get_red_float(), color.get_green_float(), color.get_blue_float(), color.get_alpha_float());
             gl.clear(WebGlRenderingContext::COLOR_BUFFER_BIT);
 
             // Once the background has been drawn, don't redraw until we need to do it again
             self.render_background = false;
-            
+            self.dirty_background.clear();
+        }
+
+        // Draw the dirty parts of the background, except the parts that will be drawn over by solid components anyway
+        if !self.dirty_background.is_empty() {
+            let color = self.background_color.expect("Only layers with a background color can have a dirty background");
+            let dirty_background = &mut self.dirty_background;
+            self.render_components.for_each_mut(|behavior, meta| {
+                let agent_cell = behavior.get_agent().upgrade().expect("Component agent shouldn't have been dropped");
+                if meta.opacity == RenderOpacity::Solid && agent_cell.borrow().did_request_render() {
+                    for part in meta.get_render_regions() {
+                        dirty_background.subtract(part);
+                    }
+                }
+            });
+
+            gl.clear_color(color.get_red_float(), color.get_green_float(), color.get_blue_float(), color.get_alpha_float());
+            let mut render_context = manager.get_render_context().borrow_mut();
+            for region in self.dirty_background.get_regions() {
+                render_context.set_clip(Some(*region));
+                render_context.flush_and_invalidate();
+                render_context.add_dirty_region(*region);
+                gl.clear(WebGlRenderingContext::COLOR_BUFFER_BIT);
+            }
+            render_context.set_clip(None);
+            drop(render_context);
+
+            self.dirty_background.clear();
         }
 
         let has_background = self.background_color.is_some();
@@ -123,30 +293,47 @@ impl RenderManager {
 
         let mut cursor_result = None;
 
-        let mut previous_render_phase = RenderPhase::Start;
-
         self.render_components.for_each_mut(|behavior, meta| {
             let agent_holder = behavior.get_agent().upgrade().expect("Component agent shouldn't have been dropped");
             let mut agent = agent_holder.borrow_mut();
             let requested_render = agent.did_request_render();
             if requested_render {
 
-                if previous_render_phase != meta.phase {
+                // The active phase is tracked by the manager, so it is only switched when it differs from the phase of the
+                // previous component that rendered, even if that component was in another layer
+                manager.switch_render_phase(gl, Some(meta.phase));
 
-                    // TODO Handle render phase switching per container rather than per layer
+                agent.set_rendering();
+                let key = agent.get_key();
+                drop(agent);
 
-                    // Currently, the Text phase is the only phase that has built-in support, other phases will have to prepare themselves
+                // Components that are re-rendered because a neighbour needs to be redrawn shouldn't draw over that neighbour, so
+                // every component is clipped to its own region. Components that only have to redraw the dirty parts of their
+                // region are rendered once for every part, clipped to that part.
+                let render_regions = meta.clip_regions.take().unwrap_or_else(|| vec![meta.region]);
+                let mut maybe_render_result: Option<BehaviorRenderResult> = None;
+                for render_region in render_regions {
+                    let mut render_context = manager.get_render_context().borrow_mut();
+                    render_context.set_clip(Some(render_region));
+                    if let Some(visible_region) = intersect_regions(render_region, Region::entire_viewport()) {
+                        render_context.add_dirty_region(visible_region);
+                    }
+
+                    // The previous behavior could have drawn text that is still pending or could have changed the GL state
                     if meta.phase == RenderPhase::Text {
-                        manager.get_text_renderer().borrow_mut().start_rendering();
+                        render_context.restart_text(&mut manager.get_text_renderer().borrow_mut());
                     }
+                    drop(render_context);
 
-                    previous_render_phase = meta.phase;
+                    let part_render_result = manager.profile("ComponentBehavior::render", ProfileCategory::BehaviorRender,
+                            ProfileSubject::Component(key), || behavior.render(&mut RenderParams::new(gl, manager)));
+                    maybe_render_result = maybe_render_result.or(Some(part_render_result));
                 }
 
-                agent.set_rendering();
-                drop(agent);
-
-                let mut local_render_result = behavior.render(&mut RenderParams::new(gl, manager));
+                let mut local_render_result = match maybe_render_result {
+                    Some(render_result) => render_result,
+                    None => return
+                };
                 let mut local_render_actions = local_render_result.get_render_actions();
 
                 // If we have a background, we will render the entire viewport anyway, so adding a part of the viewport to it is useless
@@ -167,20 +354,57 @@ impl RenderManager {
 
                 if !has_background {
                     for prev_render_action in &meta.prev_render_actions {
-                        render_actions.push(*prev_render_action);
+                        render_actions.push(prev_render_action.clone());
                     }
                 }
             }
         });
 
+        manager.get_render_context().borrow_mut().set_clip(None);
+
         (RenderResult::new(cursor_result), render_actions)
     }
 
+    /// Gives the render claims to the components of the snapshot
+    pub fn snapshot_claims(&self, snapshot: &mut LayerSnapshot) {
+        for handle in &self.render_components.vec {
+            let meta = &handle.metadata;
+            let claim = RenderClaimSnapshot::new(meta.region, meta.trigger, meta.opacity, meta.phase, meta.z);
+            if let Some(key) = super::get_component_key(&handle.weak_cell) {
+                if let Some(component) = snapshot.get_component_mut(key) {
+                    component.add_render_claim(claim);
+                }
+            }
+        }
+    }
+
+    /// Adds the render spaces of the components to claims
+    pub fn collect_claims(&self, claims: &mut Vec<ClaimOutline>) {
+        super::collect_claims(&self.render_components, ClaimKind::Render, claims, |meta| meta.region);
+    }
+
+    /// Checks if the next call to render() would draw anything
+    pub fn has_pending_render(&mut self) -> bool {
+        if (self.render_background && self.background_color.is_some()) || !self.dirty_background.is_empty() {
+            return true;
+        }
+
+        let mut pending = false;
+        self.render_components.for_each_mut(|behavior, _meta| {
+            if !pending {
+                let agent_cell = behavior.get_agent().upgrade().expect("Component agent shouldn't have been dropped");
+                pending = agent_cell.borrow().did_request_render();
+            }
+        });
+        pending
+    }
+
     /// Ensures that all components will render during the next call to render()
     pub fn force_full_render(&mut self){
         self.render_background = true;
 
-        self.render_components.for_each_mut(|behavior, _meta| {
+        self.render_components.for_each_mut(|behavior, meta| {
+            meta.clip_regions = None;
             behavior.get_agent().upgrade().expect("Component agent shouldn't have been dropped").borrow_mut().request_render();
         });
     }
@@ -196,36 +420,41 @@ impl RenderManager {
 
         let background_color = self.background_color;
         self.render_components.for_each_mut(|behavior, meta| {
-            for region in regions {
-                if meta.region.intersects_with(*region) {
+            let agent_cell = behavior.get_agent().upgrade().expect("Component agent shouldn't have been dropped");
+            let mut agent = agent_cell.borrow_mut();
 
-                    // We need this info to determine whether or not the background needs to be re-rendered
-                    if background_color.is_some() && meta.opacity == RenderOpacity::Solid {
-                        solid_rerender_regions.push(meta.region);
-                    }
+            // Components that will redraw their entire region anyway don't need to be clipped to the given regions
+            if agent.did_request_render() && meta.clip_regions.is_none() {
+                if background_color.is_some() && meta.opacity == RenderOpacity::Solid && regions.iter().any(|region| region.intersects_with(meta.region)) {
+                    solid_rerender_regions.push(meta.region);
+                }
+                return;
+            }
 
-                    let agent_cell = behavior.get_agent().upgrade().expect("Component agent shouldn't have been dropped");
-                    let mut agent = agent_cell.borrow_mut();
+            // The other components only need to redraw the parts of them that are inside the given regions
+            let new_parts = meta.add_clip_regions(regions);
+            if new_parts.is_empty() {
+                return;
+            }
+            agent.request_render();
 
-                    if !agent.did_request_render() {
-                        agent.request_render();
-                        caused_render_actions.push(PlannedRenderAction::new(meta.region, meta.opacity));
-                    }
-                    break;
+            for part in new_parts {
+
+                // We need this info to determine whether or not the background needs to be re-rendered
+                if background_color.is_some() && meta.opacity == RenderOpacity::Solid {
+                    solid_rerender_regions.push(part);
                 }
+                caused_render_actions.push(PlannedRenderAction::new(part, meta.opacity));
             }
         });
 
-        // If we have a background color and the regions are not entirely covered by solid components, we need to re-render the background
-        if self.background_color.is_some() {
+        // If we have a background color, the parts of the regions that are not covered by solid components need a new background
+        if self.background_color.is_some() && !self.render_background {
+            let solid_rerender_regions = RegionSet::from_regions(&solid_rerender_regions);
             for region in regions {
-                if !region.get_uncovered_regions(&solid_rerender_regions).is_empty() {
-
-                    // Since we need to re-render the background, we also need to re-render all other components
-                    self.force_full_render();
-                    break;
-                }
+                self.dirty_background.union(&solid_rerender_regions.get_uncovered(*region));
             }
+            caused_render_actions.append(&mut self.spread_dirty_background());
         }
 
         // Finally return the render actions that were caused by this method call
@@ -0,0 +1,76 @@
+use crate::*;
+
+use std::cell::RefCell;
+use std::rc::Weak;
+
+use wasmuri_core::*;
+
+pub struct ShortcutManager {
+
+    registry: ShortcutRegistry,
+
+    listeners: WeakMetaVec<dyn ComponentBehavior, String>
+}
+
+impl ShortcutManager {
+
+    pub fn new() -> ShortcutManager {
+        ShortcutManager {
+            registry: ShortcutRegistry::new(),
+            listeners: WeakMetaVec::new()
+        }
+    }
+
+    pub fn snapshot_listeners(&self, snapshot: &mut LayerSnapshot) {
+        super::snapshot_listeners(&self.listeners, snapshot, |action| ListenerSnapshot::new("shortcut").with_detail(action));
+    }
+
+    /// Unbinds the actions whose listener has been dropped, so that their chords no longer swallow key strokes and can be
+    /// claimed by other components
+    pub fn prune_dead_listeners(&mut self) {
+        let registry = &mut self.registry;
+        self.listeners.vec.retain(|handle| {
+            let alive = handle.weak_cell.upgrade().is_some();
+            if !alive {
+                registry.unbind(&handle.metadata);
+            }
+            alive
+        });
+    }
+
+    pub fn check_bind(&self, action: &str, chord: &KeyChord) -> Result<(), ShortcutError> {
+        self.registry.check_bind(action, chord)
+    }
+
+    /// Should only be used after check_bind confirmed that the action and chord are available
+    pub fn add_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, action: String, chord: KeyChord){
+        self.registry.bind(&action, chord).expect("check_bind should have been called");
+        self.listeners.push(behavior, action);
+    }
+
+    pub fn get_registry(&self) -> &ShortcutRegistry {
+        &self.registry
+    }
+
+    pub fn get_registry_mut(&mut self) -> &mut ShortcutRegistry {
+        &mut self.registry
+    }
+
+    /// Feeds the key stroke to the registry and notifies the behavior of the action if a chord was completed.
+    /// Returns true if the key stroke was part of a chord, in which case it should be consumed.
+    pub fn fire_key_down(&mut self, keys: &KeyInfo, manager: &ContainerManager) -> bool {
+        self.prune_dead_listeners();
+        match self.registry.process(keys) {
+            ShortcutMatch::None => false,
+            ShortcutMatch::Pending => true,
+            ShortcutMatch::Triggered(action) => {
+                self.listeners.for_each_mut(|behavior, listen_action| {
+                    if *listen_action == action {
+                        behavior.shortcut(&mut ShortcutParams::new(&action, manager));
+                    }
+                });
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::*;
+
+use std::cell::RefCell;
+use std::rc::*;
+
+use wasmuri_core::*;
+
+pub struct TooltipManager {
+
+    tooltips: WeakMetaVec<dyn ComponentBehavior, (Region, Rc<Tooltip>)>,
+
+    /// The tooltip of this layer the mouse is currently hovering over
+    current: Option<Rc<Tooltip>>
+}
+
+impl TooltipManager {
+
+    pub fn new() -> TooltipManager {
+        TooltipManager {
+            tooltips: WeakMetaVec::new(),
+            current: None
+        }
+    }
+
+    pub fn snapshot_listeners(&self, snapshot: &mut LayerSnapshot) {
+        super::snapshot_listeners(&self.tooltips, snapshot, |meta| ListenerSnapshot::new("tooltip").with_region(meta.0));
+    }
+
+    pub fn add_tooltip(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>, region: Region, tooltip: Rc<Tooltip>) {
+        self.tooltips.push(behavior, (region, tooltip));
+    }
+
+    /// Reports the tooltip at the new mouse position (if any) to the TooltipState of the manager
+    pub fn on_mouse_move(&mut self, new_mouse_pos: Option<(f32, f32)>, manager: &ContainerManager) {
+        let mut hovered = None;
+        if let Some(mouse_pos) = new_mouse_pos {
+            self.tooltips.for_each_mut(|_behavior, (region, tooltip)| {
+                if hovered.is_none() && region.is_float_inside(mouse_pos) {
+                    hovered = Some(Rc::clone(tooltip));
+                }
+            });
+        }
+
+        let mut state = manager.get_tooltips().borrow_mut();
+        if let Some(previous) = &self.current {
+            let still_hovered = match &hovered {
+                Some(tooltip) => Rc::ptr_eq(tooltip, previous),
+                None => false
+            };
+            if !still_hovered {
+                state.leave(previous);
+            }
+        }
+
+        if let Some(tooltip) = &hovered {
+            state.hover(tooltip, new_mouse_pos.unwrap());
+        }
+
+        self.current = hovered;
+    }
+}
@@ -7,24 +7,63 @@ use wasmuri_core::WeakVec;
 
 pub struct UpdateManager {
 
-    behaviors: WeakVec<dyn ComponentBehavior>
+    behaviors: WeakVec<dyn ComponentBehavior>,
+
+    /// The behaviors that want to be updated even when nothing else happens. These behaviors are also in behaviors.
+    continuous_behaviors: WeakVec<dyn ComponentBehavior>
 }
 
 impl UpdateManager {
 
     pub fn new() -> UpdateManager {
         UpdateManager {
-            behaviors: WeakVec::new()
+            behaviors: WeakVec::new(),
+            continuous_behaviors: WeakVec::new()
         }
     }
 
+    pub fn snapshot_listeners(&mut self, snapshot: &mut LayerSnapshot) {
+
+        // The continuous listeners are in both lists, but should only be listed once
+        let mut continuous_addresses = Vec::new();
+        self.continuous_behaviors.for_each_mut(|behavior| {
+            continuous_addresses.push(&*behavior as *const _ as *const ());
+        });
+        self.behaviors.for_each_mut(|behavior| {
+            let continuous = continuous_addresses.contains(&(&*behavior as *const _ as *const ()));
+            let kind = if continuous { "continuous_update" } else { "update" };
+            snapshot.add_listener(super::get_behavior_key(&*behavior), ListenerSnapshot::new(kind));
+        });
+    }
+
     pub fn add_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>){
         self.behaviors.push(behavior);
     }
 
+    pub fn add_continuous_listener(&mut self, behavior: Weak<RefCell<dyn ComponentBehavior>>){
+        self.continuous_behaviors.push(Weak::clone(&behavior));
+        self.behaviors.push(behavior);
+    }
+
+    /// Checks if any of the behaviors that were added with add_continuous_listener is still alive
+    pub fn has_continuous_listeners(&mut self) -> bool {
+        let mut alive = false;
+        self.continuous_behaviors.for_each_mut(|_behavior| {
+            alive = true;
+        });
+        alive
+    }
+
     pub fn fire_update(&mut self, manager: &ContainerManager){
+
+        // Looking up the component keys isn't free, so it only happens while profiling
+        let profiling = manager.get_profiler().borrow().is_enabled();
         self.behaviors.for_each_mut(|behavior| {
-            behavior.update(&mut UpdateParams::new(manager));
+            let key = if profiling { super::get_behavior_key(&*behavior) } else { None };
+            let subject = key.map(ProfileSubject::Component).unwrap_or(ProfileSubject::None);
+            manager.profile("ComponentBehavior::update", ProfileCategory::BehaviorUpdate, subject, || {
+                behavior.update(&mut UpdateParams::new(manager))
+            });
         });
     }
 }
\ No newline at end of file
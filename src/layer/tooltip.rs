@@ -0,0 +1,193 @@
+use crate::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasmuri_core::*;
+
+use web_sys::WebGlRenderingContext;
+
+/// An overlay layer that shows the tooltip from the TooltipState of the manager. It should be the front layer of a LayeredContainer.
+/// It never consumes events, so it doesn't affect the layers behind it.
+pub struct TooltipLayer {
+
+    /// The tooltip that is currently shown and the region it is shown in
+    shown: Option<(Rc<Tooltip>, Region)>,
+
+    /// The region of the tooltip that was shown before the last change, which needs to be cleared by the layers behind this layer
+    previous_region: Option<Region>,
+
+    needs_render: bool
+}
+
+impl TooltipLayer {
+
+    pub fn new() -> TooltipLayer {
+        TooltipLayer {
+            shown: None,
+            previous_region: None,
+            needs_render: false
+        }
+    }
+
+    /// Computes the region to draw the tooltip in: right below the cursor, but clamped to the viewport
+    fn compute_region(tooltip: &Tooltip, mouse_pos: (f32, f32), theme: &Theme) -> Region {
+        let padding = theme.spacing.padding;
+        let (width, height) = match tooltip {
+
+            // There is no way to measure text before rendering, so estimate that the average character is half as wide as it is high
+            Tooltip::Text(text) => (text.chars().count() as f32 * theme.fonts.small_height * 0.5 + 2.0 * padding, theme.fonts.small_height + 2.0 * padding),
+            Tooltip::Custom { width, height, render: _ } => (*width, *height)
+        };
+
+        let min_x = (mouse_pos.0 + padding).min(1.0 - width).max(-1.0);
+        let max_y = (mouse_pos.1 - 3.0 * padding).max(-1.0 + height).min(1.0);
+        Region::new(min_x, max_y - height, min_x + width, max_y)
+    }
+}
+
+impl Layer for TooltipLayer {
+
+    fn on_mouse_move(&mut self, _new_pos: Option<(f32, f32)>, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_mouse_click(&mut self, _click: ClickInfo, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_mouse_scroll(&mut self, _delta: f64, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_key_down(&mut self, _keys: &KeyInfo, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_key_up(&mut self, _keys: &KeyInfo, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_composition(&mut self, _composition: &CompositionInfo, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_context_menu(&mut self, _manager: &ContainerManager) -> ConsumableEventResult {
+        ConsumableEventResult::dont_consume()
+    }
+
+    fn on_copy(&mut self) -> Option<ClipboardData> {
+        None
+    }
+
+    fn on_paste(&mut self, _clipboard: &ClipboardData) -> bool {
+        false
+    }
+
+    fn on_cut(&mut self) -> Option<ClipboardData> {
+        None
+    }
+
+    fn on_update(&mut self, manager: &ContainerManager) -> EventResult {
+        let visible = manager.get_tooltips().borrow().get_visible();
+        let next_shown = visible.map(|(tooltip, mouse_pos)| {
+            let region = Self::compute_region(&tooltip, mouse_pos, &manager.get_theme());
+            (tooltip, region)
+        });
+
+        let changed = match (&self.shown, &next_shown) {
+            (Some((old_tooltip, old_region)), Some((new_tooltip, new_region))) => !Rc::ptr_eq(old_tooltip, new_tooltip) || old_region != new_region,
+            (None, None) => false,
+            _ => true
+        };
+
+        if changed {
+            if let Some((_tooltip, old_region)) = &self.shown {
+                self.previous_region = Some(*old_region);
+            }
+            self.shown = next_shown;
+            self.needs_render = self.shown.is_some();
+        }
+
+        None
+    }
+
+    fn predict_render(&mut self) -> Vec<PlannedRenderAction> {
+        let mut actions = Vec::new();
+
+        // This layer draws nothing in the previous region, so the layers behind it need to draw it again
+        if let Some(previous_region) = self.previous_region.take() {
+            actions.push(PlannedRenderAction::new(previous_region, RenderOpacity::Mixed));
+        }
+
+        if self.needs_render {
+            if let Some((_tooltip, region)) = &self.shown {
+                actions.push(PlannedRenderAction::new(*region, RenderOpacity::Solid));
+            }
+        }
+
+        actions
+    }
+
+    fn force_partial_render(&mut self, regions: &[Region]) -> Vec<PlannedRenderAction> {
+        if !self.needs_render {
+            if let Some((_tooltip, shown_region)) = &self.shown {
+                if regions.iter().any(|region| region.intersects_with(*shown_region)) {
+                    self.needs_render = true;
+                    return vec![PlannedRenderAction::new(*shown_region, RenderOpacity::Solid)];
+                }
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn on_render(&mut self, gl: &WebGlRenderingContext, manager: &ContainerManager) -> RenderResult {
+        if self.needs_render {
+            self.needs_render = false;
+
+            if let Some((tooltip, region)) = &self.shown {
+
+                // The phase of the last component that rendered shouldn't apply to what this layer draws itself
+                manager.switch_render_phase(gl, None);
+                let mut params = RenderParams::new(gl, manager);
+                match &**tooltip {
+                    Tooltip::Text(text) => {
+                        let theme = manager.get_theme();
+                        let padding = theme.spacing.padding;
+                        fill_region(&mut params, *region, theme.palette.surface);
+                        draw_outline(&mut params, *region, theme.palette.border);
+
+                        draw_text(&mut params, text, inset_region(*region, padding, padding), theme.palette.text);
+                    }, Tooltip::Custom { width: _, height: _, render } => render(&mut params, *region)
+                };
+                let mut render_context = manager.get_render_context().borrow_mut();
+                render_context.add_dirty_region(*region);
+                render_context.flush();
+            }
+        }
+
+        RenderResult::without_cursor()
+    }
+
+    fn force_render(&mut self) {
+        self.needs_render = self.shown.is_some();
+    }
+
+    // The manager keeps updating while a tooltip is waiting to be shown, so this layer never needs to ask for it
+    fn wants_update(&mut self) -> bool {
+        false
+    }
+
+    fn wants_render(&mut self) -> bool {
+        self.needs_render || self.previous_region.is_some()
+    }
+
+    fn snapshot(&mut self) -> LayerSnapshot {
+        LayerSnapshot::new("TooltipLayer")
+    }
+
+    fn add_component(&mut self, _component: Rc<RefCell<dyn Component>>) {
+        panic!("A TooltipLayer can't have components");
+    }
+}
@@ -6,10 +6,42 @@ mod layer;
 mod cursor;
 mod component;
 mod params;
+mod shortcut;
+mod ime;
+mod widget;
+mod theme;
+mod tooltip;
+mod menu;
+mod shape;
+mod rendering;
+mod regions;
+mod clock;
+mod animation;
+mod timer;
+mod accessibility;
+mod inspector;
+mod snapshot;
+mod profiler;
 
 pub use manager::*;
 pub use container::*;
 pub use layer::*;
 pub use cursor::*;
 pub use component::*;
-pub use params::*;
\ No newline at end of file
+pub use params::*;
+pub use shortcut::*;
+pub use ime::*;
+pub use widget::*;
+pub use theme::*;
+pub use tooltip::*;
+pub use menu::*;
+pub use shape::*;
+pub use rendering::*;
+pub use regions::*;
+pub use clock::*;
+pub use animation::*;
+pub use timer::*;
+pub use accessibility::*;
+pub use inspector::*;
+pub use snapshot::*;
+pub use profiler::*;
\ No newline at end of file
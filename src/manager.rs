@@ -1,9 +1,13 @@
 use crate::*;
 
-use std::cell::RefCell;
+use std::cell::{
+    Cell,
+    RefCell
+};
 use std::rc::Rc;
 
 use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
 
 use wasmuri_core::print;
 use wasmuri_events::*;
@@ -11,8 +15,10 @@ use wasmuri_events::*;
 use wasmuri_text::TextRenderer;
 
 use web_sys::{
+    Event,
     HtmlElement,
     HtmlCanvasElement,
+    WebGl2RenderingContext,
     WebGlRenderingContext
 };
 
@@ -71,6 +77,19 @@ impl ConsumableEventResult {
 
 pub type ContainerRenderResult = Cursor;
 
+/// Determines when the ContainerManager updates and renders its container
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum LoopMode {
+
+    /// The container is updated and rendered every frame. This is the default.
+    Continuous,
+
+    /// The container is only updated when input arrived, a behavior is animating, a timer is scheduled, a continuous update
+    /// listener is present or request_frame was called. It is only rendered when something wants to render. This saves a lot
+    /// of battery on idle pages.
+    OnDemand
+}
+
 pub trait ResizeListener {
 
     fn on_resize(&self, manager: &ContainerManager, event: &ResizeEvent);
@@ -82,17 +101,68 @@ pub struct ContainerManager {
     resize_listener: Option<Box<dyn ResizeListener>>,
     prev_cursor: Option<Cursor>,
     gl: WebGlRenderingContext,
+    gl2: Option<WebGl2RenderingContext>,
     
     current_container: Option<Rc<RefCell<dyn Container>>>,
 
-    text_renderer: RefCell<TextRenderer>
+    text_renderer: RefCell<TextRenderer>,
+    render_context: RefCell<RenderContext>,
+    render_phases: RefCell<RenderPhaseRegistry>,
+
+    shortcuts: RefCell<ShortcutRegistry>,
+    shortcut_listener: Option<Box<dyn ShortcutListener>>,
+
+    ime: ImeBridge,
+
+    accessibility: RefCell<AccessibilityMirror>,
+
+    theme: RefCell<Rc<Theme>>,
+
+    tooltips: RefCell<TooltipState>,
+
+    context_menu: RefCell<ContextMenuState>,
+
+    inspector: RefCell<InspectorState>,
+
+    profiler: RefCell<Profiler>,
+
+    default_prevented: Cell<bool>,
+
+    clock: Rc<dyn Clock>,
+    target_frame_rate: f64,
+    update_frame: Cell<Option<FrameInfo>>,
+    render_frame: Cell<Option<FrameInfo>>,
+
+    loop_mode: LoopMode,
+
+    /// True if something happened since the last update that requires the next update to happen, even in LoopMode::OnDemand
+    wake_requested: Cell<bool>,
+
+    /// The result of TooltipState::is_waiting during the previous check for idleness
+    tooltip_was_waiting: Cell<bool>,
+
+    /// True if the theme was changed since the last render, so the current container needs to re-render everything
+    theme_changed: Cell<bool>
 }
 
 impl ContainerManager {
 
+    /// Starts a ContainerManager that uses WebGL 1
     pub fn start(canvas: HtmlCanvasElement, resize_listener: Option<Box<dyn ResizeListener>>, leak_self: bool) -> Rc<RefCell<ContainerManager>> {
+        Self::start_with_gl(canvas, resize_listener, leak_self, GlVersion::WebGl1)
+    }
+
+    /// Starts a ContainerManager that uses the given version of WebGL if the browser supports it, and WebGL 1 otherwise. Behaviors
+    /// can use get_gl_version() to find out which version they got.
+    ///
+    /// The TextRenderer can only be created from the canvas and always asks it for a WebGL 1 context. A canvas has only one
+    /// context, so the TextRenderer is created first and create_gl will fall back to that WebGL 1 context when WebGL 2 is preferred.
+    pub fn start_with_gl(canvas: HtmlCanvasElement, resize_listener: Option<Box<dyn ResizeListener>>, leak_self: bool, 
+            gl_version: GlVersion) -> Rc<RefCell<ContainerManager>> {
 
-        let gl = wasmuri_core::get_gl(&canvas);
+        // This must happen before create_gl: once the canvas has a webgl2 context, the TextRenderer wouldn't get any context
+        let text_renderer = RefCell::new(TextRenderer::from_canvas(&canvas));
+        let (gl, gl2) = create_gl(&canvas, gl_version);
 
         let html_canvas = canvas.clone();
 
@@ -104,18 +174,52 @@ impl ContainerManager {
         html_canvas.set_height(height);
         gl.viewport(0, 0, width as i32, height as i32);
 
-        let text_renderer = RefCell::new(TextRenderer::from_canvas(&html_canvas));
+        let render_context = RefCell::new(RenderContext::new(&gl, gl2.as_ref()));
+        let ime = ImeBridge::new(&html_canvas);
+        let accessibility = RefCell::new(AccessibilityMirror::new(&html_canvas, &ime));
         set_event_source(&html_canvas.dyn_into::<HtmlElement>().expect("A canvas should be an HtmlElement"));
 
         let manager = ContainerManager {
             canvas,
             prev_cursor: None,
             gl,
+            gl2,
             resize_listener,
 
             current_container: None,
 
-            text_renderer
+            text_renderer,
+            render_context,
+            render_phases: RefCell::new(RenderPhaseRegistry::new()),
+
+            shortcuts: RefCell::new(ShortcutRegistry::new()),
+            shortcut_listener: None,
+
+            ime,
+
+            accessibility,
+
+            theme: RefCell::new(Rc::new(Theme::light())),
+
+            tooltips: RefCell::new(TooltipState::new()),
+
+            context_menu: RefCell::new(ContextMenuState::new()),
+
+            inspector: RefCell::new(InspectorState::new()),
+
+            profiler: RefCell::new(Profiler::new()),
+
+            default_prevented: Cell::new(false),
+
+            clock: Rc::new(PerformanceClock::new()),
+            target_frame_rate: 60.0,
+            update_frame: Cell::new(None),
+            render_frame: Cell::new(None),
+
+            loop_mode: LoopMode::Continuous,
+            wake_requested: Cell::new(true),
+            tooltip_was_waiting: Cell::new(false),
+            theme_changed: Cell::new(false)
         };
 
         let manager_cell = Rc::new(RefCell::new(manager));
@@ -131,6 +235,9 @@ impl ContainerManager {
         start_listen(&manager_cell, &COPY_HANDLER);
         start_listen(&manager_cell, &PASTE_HANDLER);
         start_listen(&manager_cell, &CUT_HANDLER);
+        manager_cell.borrow().ime.start_listening(&manager_cell);
+        manager_cell.borrow().accessibility.borrow_mut().start_listening(&manager_cell);
+        listen_context_menu(&manager_cell);
 
         if leak_self {
             std::mem::forget(Rc::clone(&manager_cell))
@@ -141,21 +248,195 @@ impl ContainerManager {
 
     pub fn set_container_cell(&mut self, new_container: Rc<RefCell<dyn Container>>){
         self.current_container = Some(new_container);
+        self.request_frame();
     }
 
     pub fn set_resize_listener(&mut self, new_listener: Option<Box<dyn ResizeListener>>){
         self.resize_listener = new_listener;
     }
 
+    pub fn set_shortcut_listener(&mut self, new_listener: Option<Box<dyn ShortcutListener>>){
+        self.shortcut_listener = new_listener;
+    }
+
+    /// Gives a reference to the container-wide shortcuts, which is inside a RefCell. These shortcuts are checked before the
+    /// current container gets the key event and are passed to the ShortcutListener of this manager.
+    pub fn get_shortcuts(&self) -> &RefCell<ShortcutRegistry> {
+        &self.shortcuts
+    }
+
+    /// Gets the current theme, which components should use to determine their colors, text heights and spacing
+    pub fn get_theme(&self) -> Rc<Theme> {
+        Rc::clone(&self.theme.borrow())
+    }
+
+    /// Changes the current theme and forces the current container to re-render everything during the next render so that all
+    /// components will use the new theme. This can also be called by behaviors while they are handling an event.
+    pub fn set_theme(&self, theme: Rc<Theme>) {
+        self.theme.replace(theme);
+        self.theme_changed.set(true);
+        self.request_frame();
+    }
+
+    /// Gives a reference to the TooltipState of this manager, which is inside a RefCell. It can be used to change the dwell time.
+    pub fn get_tooltips(&self) -> &RefCell<TooltipState> {
+        &self.tooltips
+    }
+
+    /// Gives a reference to the ContextMenuState of this manager, which is inside a RefCell. The open menu is drawn by the
+    /// ContextMenuLayer of the current container.
+    pub fn get_context_menu(&self) -> &RefCell<ContextMenuState> {
+        &self.context_menu
+    }
+
+    /// Gives a reference to the InspectorState of this manager, which is inside a RefCell. It can be used to enable the inspector
+    /// without the hotkey of the InspectorLayer. In LoopMode::OnDemand, request_frame should be called after enabling it.
+    pub fn get_inspector(&self) -> &RefCell<InspectorState> {
+        &self.inspector
+    }
+
+    /// Gives a reference to the Profiler of this manager, which is inside a RefCell. It is disabled by default.
+    pub fn get_profiler(&self) -> &RefCell<Profiler> {
+        &self.profiler
+    }
+
+    /// Calls the given function and records how long it took as a span of the current frame, if the profiler is enabled
+    pub fn profile<T, F: FnOnce() -> T>(&self, name: &'static str, category: ProfileCategory, subject: ProfileSubject, function: F) -> T {
+        self.profiler.borrow_mut().begin_span(name, category, subject);
+        let result = function();
+        self.profiler.borrow_mut().end_span();
+        result
+    }
+
+    /// Creates a read-only description of the current container, its layers and their components, or None if there is no current
+    /// container. Use ContainerSnapshot::to_json to attach it to a bug report. This also returns None when it is called while the
+    /// current container is busy, for instance from a behavior during an event, because the container can't be inspected then.
+    pub fn snapshot(&self) -> Option<ContainerSnapshot> {
+        let container = self.current_container.as_ref()?;
+        let mut container = container.try_borrow_mut().ok()?;
+        Some(container.snapshot())
+    }
+
+    /// Closes the current context menu and lets the current container open the context menu at the mouse position
+    fn open_context_menu(&mut self) {
+        self.context_menu.borrow_mut().close();
+        self.tooltips.borrow_mut().dismiss();
+        self.process_result(|container, manager| container.on_context_menu(manager));
+    }
+
+    /// Replaces the Clock of this manager, which is used to compute the FrameInfo of the updates and renders. Headless runners can
+    /// use a TestClock to control time.
+    pub fn set_clock(&mut self, clock: Rc<dyn Clock>) {
+        self.tooltips.borrow_mut().set_clock(Rc::clone(&clock));
+        self.profiler.borrow_mut().set_clock(Rc::clone(&clock));
+        self.clock = clock;
+    }
+
+    pub fn get_clock(&self) -> &Rc<dyn Clock> {
+        &self.clock
+    }
+
+    /// Sets the frame rate (in frames per second) the updates and renders are aiming for. This doesn't change how often the
+    /// events are fired, but behaviors can use it to decide how much work they can do per frame. The default is 60.
+    pub fn set_target_frame_rate(&mut self, target_frame_rate: f64) {
+        self.target_frame_rate = target_frame_rate;
+    }
+
+    /// Gets the FrameInfo of the current (or last) update
+    pub fn get_update_frame(&self) -> FrameInfo {
+        self.update_frame.get().unwrap_or_else(|| FrameInfo::first(self.clock.now(), self.target_frame_rate))
+    }
+
+    /// Gets the FrameInfo of the current (or last) render
+    pub fn get_render_frame(&self) -> FrameInfo {
+        self.render_frame.get().unwrap_or_else(|| FrameInfo::first(self.clock.now(), self.target_frame_rate))
+    }
+
+    /// Starts the next frame of the given kind at the current time of the clock
+    fn next_frame(&self, frame: &Cell<Option<FrameInfo>>) {
+        let time = self.clock.now();
+        frame.set(Some(match frame.get() {
+            Some(previous) => previous.next(time, self.target_frame_rate),
+            None => FrameInfo::first(time, self.target_frame_rate)
+        }));
+    }
+
+    /// Sets the LoopMode of this manager. The default is LoopMode::Continuous.
+    pub fn set_loop_mode(&mut self, loop_mode: LoopMode) {
+        self.loop_mode = loop_mode;
+        self.request_frame();
+    }
+
+    pub fn get_loop_mode(&self) -> LoopMode {
+        self.loop_mode
+    }
+
+    /// Makes sure the container will be updated during the next frame, even if it is idle in LoopMode::OnDemand. This should be
+    /// called when something outside the container changes state that the container depends on. Behaviors that need to be
+    /// updated every frame should use LayerAgent::make_continuous_update_listener instead of calling this during every update.
+    pub fn request_frame(&self) {
+        self.wake_requested.set(true);
+    }
+
+    /// Checks if the next update can be skipped because nothing would change
+    fn is_idle(&self) -> bool {
+
+        // When the dwell time of a tooltip has just passed, the TooltipLayer needs 1 more update to pick up the visible tooltip
+        let tooltip_waiting = self.tooltips.borrow().is_waiting();
+        let tooltip_dwell_ended = self.tooltip_was_waiting.replace(tooltip_waiting) && !tooltip_waiting;
+
+        if self.loop_mode == LoopMode::Continuous || self.wake_requested.get() || tooltip_waiting || tooltip_dwell_ended {
+            return false;
+        }
+
+        let mut idle = true;
+        self.with_container(|container, _manager| idle = !container.wants_update());
+        idle
+    }
+
+    /// Marks that the default action of the browser for the DOM event that is currently being processed should be prevented
+    pub(crate) fn prevent_default(&self) {
+        self.default_prevented.set(true);
+    }
+
+    /// Checks whether the default action of the current DOM event should be prevented and resets it for the next event
+    fn take_default_prevented(&self) -> bool {
+        self.default_prevented.replace(false)
+    }
+
     pub fn get_gl(&self) -> &WebGlRenderingContext {
         &self.gl
     }
 
+    /// Gets the WebGL 2 context of this manager, which is the same context as get_gl(), or None if this manager uses WebGL 1
+    pub fn get_gl2(&self) -> Option<&WebGl2RenderingContext> {
+        self.gl2.as_ref()
+    }
+
+    pub fn get_gl_version(&self) -> GlVersion {
+        match self.gl2 {
+            Some(_) => GlVersion::WebGl2,
+            None => GlVersion::WebGl1
+        }
+    }
+
     pub fn get_canvas(&self) -> &HtmlCanvasElement {
         &self.canvas
     }
 
-    fn process_result<F: FnMut(&mut dyn Container, &ContainerManager) -> EventResult>(&mut self, mut result_function: F) {
+    fn process_result<F: FnMut(&mut dyn Container, &ContainerManager) -> EventResult>(&mut self, result_function: F) {
+
+        // The event could have changed the state of the container
+        self.request_frame();
+        self.apply_result(result_function);
+    }
+
+    /// Like process_result, but doesn't request a frame. This is used for the update itself, which would otherwise keep the
+    /// manager awake forever.
+    fn apply_result<F: FnMut(&mut dyn Container, &ContainerManager) -> EventResult>(&mut self, mut result_function: F) {
+
+        // Only the current event should be affected by prevent_default
+        self.default_prevented.set(false);
 
         let maybe_new_container = match &self.current_container {
             Some(container) => {
@@ -179,11 +460,89 @@ impl ContainerManager {
         };
     }
 
+    /// Gives a reference to the ImeBridge of this manager. Text input behaviors should use it to receive composed text.
+    pub fn get_ime(&self) -> &ImeBridge {
+        &self.ime
+    }
+
+    /// Passes the composition events that were fired by the ImeBridge to the current container
+    pub(crate) fn process_pending_compositions(&mut self) {
+        for composition in self.ime.take_pending_events() {
+            self.process_result(|container, manager| container.on_composition(&composition, manager));
+        }
+    }
+
+    /// Gives a reference to the AccessibilityMirror of this manager, which is inside a RefCell
+    pub fn get_accessibility(&self) -> &RefCell<AccessibilityMirror> {
+        &self.accessibility
+    }
+
+    /// Passes the actions of assistive technology that were fired while this manager was busy to the current container
+    pub(crate) fn process_pending_access_actions(&mut self) {
+        let actions = self.accessibility.borrow().take_pending_actions();
+        for (key, action) in actions {
+
+            // The text field that is typing should lose its focus when assistive technology moves the focus to another node
+            if action == AccessAction::Focus {
+                if let Some(owner) = self.ime.get_owner().filter(|owner| *owner != key) {
+                    self.process_result(|container, manager| container.on_access_action(owner, AccessAction::Blur, manager));
+                }
+            }
+            self.process_result(|container, manager| container.on_access_action(key, action, manager));
+        }
+    }
+
+    /// Updates the AccessibilityMirror to the current accessibility nodes of the components of the current container
+    fn sync_accessibility(&self) {
+        let mut nodes = Vec::new();
+        self.with_container(|container, _manager| container.collect_access_nodes(&mut nodes));
+        self.accessibility.borrow_mut().sync(nodes);
+    }
+
     /// Gives a reference to the TextRenderer of this ContainerManager, which is inside a RefCell.
     pub fn get_text_renderer(&self) -> &RefCell<TextRenderer> {
         &self.text_renderer
     }
 
+    /// Gives a reference to the RenderContext of this ContainerManager, which is inside a RefCell. Behaviors should use it to
+    /// draw quads and sprites, so that they can be batched with those of other behaviors.
+    pub fn get_render_context(&self) -> &RefCell<RenderContext> {
+        &self.render_context
+    }
+
+    /// Gives a reference to the RenderPhaseRegistry of this ContainerManager, which is inside a RefCell. It can be used to register
+    /// the setup and teardown of custom render phases.
+    pub fn get_render_phases(&self) -> &RefCell<RenderPhaseRegistry> {
+        &self.render_phases
+    }
+
+    /// Switches to the given render phase (or to no phase at all), calling the end hook of the current phase and the begin hook
+    /// of the new phase. Nothing happens if the given phase is already active.
+    pub(crate) fn switch_render_phase(&self, gl: &WebGlRenderingContext, phase: Option<RenderPhase>) {
+        if self.render_phases.borrow().get_current_phase() == phase {
+            return;
+        }
+        let subject = match phase {
+            Some(phase) => ProfileSubject::Phase(phase),
+            None => ProfileSubject::None
+        };
+        self.profiler.borrow_mut().begin_span("switch_render_phase", ProfileCategory::PhaseSwitch, subject);
+        let (end, begin) = self.render_phases.borrow_mut().switch(phase);
+
+        // The quads that were drawn during the previous phase should be drawn with the GL state of that phase
+        self.render_context.borrow_mut().flush();
+
+        let mut params = RenderParams::new(gl, self);
+        if let Some(end) = end {
+            end(&mut params);
+            self.render_context.borrow_mut().flush_and_invalidate();
+        }
+        if let Some(begin) = begin {
+            begin(&mut params);
+        }
+        self.profiler.borrow_mut().end_span();
+    }
+
     /// Converts the position in pixel coordinates (the offset in pixels between the point and the corner of the canvas) to
     /// OpenGL coordinates.
     pub fn to_gl_coords(&self, pixel_coords: (i32, i32)) -> (f32, f32) {
@@ -198,13 +557,57 @@ fn start_listen<T>(manager_cell: &Rc<RefCell<ContainerManager>>, handler: &Handl
     handler.add_listener(weak_manager);
 }
 
+/// Suppresses the native context menu of the browser on the canvas and opens our own context menu instead
+fn listen_context_menu(manager_cell: &Rc<RefCell<ContainerManager>>) {
+    let weak_manager = Rc::downgrade(manager_cell);
+
+    let closure = Closure::wrap(Box::new(move |event: Event| {
+        event.prevent_default();
+
+        if let Some(manager_cell) = weak_manager.upgrade() {
+            if let Ok(mut manager) = manager_cell.try_borrow_mut() {
+                manager.open_context_menu();
+            }
+        }
+    }) as Box<dyn FnMut(Event)>);
+
+    manager_cell.borrow().canvas.add_event_listener_with_callback("contextmenu", closure.as_ref().unchecked_ref())
+            .expect("Should be able to add contextmenu listener");
+
+    // The closure needs to live as long as the canvas
+    closure.forget();
+}
+
 impl Listener<KeyDownEvent> for ContainerManager {
 
     fn process(&mut self, event: &KeyDownEvent){
-        self.process_result(|container, manager| {
-            container.on_key_down(&KeyInfo::new(event.key_event.key(), event.key_event.ctrl_key(), event.key_event.shift_key(), 
-                    event.key_event.alt_key(), event.key_event.meta_key()), manager)
-        });
+        self.tooltips.borrow_mut().dismiss();
+
+        let keys = KeyInfo::new(event.key_event.key(), event.key_event.ctrl_key(), event.key_event.shift_key(), 
+                event.key_event.alt_key(), event.key_event.meta_key()).with_composing(event.key_event.is_composing());
+
+        // Key events that are part of a text composition should never trigger shortcuts. An open context menu is modal, so it gets
+        // all keys until it closes.
+        let skip_shortcuts = keys.is_composing() || self.context_menu.borrow().is_open();
+        let shortcut_match = if skip_shortcuts { ShortcutMatch::None } else { self.shortcuts.borrow_mut().process(&keys) };
+        match shortcut_match {
+            ShortcutMatch::Triggered(action) => {
+                let maybe_new_container = match &self.shortcut_listener {
+                    Some(listener) => listener.on_shortcut(self, &action),
+                    None => None
+                };
+                if let Some(new_container) = maybe_new_container {
+                    self.set_container_cell(new_container);
+                }
+                return;
+            }, ShortcutMatch::Pending => return,
+            ShortcutMatch::None => {}
+        };
+
+        self.process_result(|container, manager| container.on_key_down(&keys, manager));
+        if self.take_default_prevented() {
+            event.key_event.prevent_default();
+        }
     }
 }
 
@@ -213,18 +616,25 @@ impl Listener<KeyUpEvent> for ContainerManager {
     fn process(&mut self, event: &KeyUpEvent){
         self.process_result(|container, manager| {
             container.on_key_up(&KeyInfo::new(event.key_event.key(), event.key_event.ctrl_key(), event.key_event.shift_key(), 
-                        event.key_event.alt_key(), event.key_event.meta_key()), manager)
+                        event.key_event.alt_key(), event.key_event.meta_key()).with_composing(event.key_event.is_composing()), manager)
         });
+        if self.take_default_prevented() {
+            event.key_event.prevent_default();
+        }
     }
 }
 
 impl Listener<MouseClickEvent> for ContainerManager {
 
     fn process(&mut self, event: &MouseClickEvent){
+        self.tooltips.borrow_mut().dismiss();
         self.process_result(|container, manager| {
             container.on_mouse_click(ClickInfo::new(event.mouse_event.button(), event.mouse_event.ctrl_key(), 
                         event.mouse_event.shift_key(), event.mouse_event.alt_key(), event.mouse_event.meta_key()), manager)
         });
+        if self.take_default_prevented() {
+            event.mouse_event.prevent_default();
+        }
     }
 }
 
@@ -239,19 +649,42 @@ impl Listener<MouseScrollEvent> for ContainerManager {
 
     fn process(&mut self, event: &MouseScrollEvent){
         self.process_result(|container, manager| container.on_mouse_scroll(event, manager));
+        if self.take_default_prevented() {
+            event.mouse_event.prevent_default();
+        }
     }
 }
 
 impl Listener<UpdateEvent> for ContainerManager {
 
     fn process(&mut self, _event: &UpdateEvent){
-        self.process_result(|container, manager| container.on_update(manager));
+
+        self.next_frame(&self.update_frame);
+
+        // Composition events and accessibility actions that were fired while this manager was busy are processed now
+        self.process_pending_compositions();
+        self.process_pending_access_actions();
+
+        if self.is_idle() {
+            return;
+        }
+
+        // Reset before the update, so that request_frame() calls during the update (or a container switch) are kept for the next one
+        self.wake_requested.set(false);
+
+        self.profiler.borrow_mut().begin_frame(FrameKind::Update, self.get_update_frame());
+        self.apply_result(|container, manager| {
+            manager.profile("Container::on_update", ProfileCategory::ContainerUpdate, ProfileSubject::None, || container.on_update(manager))
+        });
+        self.sync_accessibility();
+        self.profiler.borrow_mut().end_frame();
     }
 }
 
 impl Listener<ResizeEvent> for ContainerManager {
 
     fn process(&mut self, event: &ResizeEvent){
+        self.request_frame();
         match &self.resize_listener {
             Some(listener) => { listener.on_resize(self, event); },
             None => {
@@ -274,8 +707,35 @@ impl Listener<RenderEvent> for ContainerManager {
         let mut change_cursor = false;
         let mut result = None;
 
+        // The container can't be borrowed in set_theme because behaviors may call it while the container is handling an event
+        if self.theme_changed.replace(false) {
+            self.with_container(|container, _manager| container.force_render());
+        }
+
+        if self.loop_mode == LoopMode::OnDemand {
+            let mut wants_render = false;
+            if let Some(container) = &self.current_container {
+                wants_render = container.borrow_mut().wants_render();
+            }
+            if !wants_render {
+                return;
+            }
+        }
+
+        self.next_frame(&self.render_frame);
+        self.profiler.borrow_mut().begin_frame(FrameKind::Render, self.get_render_frame());
+        self.render_context.borrow_mut().begin_frame();
+
         self.with_container(|container, manager| {
-            let local_result = container.render(&manager.gl, manager);
+            let local_result = manager.profile("Container::render", ProfileCategory::ContainerRender, ProfileSubject::None, || {
+                container.render(&manager.gl, manager)
+            });
+
+            // End the phase of the last component that rendered, so that every render starts without an active phase
+            manager.switch_render_phase(&manager.gl, None);
+
+            // Draw the quads of the last layer that rendered and leave the GL state to whoever uses it next
+            manager.render_context.borrow_mut().flush_and_invalidate();
             
             if manager.prev_cursor.is_none() {
                 change_cursor = true;
@@ -292,12 +752,15 @@ impl Listener<RenderEvent> for ContainerManager {
             css.set_property("cursor", &result.as_ref().unwrap().to_css_value()).expect("Should be able to set cursor property");
             self.prev_cursor = result;
         }
+
+        self.profiler.borrow_mut().end_frame();
     }
 }
 
 impl Listener<CopyEvent> for ContainerManager {
 
     fn process(&mut self, event: &CopyEvent) {
+        self.request_frame();
         self.with_container(|container, _manager| {
             match event.clipboard_event.clipboard_data() {
                 Some(clipboard) => {
@@ -322,6 +785,7 @@ impl Listener<CopyEvent> for ContainerManager {
 impl Listener<PasteEvent> for ContainerManager {
 
     fn process(&mut self, event: &PasteEvent) {
+        self.request_frame();
         self.with_container(|container, _manager| {
             match event.clipboard_event.clipboard_data() {
                 Some(clipboard) => {
@@ -339,6 +803,7 @@ impl Listener<PasteEvent> for ContainerManager {
 impl Listener<CutEvent> for ContainerManager {
 
     fn process(&mut self, event: &CutEvent) {
+        self.request_frame();
         self.with_container(|container, _manager| {
             match event.clipboard_event.clipboard_data() {
                 Some(clipboard) => {
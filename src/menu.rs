@@ -0,0 +1,287 @@
+use crate::*;
+
+use std::cell::RefCell;
+use std::rc::Weak;
+
+#[derive(Clone)]
+pub enum MenuEntry {
+
+    /// An entry that calls the context_menu_action method of the behavior that opened the menu when it is activated
+    Item {
+        label: String,
+        action: String,
+        shortcut: Option<KeyChord>,
+        enabled: bool
+    },
+
+    /// A horizontal line between groups of entries
+    Separator,
+
+    /// An entry that opens another list of entries next to the menu
+    Submenu {
+        label: String,
+        entries: Vec<MenuEntry>,
+        enabled: bool
+    }
+}
+
+impl MenuEntry {
+
+    pub fn item(label: &str, action: &str) -> MenuEntry {
+        MenuEntry::Item {
+            label: label.to_string(),
+            action: action.to_string(),
+            shortcut: None,
+            enabled: true
+        }
+    }
+
+    pub fn submenu(label: &str, entries: Vec<MenuEntry>) -> MenuEntry {
+        MenuEntry::Submenu {
+            label: label.to_string(),
+            entries,
+            enabled: true
+        }
+    }
+
+    /// Shows the given chord next to the label of this item. This is only a hint for the user, the shortcut itself still needs to be
+    /// claimed separately. This has no effect on separators and submenus.
+    pub fn with_shortcut(mut self, chord: KeyChord) -> MenuEntry {
+        if let MenuEntry::Item { shortcut, .. } = &mut self {
+            *shortcut = Some(chord);
+        }
+        self
+    }
+
+    /// Marks this entry as disabled: it will be drawn, but can't be highlighted or activated
+    pub fn disabled(mut self) -> MenuEntry {
+        match &mut self {
+            MenuEntry::Item { enabled, .. } => *enabled = false,
+            MenuEntry::Submenu { enabled, .. } => *enabled = false,
+            MenuEntry::Separator => {}
+        };
+        self
+    }
+
+    pub fn is_selectable(&self) -> bool {
+        match self {
+            MenuEntry::Item { enabled, .. } => *enabled,
+            MenuEntry::Submenu { enabled, .. } => *enabled,
+            MenuEntry::Separator => false
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ContextMenu {
+
+    entries: Vec<MenuEntry>
+}
+
+impl ContextMenu {
+
+    pub fn new(entries: Vec<MenuEntry>) -> ContextMenu {
+        ContextMenu {
+            entries
+        }
+    }
+
+    pub fn get_entries(&self) -> &[MenuEntry] {
+        &self.entries
+    }
+}
+
+struct OpenMenu {
+
+    menu: ContextMenu,
+    position: (f32, f32),
+    owner: Weak<RefCell<dyn ComponentBehavior>>,
+
+    /// The highlighted entry of each open panel. The first panel is the menu itself and each next panel is the submenu
+    /// of the highlighted entry of the previous panel.
+    highlights: Vec<Option<usize>>
+}
+
+/// Keeps track of the context menu that is currently open. The ContextMenuLayer draws the menu and handles its input.
+pub struct ContextMenuState {
+
+    open_menu: Option<OpenMenu>,
+
+    /// Increased whenever the menu is opened, closed or navigated, so that the ContextMenuLayer knows when to render again
+    version: u64
+}
+
+impl ContextMenuState {
+
+    pub fn new() -> ContextMenuState {
+        ContextMenuState {
+            open_menu: None,
+            version: 0
+        }
+    }
+
+    /// Opens the given menu at the given position (in OpenGL coordinates). The actions of the menu will be passed to the owner.
+    pub fn open(&mut self, menu: ContextMenu, position: (f32, f32), owner: Weak<RefCell<dyn ComponentBehavior>>) {
+        self.open_menu = Some(OpenMenu {
+            menu,
+            position,
+            owner,
+            highlights: vec![None]
+        });
+        self.version += 1;
+    }
+
+    pub fn close(&mut self) {
+        if self.open_menu.is_some() {
+            self.open_menu = None;
+            self.version += 1;
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open_menu.is_some()
+    }
+
+    pub fn get_version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn get_position(&self) -> Option<(f32, f32)> {
+        self.open_menu.as_ref().map(|open_menu| open_menu.position)
+    }
+
+    pub fn get_num_panels(&self) -> usize {
+        self.open_menu.as_ref().map(|open_menu| open_menu.highlights.len()).unwrap_or(0)
+    }
+
+    /// Gets the entries of the panel with the given index. Panel 0 is the menu itself.
+    pub fn get_panel_entries(&self, panel: usize) -> &[MenuEntry] {
+        let open_menu = self.open_menu.as_ref().expect("The menu should be open");
+        let mut entries = open_menu.menu.get_entries();
+        for level in 0 .. panel {
+            let highlighted = open_menu.highlights[level].expect("Only highlighted submenus can be open");
+            entries = match &entries[highlighted] {
+                MenuEntry::Submenu { entries, .. } => entries,
+                _ => panic!("Only submenus can be open")
+            };
+        }
+        entries
+    }
+
+    pub fn get_highlight(&self, panel: usize) -> Option<usize> {
+        self.open_menu.as_ref().and_then(|open_menu| open_menu.highlights.get(panel).cloned().flatten())
+    }
+
+    /// Highlights the given entry of the given panel and closes the panels after it. If the entry is a submenu, its panel is opened.
+    pub fn highlight(&mut self, panel: usize, entry: Option<usize>) {
+        if panel >= self.get_num_panels() {
+            return;
+        }
+
+        let selectable = entry.map(|index| self.get_panel_entries(panel)[index].is_selectable()).unwrap_or(true);
+        if !selectable {
+            return;
+        }
+
+        let is_submenu = entry.map(|index| self.is_submenu(panel, index)).unwrap_or(false);
+        let expected_num_panels = if is_submenu { panel + 2 } else { panel + 1 };
+        if self.get_highlight(panel) == entry && self.get_num_panels() == expected_num_panels {
+            return;
+        }
+
+        let open_menu = self.open_menu.as_mut().unwrap();
+        open_menu.highlights.truncate(panel + 1);
+        open_menu.highlights[panel] = entry;
+        if is_submenu {
+            open_menu.highlights.push(None);
+        }
+        self.version += 1;
+    }
+
+    /// Moves the highlight of the last open panel up or down, skipping separators and disabled entries
+    pub fn move_highlight(&mut self, down: bool) {
+        let panel = self.get_active_panel();
+        let entries = self.get_panel_entries(panel);
+        let num_entries = entries.len() as isize;
+
+        let step = if down { 1 } else { -1 };
+        let mut index = match self.get_highlight(panel) {
+            Some(current) => current as isize,
+            None => if down { -1 } else { num_entries }
+        };
+
+        let mut next_highlight = None;
+        for _attempt in 0 .. num_entries {
+            index = (index + step + num_entries) % num_entries;
+            if entries[index as usize].is_selectable() {
+                next_highlight = Some(index as usize);
+                break;
+            }
+        }
+
+        if next_highlight.is_some() {
+            let open_menu = self.open_menu.as_mut().unwrap();
+            open_menu.highlights.truncate(panel + 1);
+            open_menu.highlights[panel] = next_highlight;
+            self.version += 1;
+        }
+    }
+
+    /// Opens the submenu of the highlighted entry of the active panel and highlights its first selectable entry
+    pub fn enter_submenu(&mut self) {
+        let panel = self.get_active_panel();
+        if let Some(index) = self.get_highlight(panel) {
+            if self.is_submenu(panel, index) {
+                let open_menu = self.open_menu.as_mut().unwrap();
+                open_menu.highlights.truncate(panel + 1);
+                open_menu.highlights.push(None);
+                self.version += 1;
+                self.move_highlight(true);
+            }
+        }
+    }
+
+    /// Closes the last submenu panel, if there is more than 1 panel
+    pub fn leave_submenu(&mut self) {
+        let panel = self.get_active_panel();
+        if panel > 0 {
+            let open_menu = self.open_menu.as_mut().unwrap();
+            open_menu.highlights.truncate(panel);
+            self.version += 1;
+        }
+    }
+
+    /// Activates the highlighted entry of the active panel. If it is an item, the menu is closed and the owner and action are returned.
+    /// If it is a submenu, the submenu is entered instead.
+    pub fn activate(&mut self) -> Option<(Weak<RefCell<dyn ComponentBehavior>>, String)> {
+        let panel = self.get_active_panel();
+        let index = self.get_highlight(panel)?;
+        let action = match &self.get_panel_entries(panel)[index] {
+            MenuEntry::Item { action, enabled: true, .. } => Some(action.clone()),
+            _ => None
+        };
+
+        match action {
+            Some(action) => {
+                let owner = self.open_menu.as_ref().unwrap().owner.clone();
+                self.close();
+                Some((owner, action))
+            }, None => {
+                self.enter_submenu();
+                None
+            }
+        }
+    }
+
+    fn is_submenu(&self, panel: usize, index: usize) -> bool {
+        match self.get_panel_entries(panel)[index] {
+            MenuEntry::Submenu { .. } => true,
+            _ => false
+        }
+    }
+
+    /// The keyboard navigates through the last open panel
+    fn get_active_panel(&self) -> usize {
+        self.get_num_panels().saturating_sub(1)
+    }
+}
@@ -1,6 +1,14 @@
-use crate::ContainerManager;
+use crate::*;
 
-use web_sys::WebGlRenderingContext;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasmuri_core::*;
+
+use web_sys::{
+    WebGl2RenderingContext,
+    WebGlRenderingContext
+};
 
 pub struct KeyDownParams<'a> {
 
@@ -109,7 +117,10 @@ impl<'a> MouseScrollParams<'a> {
 pub struct RenderParams<'a> {
 
     pub gl: &'a WebGlRenderingContext, 
-    pub manager: &'a ContainerManager
+    pub manager: &'a ContainerManager,
+
+    /// The time, delta time and index of the current render
+    pub frame: FrameInfo
 }
 
 impl<'a> RenderParams<'a> {
@@ -117,6 +128,140 @@ impl<'a> RenderParams<'a> {
     pub fn new(gl: &'a WebGlRenderingContext, manager: &'a ContainerManager) -> RenderParams<'a> {
         RenderParams {
             gl,
+            manager,
+            frame: manager.get_render_frame()
+        }
+    }
+
+    /// Gets the current theme of the manager
+    pub fn get_theme(&self) -> Rc<Theme> {
+        self.manager.get_theme()
+    }
+
+    /// Gets the WebGL 2 view of the gl field, or None if the manager uses WebGL 1
+    pub fn get_gl2(&self) -> Option<&'a WebGl2RenderingContext> {
+        self.manager.get_gl2()
+    }
+
+    pub fn get_gl_version(&self) -> GlVersion {
+        self.manager.get_gl_version()
+    }
+
+    /// Gets the RenderContext of the manager. Behaviors that use the gl field directly should call flush_and_invalidate() on it
+    /// first, so that the quads of the previous behaviors are drawn before theirs.
+    pub fn get_render_context(&self) -> &'a RefCell<RenderContext> {
+        self.manager.get_render_context()
+    }
+
+    /// Fills the given region with the given color, batched with the quads of the other behaviors
+    pub fn fill(&self, region: Region, color: Color) {
+        self.manager.get_render_context().borrow_mut().fill_quad(region, color);
+    }
+
+    /// Overwrites the given region with the given color, without blending. See RenderContext::clear_region.
+    pub fn clear(&self, region: Region, color: Color) {
+        self.manager.get_render_context().borrow_mut().clear_region(region, color);
+    }
+
+    /// Draws the given part of the given texture in the given region, batched with the sprites of the other behaviors
+    pub fn draw_sprite(&self, region: Region, texture: &Rc<SpriteTexture>, texture_region: Region, tint: Color) {
+        self.manager.get_render_context().borrow_mut().draw_sprite(region, texture, texture_region, tint);
+    }
+}
+
+pub struct ShortcutParams<'a> {
+
+    pub action: &'a str,
+    pub manager: &'a ContainerManager
+}
+
+impl<'a> ShortcutParams<'a> {
+
+    pub fn new(action: &'a str, manager: &'a ContainerManager) -> ShortcutParams<'a> {
+        ShortcutParams {
+            action,
+            manager
+        }
+    }
+}
+
+pub struct ContextMenuParams<'a> {
+
+    pub mouse_pos: (f32,f32),
+    pub manager: &'a ContainerManager
+}
+
+impl<'a> ContextMenuParams<'a> {
+
+    pub fn new(mouse_pos: (f32,f32), manager: &'a ContainerManager) -> ContextMenuParams<'a> {
+        ContextMenuParams {
+            mouse_pos,
+            manager
+        }
+    }
+}
+
+pub struct MenuActionParams<'a> {
+
+    pub action: &'a str,
+    pub manager: &'a ContainerManager
+}
+
+impl<'a> MenuActionParams<'a> {
+
+    pub fn new(action: &'a str, manager: &'a ContainerManager) -> MenuActionParams<'a> {
+        MenuActionParams {
+            action,
+            manager
+        }
+    }
+}
+
+pub struct CaptureParams<'a> {
+
+    pub event: &'a DispatchEvent<'a>,
+    pub context: &'a mut DispatchContext,
+    pub manager: &'a ContainerManager
+}
+
+impl<'a> CaptureParams<'a> {
+
+    pub fn new(event: &'a DispatchEvent<'a>, context: &'a mut DispatchContext, manager: &'a ContainerManager) -> CaptureParams<'a> {
+        CaptureParams {
+            event,
+            context,
+            manager
+        }
+    }
+}
+
+pub struct AccessActionParams<'a> {
+
+    pub action: AccessAction,
+    pub manager: &'a ContainerManager
+}
+
+impl<'a> AccessActionParams<'a> {
+
+    pub fn new(action: AccessAction, manager: &'a ContainerManager) -> AccessActionParams<'a> {
+        AccessActionParams {
+            action,
+            manager
+        }
+    }
+}
+
+pub struct CompositionParams<'a> {
+
+    pub composition: &'a CompositionInfo,
+    pub manager: &'a ContainerManager
+}
+
+impl<'a> CompositionParams<'a> {
+
+    pub fn new(composition: &'a CompositionInfo, manager: &'a ContainerManager) -> CompositionParams<'a> {
+        CompositionParams {
+            composition,
             manager
         }
     }
@@ -138,14 +283,18 @@ impl<'a> CursorParams<'a> {
 
 pub struct UpdateParams<'a> {
 
-    pub manager: &'a ContainerManager
+    pub manager: &'a ContainerManager,
+
+    /// The time, delta time and index of the current update
+    pub frame: FrameInfo
 }
 
 impl<'a> UpdateParams<'a> {
 
     pub fn new(manager: &'a ContainerManager) -> UpdateParams<'a> {
         UpdateParams {
-            manager
+            manager,
+            frame: manager.get_update_frame()
         }
     }
 }
@@ -199,7 +348,8 @@ pub struct KeyInfo {
     control_down: bool,
     shift_down: bool,
     alt_down: bool,
-    meta_down: bool
+    meta_down: bool,
+    composing: bool
 }
 
 impl KeyInfo {
@@ -210,10 +360,17 @@ impl KeyInfo {
             control_down,
             shift_down,
             alt_down,
-            meta_down
+            meta_down,
+            composing: false
         }
     }
 
+    /// Marks whether this key event was fired while the user is composing text with an input method editor
+    pub fn with_composing(mut self, composing: bool) -> KeyInfo {
+        self.composing = composing;
+        self
+    }
+
     pub fn get_key(&self) -> &str {
         &self.key
     }
@@ -233,6 +390,12 @@ impl KeyInfo {
     pub fn is_meta_down(&self) -> bool {
         self.meta_down
     }
+
+    /// Checks if this key event is part of a text composition. Text input behaviors should ignore such key events because the
+    /// composed text will be passed to them as composition events.
+    pub fn is_composing(&self) -> bool {
+        self.composing
+    }
 }
 
 pub enum ClipboardData {
@@ -0,0 +1,341 @@
+use crate::*;
+
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// The kind of work a ProfileSpan measured
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum ProfileCategory {
+
+    /// The on_update of the container
+    ContainerUpdate,
+
+    /// The render of the container
+    ContainerRender,
+
+    /// The propagation of the render actions of a layer to the layers in front of and behind it
+    Propagation,
+
+    /// The on_render of a layer
+    LayerRender,
+
+    /// A call to ComponentBehavior::update
+    BehaviorUpdate,
+
+    /// A call to ComponentBehavior::render
+    BehaviorRender,
+
+    /// The end and begin hooks of a render phase switch
+    PhaseSwitch,
+
+    /// Spans that were started by the application itself
+    Custom
+}
+
+impl ProfileCategory {
+
+    /// Gets the name of this category in the Chrome trace format
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ProfileCategory::ContainerUpdate => "container_update",
+            ProfileCategory::ContainerRender => "container_render",
+            ProfileCategory::Propagation => "propagation",
+            ProfileCategory::LayerRender => "layer_render",
+            ProfileCategory::BehaviorUpdate => "behavior_update",
+            ProfileCategory::BehaviorRender => "behavior_render",
+            ProfileCategory::PhaseSwitch => "phase_switch",
+            ProfileCategory::Custom => "custom"
+        }
+    }
+}
+
+/// The thing a ProfileSpan was measured for, if it is more specific than its category
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum ProfileSubject {
+
+    None,
+
+    /// The index of a layer in its container
+    Layer(usize),
+
+    Component(ComponentKey),
+
+    Phase(RenderPhase)
+}
+
+/// A measured piece of work during a frame
+#[derive(Clone,Debug)]
+pub struct ProfileSpan {
+
+    name: &'static str,
+    category: ProfileCategory,
+    subject: ProfileSubject,
+
+    /// The time (in milliseconds, according to the Clock of the Profiler) at which the work started
+    start: f64,
+    duration: f64,
+
+    /// The number of spans this span is nested in
+    depth: usize
+}
+
+impl ProfileSpan {
+
+    pub fn get_name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn get_category(&self) -> ProfileCategory {
+        self.category
+    }
+
+    pub fn get_subject(&self) -> ProfileSubject {
+        self.subject
+    }
+
+    pub fn get_start(&self) -> f64 {
+        self.start
+    }
+
+    pub fn get_duration(&self) -> f64 {
+        self.duration
+    }
+
+    pub fn get_depth(&self) -> usize {
+        self.depth
+    }
+}
+
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum FrameKind {
+
+    Update,
+    Render
+}
+
+/// The spans that were measured during a single update or render frame
+#[derive(Clone,Debug)]
+pub struct FrameProfile {
+
+    kind: FrameKind,
+    frame_index: u64,
+
+    start: f64,
+    duration: f64,
+
+    /// The spans in the order in which they were started
+    spans: Vec<ProfileSpan>
+}
+
+impl FrameProfile {
+
+    pub fn get_kind(&self) -> FrameKind {
+        self.kind
+    }
+
+    /// Gets the index of the FrameInfo of this frame
+    pub fn get_frame_index(&self) -> u64 {
+        self.frame_index
+    }
+
+    pub fn get_start(&self) -> f64 {
+        self.start
+    }
+
+    pub fn get_duration(&self) -> f64 {
+        self.duration
+    }
+
+    pub fn get_spans(&self) -> &[ProfileSpan] {
+        &self.spans
+    }
+
+    /// Gets the total duration of the spans of the given category. Nested spans of the same category are counted twice.
+    pub fn get_category_duration(&self, category: ProfileCategory) -> f64 {
+        self.spans.iter().filter(|span| span.category == category).map(|span| span.duration).sum()
+    }
+}
+
+/// Measures how long the container, its layers and the behaviors of its components take during update and render frames. The
+/// ContainerManager has a Profiler that is disabled by default. When it is enabled, the most recent frames are kept in a ring
+/// buffer, which can be shown with a FrameGraphLayer or exported with to_chrome_trace.
+pub struct Profiler {
+
+    enabled: bool,
+    clock: Rc<dyn Clock>,
+
+    capacity: usize,
+    frames: VecDeque<FrameProfile>,
+
+    /// The total number of frames that were recorded since this Profiler was created
+    recorded_frames: u64,
+
+    current: Option<FrameProfile>,
+
+    /// The indices (in the spans of the current frame) of the spans that have been started, but not yet ended
+    open_spans: Vec<usize>
+}
+
+impl Profiler {
+
+    /// Creates a disabled Profiler that keeps the last 120 frames
+    pub fn new() -> Profiler {
+        Profiler {
+            enabled: false,
+            clock: Rc::new(PerformanceClock::new()),
+            capacity: 120,
+            frames: VecDeque::new(),
+            recorded_frames: 0,
+            current: None,
+            open_spans: Vec::new()
+        }
+    }
+
+    /// Sets the Clock that is used to measure the spans. The ContainerManager gives its own Clock to its Profiler.
+    pub fn set_clock(&mut self, clock: Rc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or disables the profiler. The frames that were recorded so far are kept when it is disabled.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.current = None;
+            self.open_spans.clear();
+        }
+    }
+
+    /// Gets the maximum number of frames that are kept
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sets the maximum number of frames that are kept. If more frames were recorded, the oldest ones are discarded.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.frames.len() > capacity {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Gets the recorded frames, from old to new
+    pub fn get_frames(&self) -> &VecDeque<FrameProfile> {
+        &self.frames
+    }
+
+    /// Gets the total number of frames that were recorded, including the frames that no longer fit in the ring buffer
+    pub fn get_recorded_frame_count(&self) -> u64 {
+        self.recorded_frames
+    }
+
+    /// Discards all recorded frames
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    pub(crate) fn begin_frame(&mut self, kind: FrameKind, frame: FrameInfo) {
+        if !self.enabled {
+            return;
+        }
+        self.current = Some(FrameProfile {
+            kind,
+            frame_index: frame.get_frame_index(),
+            start: self.clock.now(),
+            duration: 0.0,
+            spans: Vec::new()
+        });
+        self.open_spans.clear();
+    }
+
+    pub(crate) fn end_frame(&mut self) {
+        if let Some(mut frame) = self.current.take() {
+            let now = self.clock.now();
+            for index in self.open_spans.drain(..) {
+                let span = &mut frame.spans[index];
+                span.duration = now - span.start;
+            }
+            frame.duration = now - frame.start;
+
+            if self.capacity > 0 {
+                if self.frames.len() == self.capacity {
+                    self.frames.pop_front();
+                }
+                self.frames.push_back(frame);
+            }
+            self.recorded_frames += 1;
+        }
+    }
+
+    /// Starts measuring a span of work in the current frame. Every call to begin_span must be followed by a call to end_span.
+    /// Nothing happens if the profiler is disabled or if this is called outside an update or render frame.
+    pub fn begin_span(&mut self, name: &'static str, category: ProfileCategory, subject: ProfileSubject) {
+        if let Some(frame) = &mut self.current {
+            let now = self.clock.now();
+            frame.spans.push(ProfileSpan {
+                name,
+                category,
+                subject,
+                start: now,
+                duration: 0.0,
+                depth: self.open_spans.len()
+            });
+            self.open_spans.push(frame.spans.len() - 1);
+        }
+    }
+
+    /// Stops measuring the span that was started most recently
+    pub fn end_span(&mut self) {
+        if let Some(frame) = &mut self.current {
+            if let Some(index) = self.open_spans.pop() {
+                let span = &mut frame.spans[index];
+                span.duration = self.clock.now() - span.start;
+            }
+        }
+    }
+
+    /// Converts the recorded frames to the JSON trace event format of Chrome, which can be loaded in chrome://tracing and in the
+    /// performance panel of the developer tools
+    pub fn to_chrome_trace(&self) -> String {
+        let mut out = String::new();
+        out.push_str("{\"displayTimeUnit\":\"ms\",\"traceEvents\":[");
+        let mut first = true;
+        for frame in &self.frames {
+            let frame_name = match frame.kind {
+                FrameKind::Update => "update frame",
+                FrameKind::Render => "render frame"
+            };
+            write_trace_event(&mut out, &mut first, frame_name, "frame", frame.start, frame.duration,
+                    &format!("\"frame_index\":{}", frame.frame_index));
+
+            for span in &frame.spans {
+                let args = match span.subject {
+                    ProfileSubject::None => String::new(),
+                    ProfileSubject::Layer(index) => format!("\"layer\":{}", index),
+                    ProfileSubject::Component(key) => format!("\"component\":{}", key.get_value()),
+                    ProfileSubject::Phase(phase) => format!("\"phase\":{}", phase.get_order())
+                };
+                write_trace_event(&mut out, &mut first, span.name, span.category.get_name(), span.start, span.duration, &args);
+            }
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+/// Appends a complete ('X') trace event. The times are converted from milliseconds to the microseconds of the trace format.
+fn write_trace_event(out: &mut String, first: &mut bool, name: &str, category: &str, start: f64, duration: f64, args: &str) {
+    if !*first {
+        out.push(',');
+    }
+    *first = false;
+
+    out.push_str("{\"name\":");
+    write_json_string(out, name);
+    out.push_str(",\"cat\":");
+    write_json_string(out, category);
+    out.push_str(&format!(",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":1,\"args\":{{{}}}}}", start * 1000.0, duration * 1000.0, args));
+}
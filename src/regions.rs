@@ -0,0 +1,181 @@
+use wasmuri_core::Region;
+
+/// A set of points in OpenGL coordinates, stored as a list of regions that don't overlap each other. It is used to keep track of
+/// which parts of the viewport need to be re-rendered and which parts are covered by solid render actions.
+#[derive(Clone,Default)]
+pub struct RegionSet {
+
+    regions: Vec<Region>
+}
+
+impl RegionSet {
+
+    pub fn new() -> RegionSet {
+        RegionSet {
+            regions: Vec::new()
+        }
+    }
+
+    pub fn from_region(region: Region) -> RegionSet {
+        RegionSet {
+            regions: vec![region]
+        }
+    }
+
+    /// Creates the union of the given regions, which are allowed to overlap
+    pub fn from_regions(regions: &[Region]) -> RegionSet {
+        let mut set = RegionSet::new();
+        for region in regions {
+            set.add(*region);
+        }
+        set
+    }
+
+    /// Gets the regions of this set. They don't overlap each other, but they are not necessarily the same regions that were added.
+    pub fn get_regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+
+    /// Adds the given region to this set. Only the parts of the region that are not yet in this set are stored.
+    pub fn add(&mut self, region: Region) {
+        let mut uncovered = region.get_uncovered_regions(&self.regions);
+        self.regions.append(&mut uncovered);
+    }
+
+    /// Adds all regions of the other set to this set
+    pub fn union(&mut self, other: &RegionSet) {
+        for region in &other.regions {
+            self.add(*region);
+        }
+    }
+
+    /// Removes all points inside the given region from this set
+    pub fn subtract(&mut self, region: Region) {
+        if !self.intersects_with(region) {
+            return;
+        }
+
+        let obstacle = vec![region];
+        let mut remaining = Vec::with_capacity(self.regions.len());
+        for existing in &self.regions {
+            if existing.intersects_with(region) {
+                remaining.append(&mut existing.get_uncovered_regions(&obstacle));
+            } else {
+                remaining.push(*existing);
+            }
+        }
+        self.regions = remaining;
+    }
+
+    /// Removes all points of the other set from this set
+    pub fn subtract_set(&mut self, other: &RegionSet) {
+        for region in &other.regions {
+            self.subtract(*region);
+        }
+    }
+
+    /// Checks if every point of the given region is inside this set
+    pub fn covers(&self, region: Region) -> bool {
+        region.get_uncovered_regions(&self.regions).is_empty()
+    }
+
+    /// Checks if at least 1 region of this set intersects with the given region
+    pub fn intersects_with(&self, region: Region) -> bool {
+        self.regions.iter().any(|existing| existing.intersects_with(region))
+    }
+
+    /// Gets the parts of the given region that are not in this set
+    pub fn get_uncovered(&self, region: Region) -> RegionSet {
+        RegionSet {
+            regions: region.get_uncovered_regions(&self.regions)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn get_area(set: &RegionSet) -> f32 {
+        set.get_regions().iter().map(|region| (region.get_max_x() - region.get_min_x()) * (region.get_max_y() - region.get_min_y())).sum()
+    }
+
+    #[test]
+    fn test_add() {
+        let mut set = RegionSet::new();
+        assert!(set.is_empty());
+
+        set.add(Region::new(0.0, 0.0, 0.5, 0.5));
+        set.add(Region::new(0.25, 0.0, 0.75, 0.5));
+        assert_eq!(0.375, get_area(&set));
+        assert!(set.covers(Region::new(0.0, 0.0, 0.75, 0.5)));
+        assert!(!set.covers(Region::new(0.0, 0.0, 0.75, 0.75)));
+
+        // Adding a region that is already covered shouldn't change anything
+        let num_regions = set.get_regions().len();
+        set.add(Region::new(0.25, 0.25, 0.5, 0.5));
+        assert_eq!(num_regions, set.get_regions().len());
+    }
+
+    #[test]
+    fn test_from_regions() {
+        let set = RegionSet::from_regions(&[Region::new(0.0, 0.0, 0.5, 0.5), Region::new(0.0, 0.0, 0.5, 0.5), Region::new(0.5, 0.0, 1.0, 0.5)]);
+        assert_eq!(0.5, get_area(&set));
+        assert!(set.covers(Region::new(0.0, 0.0, 1.0, 0.5)));
+    }
+
+    #[test]
+    fn test_subtract() {
+        let mut set = RegionSet::from_region(Region::new(0.0, 0.0, 1.0, 1.0));
+        set.subtract(Region::new(0.25, 0.25, 0.75, 0.75));
+        assert_eq!(0.75, get_area(&set));
+        assert!(!set.intersects_with(Region::new(0.375, 0.375, 0.625, 0.625)));
+        assert!(set.covers(Region::new(0.0, 0.0, 1.0, 0.25)));
+
+        // Subtracting a region that doesn't intersect shouldn't change anything
+        set.subtract(Region::new(2.0, 2.0, 3.0, 3.0));
+        assert_eq!(0.75, get_area(&set));
+
+        set.subtract(Region::new(0.0, 0.0, 1.0, 1.0));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_union_and_subtract_set() {
+        let mut set = RegionSet::from_region(Region::new(0.0, 0.0, 0.5, 0.5));
+        set.union(&RegionSet::from_region(Region::new(0.5, 0.0, 1.0, 0.5)));
+        assert_eq!(0.5, get_area(&set));
+
+        set.subtract_set(&RegionSet::from_regions(&[Region::new(0.0, 0.0, 0.25, 0.5), Region::new(0.75, 0.0, 1.0, 0.5)]));
+        assert_eq!(0.25, get_area(&set));
+        assert!(set.covers(Region::new(0.25, 0.0, 0.75, 0.5)));
+    }
+
+    #[test]
+    fn test_get_uncovered() {
+        let set = RegionSet::from_region(Region::new(0.0, 0.0, 0.5, 1.0));
+        let uncovered = set.get_uncovered(Region::new(0.25, 0.0, 1.0, 1.0));
+        assert_eq!(0.5, get_area(&uncovered));
+        assert!(uncovered.covers(Region::new(0.5, 0.0, 1.0, 1.0)));
+        assert!(!uncovered.intersects_with(Region::new(0.0, 0.0, 0.375, 1.0)));
+
+        assert!(set.get_uncovered(Region::new(0.0, 0.0, 0.25, 0.25)).is_empty());
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut set = RegionSet::from_region(Region::new(0.0, 0.0, 1.0, 1.0));
+        set.clear();
+        assert!(set.is_empty());
+        assert!(!set.intersects_with(Region::new(0.0, 0.0, 1.0, 1.0)));
+    }
+}
@@ -0,0 +1,305 @@
+use crate::*;
+
+use std::rc::Rc;
+use std::sync::atomic::{
+    AtomicU32,
+    Ordering
+};
+
+use wasmuri_core::*;
+
+use web_sys::{
+    WebGlBuffer,
+    WebGlProgram,
+    WebGlRenderingContext,
+    WebGlShader,
+    WebGlTexture,
+    WebGlUniformLocation
+};
+
+static NEXT_GL_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Gives a new id for a GL object, so that a GlState can recognize it
+pub fn next_gl_id() -> u32 {
+    NEXT_GL_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A texture that can be drawn with RenderContext::draw_sprite. All sprites that use the same texture can be drawn with a single draw call.
+pub struct SpriteTexture {
+
+    id: u32,
+    texture: WebGlTexture,
+
+    /// True if row 0 of the texture is the bottom row rather than the top row, which is the case for textures that were rendered to
+    flipped: bool
+}
+
+impl SpriteTexture {
+
+    pub fn new(texture: WebGlTexture) -> SpriteTexture {
+        SpriteTexture {
+            id: next_gl_id(),
+            texture,
+            flipped: false
+        }
+    }
+
+    pub fn celled(texture: WebGlTexture) -> Rc<SpriteTexture> {
+        Rc::new(Self::new(texture))
+    }
+
+    /// Creates a SpriteTexture for a texture whose bottom row is row 0, like textures that are attached to a framebuffer. The texture
+    /// regions of sprites of this texture are still given with (0, 0) as the top left corner.
+    pub fn flipped(texture: WebGlTexture) -> SpriteTexture {
+        SpriteTexture {
+            id: next_gl_id(),
+            texture,
+            flipped: true
+        }
+    }
+
+    pub fn is_flipped(&self) -> bool {
+        self.flipped
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn get_texture(&self) -> &WebGlTexture {
+        &self.texture
+    }
+}
+
+const VERTEX_SHADER: &str = "
+attribute vec2 a_position;
+attribute vec2 a_tex_coord;
+attribute vec4 a_color;
+
+varying vec2 v_tex_coord;
+varying vec4 v_color;
+
+void main() {
+    v_tex_coord = a_tex_coord;
+    v_color = a_color;
+    gl_Position = vec4(a_position, 0.0, 1.0);
+}
+";
+
+const FRAGMENT_SHADER: &str = "
+precision mediump float;
+
+uniform sampler2D u_texture;
+
+varying vec2 v_tex_coord;
+varying vec4 v_color;
+
+void main() {
+    gl_FragColor = texture2D(u_texture, v_tex_coord) * v_color;
+}
+";
+
+/// The position (2), texture coordinates (2) and color (4) of each vertex
+const FLOATS_PER_VERTEX: usize = 8;
+
+/// The indices are unsigned shorts, so the number of vertices in a single draw call is limited
+const MAX_QUADS: usize = 8192;
+
+/// Collects colored quads and textured sprites and draws them with as few draw calls as possible. Quads are drawn as sprites with
+/// a white texture, so quads and sprites only need separate draw calls when the sprite texture changes.
+pub struct QuadBatch {
+
+    program_id: u32,
+    program: WebGlProgram,
+
+    vertex_buffer: WebGlBuffer,
+    index_buffer: WebGlBuffer,
+
+    position_location: u32,
+    tex_coord_location: u32,
+    color_location: u32,
+    texture_location: Option<WebGlUniformLocation>,
+
+    white_texture: Rc<SpriteTexture>,
+
+    vertices: Vec<f32>,
+    texture: Option<Rc<SpriteTexture>>,
+
+    draw_calls: u32
+}
+
+impl QuadBatch {
+
+    pub fn new(gl: &WebGlRenderingContext) -> QuadBatch {
+        let vertex_shader = compile_shader(gl, WebGlRenderingContext::VERTEX_SHADER, VERTEX_SHADER);
+        let fragment_shader = compile_shader(gl, WebGlRenderingContext::FRAGMENT_SHADER, FRAGMENT_SHADER);
+        let program = link_program(gl, &vertex_shader, &fragment_shader);
+
+        let position_location = gl.get_attrib_location(&program, "a_position") as u32;
+        let tex_coord_location = gl.get_attrib_location(&program, "a_tex_coord") as u32;
+        let color_location = gl.get_attrib_location(&program, "a_color") as u32;
+        let texture_location = gl.get_uniform_location(&program, "u_texture");
+
+        let vertex_buffer = gl.create_buffer().expect("Should be able to create vertex buffer");
+        let index_buffer = gl.create_buffer().expect("Should be able to create index buffer");
+
+        // Every quad consists of 2 triangles, so the indices are the same for every batch
+        let mut index_bytes = Vec::with_capacity(MAX_QUADS * 6 * 2);
+        for quad in 0 .. MAX_QUADS {
+            let first = (quad * 4) as u16;
+            for index in &[first, first + 1, first + 2, first + 2, first + 3, first] {
+                index_bytes.extend_from_slice(&index.to_le_bytes());
+            }
+        }
+        gl.bind_buffer(WebGlRenderingContext::ELEMENT_ARRAY_BUFFER, Some(&index_buffer));
+        gl.buffer_data_with_u8_array(WebGlRenderingContext::ELEMENT_ARRAY_BUFFER, &index_bytes, WebGlRenderingContext::STATIC_DRAW);
+
+        let white_texture = gl.create_texture().expect("Should be able to create texture");
+        gl.bind_texture(WebGlRenderingContext::TEXTURE_2D, Some(&white_texture));
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            WebGlRenderingContext::TEXTURE_2D, 0, WebGlRenderingContext::RGBA as i32, 1, 1, 0,
+            WebGlRenderingContext::RGBA, WebGlRenderingContext::UNSIGNED_BYTE, Some(&[255, 255, 255, 255])
+        ).expect("Should be able to fill white texture");
+        gl.tex_parameteri(WebGlRenderingContext::TEXTURE_2D, WebGlRenderingContext::TEXTURE_MIN_FILTER, WebGlRenderingContext::NEAREST as i32);
+        gl.tex_parameteri(WebGlRenderingContext::TEXTURE_2D, WebGlRenderingContext::TEXTURE_MAG_FILTER, WebGlRenderingContext::NEAREST as i32);
+
+        QuadBatch {
+            program_id: next_gl_id(),
+            program,
+
+            vertex_buffer,
+            index_buffer,
+
+            position_location,
+            tex_coord_location,
+            color_location,
+            texture_location,
+
+            white_texture: SpriteTexture::celled(white_texture),
+
+            vertices: Vec::with_capacity(64 * 4 * FLOATS_PER_VERTEX),
+            texture: None,
+
+            draw_calls: 0
+        }
+    }
+
+    fn get_num_quads(&self) -> usize {
+        self.vertices.len() / (4 * FLOATS_PER_VERTEX)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Adds a quad with the given color to the batch
+    pub fn add_quad(&mut self, gl: &WebGlRenderingContext, state: &mut GlState, region: Region, color: Color) {
+        let white_texture = Rc::clone(&self.white_texture);
+        self.add_sprite(gl, state, region, &white_texture, Region::new(0.0, 0.0, 1.0, 1.0), color);
+    }
+
+    /// Adds a sprite with the given texture to the batch. The texture region is in texture coordinates, where (0, 0) is the top left
+    /// corner of the texture. The colors of the texture are multiplied by the tint. If the texture differs from the texture of the
+    /// sprites that are already in the batch, the batch is flushed first.
+    pub fn add_sprite(&mut self, gl: &WebGlRenderingContext, state: &mut GlState, region: Region, texture: &Rc<SpriteTexture>, 
+            texture_region: Region, tint: Color) {
+
+        let same_texture = match &self.texture {
+            Some(current) => current.get_id() == texture.get_id(),
+            None => true
+        };
+        if !same_texture || self.get_num_quads() == MAX_QUADS {
+            self.flush(gl, state);
+        }
+        self.texture = Some(Rc::clone(texture));
+
+        let color = [tint.get_red_float(), tint.get_green_float(), tint.get_blue_float(), tint.get_alpha_float()];
+        let (top_v, bottom_v) = if texture.is_flipped() {
+            (1.0 - texture_region.get_min_y(), 1.0 - texture_region.get_max_y())
+        } else {
+            (texture_region.get_min_y(), texture_region.get_max_y())
+        };
+        let corners = [
+            (region.get_min_x(), region.get_max_y(), texture_region.get_min_x(), top_v),
+            (region.get_max_x(), region.get_max_y(), texture_region.get_max_x(), top_v),
+            (region.get_max_x(), region.get_min_y(), texture_region.get_max_x(), bottom_v),
+            (region.get_min_x(), region.get_min_y(), texture_region.get_min_x(), bottom_v)
+        ];
+        for (x, y, u, v) in &corners {
+            self.vertices.extend_from_slice(&[*x, *y, *u, *v]);
+            self.vertices.extend_from_slice(&color);
+        }
+    }
+
+    /// Draws all quads and sprites in the batch with a single draw call and clears the batch
+    pub fn flush(&mut self, gl: &WebGlRenderingContext, state: &mut GlState) {
+        if self.vertices.is_empty() {
+            return;
+        }
+
+        let num_quads = self.get_num_quads();
+        let texture = self.texture.take().expect("A non-empty batch should have a texture");
+
+        state.use_program(gl, self.program_id, &self.program);
+        state.set_blend(gl, true);
+        gl.active_texture(WebGlRenderingContext::TEXTURE0);
+        state.bind_texture(gl, texture.get_id(), texture.get_texture());
+        gl.uniform1i(self.texture_location.as_ref(), 0);
+
+        let mut vertex_bytes = Vec::with_capacity(self.vertices.len() * 4);
+        for value in &self.vertices {
+            vertex_bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        gl.bind_buffer(WebGlRenderingContext::ARRAY_BUFFER, Some(&self.vertex_buffer));
+        gl.buffer_data_with_u8_array(WebGlRenderingContext::ARRAY_BUFFER, &vertex_bytes, WebGlRenderingContext::STREAM_DRAW);
+
+        let stride = (FLOATS_PER_VERTEX * 4) as i32;
+        gl.vertex_attrib_pointer_with_i32(self.position_location, 2, WebGlRenderingContext::FLOAT, false, stride, 0);
+        gl.vertex_attrib_pointer_with_i32(self.tex_coord_location, 2, WebGlRenderingContext::FLOAT, false, stride, 8);
+        gl.vertex_attrib_pointer_with_i32(self.color_location, 4, WebGlRenderingContext::FLOAT, false, stride, 16);
+        gl.enable_vertex_attrib_array(self.position_location);
+        gl.enable_vertex_attrib_array(self.tex_coord_location);
+        gl.enable_vertex_attrib_array(self.color_location);
+
+        gl.bind_buffer(WebGlRenderingContext::ELEMENT_ARRAY_BUFFER, Some(&self.index_buffer));
+        gl.draw_elements_with_i32(WebGlRenderingContext::TRIANGLES, (num_quads * 6) as i32, WebGlRenderingContext::UNSIGNED_SHORT, 0);
+
+        self.vertices.clear();
+        self.draw_calls += 1;
+    }
+
+    /// Gets the number of draw calls this batch made since the last call to reset_draw_calls
+    pub fn get_draw_calls(&self) -> u32 {
+        self.draw_calls
+    }
+
+    pub fn reset_draw_calls(&mut self) {
+        self.draw_calls = 0;
+    }
+}
+
+fn compile_shader(gl: &WebGlRenderingContext, shader_type: u32, source: &str) -> WebGlShader {
+    let shader = gl.create_shader(shader_type).expect("Should be able to create shader");
+    gl.shader_source(&shader, source);
+    gl.compile_shader(&shader);
+
+    if !gl.get_shader_parameter(&shader, WebGlRenderingContext::COMPILE_STATUS).as_bool().unwrap_or(false) {
+        panic!("Failed to compile batch shader: {}", gl.get_shader_info_log(&shader).unwrap_or_default());
+    }
+
+    shader
+}
+
+fn link_program(gl: &WebGlRenderingContext, vertex_shader: &WebGlShader, fragment_shader: &WebGlShader) -> WebGlProgram {
+    let program = gl.create_program().expect("Should be able to create program");
+    gl.attach_shader(&program, vertex_shader);
+    gl.attach_shader(&program, fragment_shader);
+    gl.link_program(&program);
+
+    if !gl.get_program_parameter(&program, WebGlRenderingContext::LINK_STATUS).as_bool().unwrap_or(false) {
+        panic!("Failed to link batch program: {}", gl.get_program_info_log(&program).unwrap_or_default());
+    }
+
+    program
+}
@@ -0,0 +1,240 @@
+use crate::*;
+
+use std::rc::Rc;
+
+use wasmuri_core::*;
+use wasmuri_text::TextRenderer;
+
+use web_sys::{
+    WebGl2RenderingContext,
+    WebGlRenderingContext
+};
+
+/// Collects the quads and sprites that are drawn by the behaviors and tracks the GL state, so that consecutive behaviors can
+/// share draw calls and state changes. Every ContainerManager has a RenderContext that can be obtained with get_render_context().
+///
+/// Quads and sprites are not drawn immediately: they are drawn when the batch is flushed. Behaviors that use the
+/// WebGlRenderingContext directly should call flush_and_invalidate() before doing so, to make sure everything that was drawn
+/// before them is on the screen and that the tracked GL state won't be used after they changed it.
+pub struct RenderContext {
+
+    gl: WebGlRenderingContext,
+
+    /// The WebGL 2 view of gl, if the manager uses WebGL 2
+    gl2: Option<WebGl2RenderingContext>,
+
+    state: GlState,
+    batch: QuadBatch,
+
+    /// True if the TextRenderer has been prepared with start_rendering and no quads have been drawn since then
+    text_active: bool,
+
+    /// The region outside of which nothing should be drawn, or None if everything can be drawn
+    clip: Option<Region>,
+
+    /// The regions that were redrawn during the current frame
+    dirty_regions: Vec<Region>
+}
+
+impl RenderContext {
+
+    pub fn new(gl: &WebGlRenderingContext, gl2: Option<&WebGl2RenderingContext>) -> RenderContext {
+        RenderContext {
+            gl: gl.clone(),
+            gl2: gl2.cloned(),
+
+            state: GlState::new(),
+            batch: QuadBatch::new(gl),
+
+            text_active: false,
+
+            clip: None,
+            dirty_regions: Vec::new()
+        }
+    }
+
+    /// Adds a quad with the given color to the batch. Transparent colors are blended with whatever was drawn before them.
+    pub fn fill_quad(&mut self, region: Region, color: Color) {
+        let clipped = match self.clip_region(region) {
+            Some(clipped) => clipped,
+            None => return
+        };
+        self.batch.add_quad(&self.gl, &mut self.state, clipped, color);
+    }
+
+    /// Overwrites the given region with the given color, including its alpha, so transparent colors are not blended with whatever
+    /// was drawn before. This can't be batched: the pending quads are drawn first and the region is cleared with the GL scissor.
+    pub fn clear_region(&mut self, region: Region, color: Color) {
+        let clipped = match self.clip_region(region) {
+            Some(clipped) => clipped,
+            None => return
+        };
+        self.flush();
+
+        let previous_clip = self.clip;
+        self.clip = Some(clipped);
+        self.apply_clip();
+        self.clip = previous_clip;
+
+        self.gl.clear_color(color.get_red_float(), color.get_green_float(), color.get_blue_float(), color.get_alpha_float());
+        self.gl.clear(WebGlRenderingContext::COLOR_BUFFER_BIT);
+
+        // The scissor should keep matching the clip for the behaviors that draw directly
+        self.apply_clip();
+    }
+
+    /// Adds a sprite to the batch. See QuadBatch::add_sprite for the meaning of the parameters.
+    pub fn draw_sprite(&mut self, region: Region, texture: &Rc<SpriteTexture>, texture_region: Region, tint: Color) {
+        let clipped = match self.clip_region(region) {
+            Some(clipped) => clipped,
+            None => return
+        };
+
+        // Cut the same part off the texture region as was cut off the region
+        let width = region.get_max_x() - region.get_min_x();
+        let height = region.get_max_y() - region.get_min_y();
+        let texture_width = texture_region.get_max_x() - texture_region.get_min_x();
+        let texture_height = texture_region.get_max_y() - texture_region.get_min_y();
+        let clipped_texture_region = Region::new(
+            texture_region.get_min_x() + texture_width * (clipped.get_min_x() - region.get_min_x()) / width,
+            texture_region.get_min_y() + texture_height * (region.get_max_y() - clipped.get_max_y()) / height,
+            texture_region.get_max_x() - texture_width * (region.get_max_x() - clipped.get_max_x()) / width,
+            texture_region.get_max_y() - texture_height * (clipped.get_min_y() - region.get_min_y()) / height
+        );
+        self.batch.add_sprite(&self.gl, &mut self.state, clipped, texture, clipped_texture_region, tint);
+    }
+
+    /// Returns the part of the given region that is inside the clip, or None if nothing of it is inside the clip
+    fn clip_region(&self, region: Region) -> Option<Region> {
+        match self.clip {
+            Some(clip) => intersect_regions(region, clip),
+            None => Some(region)
+        }
+    }
+
+    /// Sets the GL scissor to the current clip, so that things that are not drawn by the batch will be clipped as well
+    fn apply_clip(&mut self) {
+        let scissor = self.clip.map(|clip| {
+            let buffer_width = self.gl.drawing_buffer_width() as f32;
+            let buffer_height = self.gl.drawing_buffer_height() as f32;
+
+            let min_x = ((clip.get_min_x() + 1.0) * 0.5 * buffer_width).round() as i32;
+            let min_y = ((clip.get_min_y() + 1.0) * 0.5 * buffer_height).round() as i32;
+            let max_x = ((clip.get_max_x() + 1.0) * 0.5 * buffer_width).round() as i32;
+            let max_y = ((clip.get_max_y() + 1.0) * 0.5 * buffer_height).round() as i32;
+            (min_x, min_y, max_x - min_x, max_y - min_y)
+        });
+        self.state.set_scissor(&self.gl, scissor);
+    }
+
+    /// Prepares the given TextRenderer for drawing text. The pending quads are drawn first, so that text will be drawn on top of
+    /// them. The TextRenderer is only prepared again if quads were drawn since the last time. The text will be clipped to the
+    /// current clip.
+    pub fn begin_text(&mut self, text_renderer: &mut TextRenderer) {
+        self.flush();
+        if !self.text_active {
+            if let Some(gl2) = &self.gl2 {
+                self.state.bind_default_vertex_array(gl2);
+            }
+            text_renderer.start_rendering();
+
+            // The TextRenderer uses its own program, textures and blending
+            self.state.invalidate();
+            self.text_active = true;
+        }
+        self.apply_clip();
+    }
+
+    /// Like begin_text, but prepares the TextRenderer again even if no quads were drawn since the last time. The RenderManager
+    /// does this before every behavior in the Text phase, so that behaviors don't depend on the TextRenderer state that was left
+    /// behind by the previous behavior.
+    pub fn restart_text(&mut self, text_renderer: &mut TextRenderer) {
+        self.text_active = false;
+        self.begin_text(text_renderer);
+    }
+
+    /// Draws all pending quads and sprites
+    pub fn flush(&mut self) {
+        if !self.batch.is_empty() {
+
+            // The quads were already clipped when they were added, and they may have been added with different clips
+            self.state.set_scissor(&self.gl, None);
+            if let Some(gl2) = &self.gl2 {
+                self.state.bind_default_vertex_array(gl2);
+            }
+            self.batch.flush(&self.gl, &mut self.state);
+            self.text_active = false;
+            self.apply_clip();
+        }
+    }
+
+    /// Draws all pending quads and sprites, sets the GL scissor to the current clip and forgets the tracked GL state. This should
+    /// be called before using the WebGlRenderingContext directly. With WebGL 2, the default vertex array object is bound again
+    /// before the next batch is drawn, so behaviors may leave their own vertex array object bound.
+    pub fn flush_and_invalidate(&mut self) {
+        self.flush();
+        self.apply_clip();
+        self.state.invalidate();
+        self.text_active = false;
+    }
+
+    /// Restricts all drawing to the given region, or removes the restriction if it is None. Quads and sprites are clipped before
+    /// they are added to the batch, so changing the clip doesn't require a new draw call. The GL scissor is set to the clip right
+    /// away (and kept that way after every flush), so behaviors that draw with the WebGlRenderingContext directly are clipped too.
+    pub fn set_clip(&mut self, clip: Option<Region>) {
+        self.clip = clip;
+        self.apply_clip();
+    }
+
+    pub fn get_clip(&self) -> Option<Region> {
+        self.clip
+    }
+
+    /// Forgets the dirty regions of the previous frame. This is called by the ContainerManager before the container renders.
+    pub(crate) fn begin_frame(&mut self) {
+        self.dirty_regions.clear();
+    }
+
+    /// Marks the given region as redrawn during the current frame. The RenderManager of each SimpleLayer does this for every
+    /// component that renders, so this only needs to be called by layers that render on their own.
+    pub fn add_dirty_region(&mut self, region: Region) {
+        self.dirty_regions.push(region);
+    }
+
+    /// Removes the dirty regions of the current frame and returns them. Layers that render into a texture use this together with
+    /// restore_dirty_regions, so that the regions they draw into the texture are not reported as redrawn parts of the canvas.
+    pub fn take_dirty_regions(&mut self) -> Vec<Region> {
+        std::mem::replace(&mut self.dirty_regions, Vec::new())
+    }
+
+    /// Replaces the dirty regions of the current frame with the given regions, which were usually obtained with take_dirty_regions
+    pub fn restore_dirty_regions(&mut self, dirty_regions: Vec<Region>) {
+        self.dirty_regions = dirty_regions;
+    }
+
+    /// Gets the regions that were redrawn during the current frame (or the last frame, if no frame is being rendered). The regions
+    /// can overlap.
+    pub fn get_dirty_regions(&self) -> &[Region] {
+        &self.dirty_regions
+    }
+
+    /// Gets the GlState of this context, which can be used to change the GL state without causing redundant state changes
+    pub fn get_state(&mut self) -> &mut GlState {
+        &mut self.state
+    }
+
+    /// Gets the number of draw calls that were made by the batch since the last call to reset_statistics
+    pub fn get_draw_calls(&self) -> u32 {
+        self.batch.get_draw_calls()
+    }
+
+    /// Gets the number of GL state changes that were made since the last call to reset_statistics
+    pub fn get_state_changes(&self) -> u32 {
+        self.state.get_state_changes()
+    }
+
+    pub fn reset_statistics(&mut self) {
+        self.batch.reset_draw_calls();
+        self.state.reset_state_changes();
+    }
+}
@@ -0,0 +1,11 @@
+mod state;
+mod batch;
+mod context;
+mod phase;
+mod version;
+
+pub use state::*;
+pub use batch::*;
+pub use context::*;
+pub use phase::*;
+pub use version::*;
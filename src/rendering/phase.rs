@@ -0,0 +1,88 @@
+use crate::*;
+
+use std::rc::Rc;
+
+/// A function that is called when the ContainerManager switches to or away from a RenderPhase
+pub type RenderPhaseHook = Rc<dyn Fn(&mut RenderParams)>;
+
+struct PhaseHooks {
+
+    phase: RenderPhase,
+
+    begin: Option<RenderPhaseHook>,
+    end: Option<RenderPhaseHook>
+}
+
+/// Keeps track of the setup and teardown hooks of the render phases and of the phase that is currently active. The phase is
+/// switched by the ContainerManager whenever the next component to render has a different phase than the previous one, so the
+/// active phase carries over from one layer to the next. The begin hook of a phase is called when it becomes active and the end
+/// hook is called when another phase becomes active or when the container is done rendering.
+pub struct RenderPhaseRegistry {
+
+    hooks: Vec<PhaseHooks>,
+
+    current_phase: Option<RenderPhase>
+}
+
+impl RenderPhaseRegistry {
+
+    /// Creates a registry that only has hooks for the built-in Text phase, which prepares the TextRenderer of the manager
+    pub fn new() -> RenderPhaseRegistry {
+        let mut registry = RenderPhaseRegistry {
+            hooks: Vec::new(),
+            current_phase: None
+        };
+
+        let begin_text: RenderPhaseHook = Rc::new(|params: &mut RenderParams| {
+            let mut text_renderer = params.manager.get_text_renderer().borrow_mut();
+            params.get_render_context().borrow_mut().begin_text(&mut text_renderer);
+        });
+        registry.register(RenderPhase::Text, Some(begin_text), None);
+
+        registry
+    }
+
+    /// Registers the given begin and end hooks for the given phase, replacing the hooks that were registered for it earlier.
+    /// The hooks should not draw anything themselves, but can use the gl of the RenderParams to change the GL state. The
+    /// RenderContext is flushed before the end hook is called, so the hooks don't need to do that.
+    pub fn register(&mut self, phase: RenderPhase, begin: Option<RenderPhaseHook>, end: Option<RenderPhaseHook>) {
+        let hooks = PhaseHooks {
+            phase,
+            begin,
+            end
+        };
+        match self.hooks.binary_search_by_key(&phase, |existing| existing.phase) {
+            Ok(index) => self.hooks[index] = hooks,
+            Err(index) => self.hooks.insert(index, hooks)
+        };
+    }
+
+    /// Removes the hooks of the given phase and returns true if it had any
+    pub fn unregister(&mut self, phase: RenderPhase) -> bool {
+        match self.hooks.binary_search_by_key(&phase, |existing| existing.phase) {
+            Ok(index) => {
+                self.hooks.remove(index);
+                true
+            }, Err(_) => false
+        }
+    }
+
+    fn get_hooks(&self, phase: RenderPhase) -> Option<&PhaseHooks> {
+        self.hooks.binary_search_by_key(&phase, |existing| existing.phase).ok().map(|index| &self.hooks[index])
+    }
+
+    /// Gets the phase that is currently active, or None if no component is rendering
+    pub fn get_current_phase(&self) -> Option<RenderPhase> {
+        self.current_phase
+    }
+
+    /// Makes the given phase (or no phase) the active phase, which should differ from the current phase, and returns the end hook of the previous phase and the begin hook of
+    /// the new phase. The hooks are returned rather than called because they need to borrow the registry themselves.
+    pub(crate) fn switch(&mut self, phase: Option<RenderPhase>) -> (Option<RenderPhaseHook>, Option<RenderPhaseHook>) {
+        let end = self.current_phase.and_then(|previous| self.get_hooks(previous)).and_then(|hooks| hooks.end.clone());
+        let begin = phase.and_then(|next| self.get_hooks(next)).and_then(|hooks| hooks.begin.clone());
+        self.current_phase = phase;
+
+        (end, begin)
+    }
+}
@@ -0,0 +1,119 @@
+use web_sys::{
+    WebGl2RenderingContext,
+    WebGlProgram,
+    WebGlRenderingContext,
+    WebGlTexture
+};
+
+/// Keeps track of the GL state that was set through it, so that redundant state changes can be skipped. The state of each property
+/// is unknown until it is set for the first time and after invalidate() is called.
+pub struct GlState {
+
+    program: Option<u32>,
+    texture: Option<u32>,
+    blend: Option<bool>,
+
+    /// The scissor rectangle (x, y, width, height) in pixels, or None if the scissor test is disabled
+    scissor: Option<Option<(i32, i32, i32, i32)>>,
+
+    /// Some(true) if it is known that no vertex array object is bound. This is only used with WebGL 2.
+    default_vertex_array: Option<bool>,
+
+    state_changes: u32
+}
+
+impl GlState {
+
+    pub fn new() -> GlState {
+        GlState {
+            program: None,
+            texture: None,
+            blend: None,
+            scissor: None,
+            default_vertex_array: None,
+
+            state_changes: 0
+        }
+    }
+
+    /// Forgets the tracked state. This should be called after something changed the GL state without using this GlState, for
+    /// instance the TextRenderer or a behavior that uses the WebGlRenderingContext directly.
+    pub fn invalidate(&mut self) {
+        self.program = None;
+        self.texture = None;
+        self.blend = None;
+        self.scissor = None;
+        self.default_vertex_array = None;
+    }
+
+    /// Uses the given program, unless the program with the given id is already in use
+    pub fn use_program(&mut self, gl: &WebGlRenderingContext, id: u32, program: &WebGlProgram) {
+        if self.program != Some(id) {
+            gl.use_program(Some(program));
+            self.program = Some(id);
+            self.state_changes += 1;
+        }
+    }
+
+    /// Binds the given texture to the active texture unit, unless the texture with the given id is already bound
+    pub fn bind_texture(&mut self, gl: &WebGlRenderingContext, id: u32, texture: &WebGlTexture) {
+        if self.texture != Some(id) {
+            gl.bind_texture(WebGlRenderingContext::TEXTURE_2D, Some(texture));
+            self.texture = Some(id);
+            self.state_changes += 1;
+        }
+    }
+
+    /// Enables or disables alpha blending. When enabled, the usual (source alpha, 1 - source alpha) blend function is used.
+    pub fn set_blend(&mut self, gl: &WebGlRenderingContext, blend: bool) {
+        if self.blend != Some(blend) {
+            if blend {
+                gl.enable(WebGlRenderingContext::BLEND);
+                gl.blend_func(WebGlRenderingContext::SRC_ALPHA, WebGlRenderingContext::ONE_MINUS_SRC_ALPHA);
+            } else {
+                gl.disable(WebGlRenderingContext::BLEND);
+            }
+            self.blend = Some(blend);
+            self.state_changes += 1;
+        }
+    }
+
+    /// Sets the scissor rectangle (x, y, width, height) in pixels, or disables the scissor test if it is None
+    pub fn set_scissor(&mut self, gl: &WebGlRenderingContext, scissor: Option<(i32, i32, i32, i32)>) {
+        if self.scissor != Some(scissor) {
+            match scissor {
+                Some((x, y, width, height)) => {
+                    if self.scissor.flatten().is_none() {
+                        gl.enable(WebGlRenderingContext::SCISSOR_TEST);
+                    }
+                    gl.scissor(x, y, width, height);
+                }, None => gl.disable(WebGlRenderingContext::SCISSOR_TEST)
+            };
+            self.scissor = Some(scissor);
+            self.state_changes += 1;
+        }
+    }
+
+    /// Unbinds the vertex array object that is bound, unless it is known that none is bound. Behaviors that bind their own vertex
+    /// array object could otherwise get the attribute pointers of the batch written into it.
+    pub fn bind_default_vertex_array(&mut self, gl2: &WebGl2RenderingContext) {
+        if self.default_vertex_array != Some(true) {
+            gl2.bind_vertex_array(None);
+            self.default_vertex_array = Some(true);
+            self.state_changes += 1;
+        }
+    }
+
+    pub fn get_scissor(&self) -> Option<Option<(i32, i32, i32, i32)>> {
+        self.scissor
+    }
+
+    /// Gets the number of GL state changes that were made through this GlState since the last call to reset_state_changes
+    pub fn get_state_changes(&self) -> u32 {
+        self.state_changes
+    }
+
+    pub fn reset_state_changes(&mut self) {
+        self.state_changes = 0;
+    }
+}
@@ -0,0 +1,34 @@
+use wasm_bindgen::JsCast;
+
+use web_sys::{
+    HtmlCanvasElement,
+    WebGl2RenderingContext,
+    WebGlRenderingContext
+};
+
+/// The version of WebGL that a ContainerManager uses
+#[derive(Clone,Copy,PartialEq,Eq,PartialOrd,Ord,Debug)]
+pub enum GlVersion {
+
+    WebGl1,
+
+    /// WebGL 2 supports instancing, vertex array objects and float textures without extensions. Every WebGL 2 context also supports
+    /// all methods of WebGL 1, so the same context is passed as WebGlRenderingContext to everything that doesn't need WebGL 2.
+    WebGl2
+}
+
+/// Creates a WebGL context for the given canvas. If WebGL 2 is preferred, but the browser doesn't support it or the canvas already
+/// has a WebGL 1 context, a WebGL 1 context is used instead. The second element of the result is the WebGL 2 view of the context, if it is a WebGL 2 context.
+pub fn create_gl(canvas: &HtmlCanvasElement, preferred: GlVersion) -> (WebGlRenderingContext, Option<WebGl2RenderingContext>) {
+    if preferred == GlVersion::WebGl2 {
+        let maybe_gl2 = canvas.get_context("webgl2").ok().flatten().and_then(|context| context.dyn_into::<WebGl2RenderingContext>().ok());
+        if let Some(gl2) = maybe_gl2 {
+
+            // A WebGL2RenderingContext is not an instance of WebGLRenderingContext in JavaScript, but it has all of its methods
+            let gl = gl2.clone().unchecked_into::<WebGlRenderingContext>();
+            return (gl, Some(gl2));
+        }
+    }
+
+    (wasmuri_core::get_gl(canvas), None)
+}
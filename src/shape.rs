@@ -0,0 +1,318 @@
+use std::rc::Rc;
+
+use wasmuri_core::Region;
+
+/// The area in which a component reacts to the mouse, in OpenGL coordinates. Regions are the most common hit shape, but components
+/// that aren't rectangular can use one of the other shapes to get correct hover and click areas.
+#[derive(Clone)]
+pub enum HitShape {
+
+    Rect(Region),
+
+    /// An ellipse with the given center and radii. Because OpenGL coordinates are stretched to the size of the canvas, the x-radius and
+    /// y-radius will need to be different to get a circle on a canvas that is not square.
+    Circle {
+        center: (f32, f32),
+        radius_x: f32,
+        radius_y: f32
+    },
+
+    /// A polygon with the given corners, in order. The polygon doesn't need to be convex, but it shouldn't intersect itself.
+    Polygon(Vec<(f32, f32)>),
+
+    RoundedRect {
+        region: Region,
+        corner_radius_x: f32,
+        corner_radius_y: f32
+    },
+
+    /// A shape that is determined by the given function. The bounds should contain all points for which the function returns true.
+    Custom {
+        bounds: Region,
+        contains: Rc<dyn Fn((f32, f32)) -> bool>
+    }
+}
+
+impl HitShape {
+
+    pub fn circle(center: (f32, f32), radius_x: f32, radius_y: f32) -> HitShape {
+        HitShape::Circle {
+            center,
+            radius_x,
+            radius_y
+        }
+    }
+
+    pub fn polygon(corners: Vec<(f32, f32)>) -> HitShape {
+        HitShape::Polygon(corners)
+    }
+
+    pub fn rounded_rect(region: Region, corner_radius_x: f32, corner_radius_y: f32) -> HitShape {
+        HitShape::RoundedRect {
+            region,
+            corner_radius_x,
+            corner_radius_y
+        }
+    }
+
+    pub fn custom<F: Fn((f32, f32)) -> bool + 'static>(bounds: Region, contains: F) -> HitShape {
+        HitShape::Custom {
+            bounds,
+            contains: Rc::new(contains)
+        }
+    }
+
+    /// Checks if the given point (in OpenGL coordinates) is inside this shape
+    pub fn contains(&self, point: (f32, f32)) -> bool {
+        match self {
+            HitShape::Rect(region) => region.is_float_inside(point),
+            HitShape::Circle { center, radius_x, radius_y } => ellipse_contains(*center, *radius_x, *radius_y, point),
+            HitShape::Polygon(corners) => Self::polygon_contains(corners, point),
+            HitShape::RoundedRect { region, corner_radius_x, corner_radius_y } => {
+                rounded_rect_contains(*region, *corner_radius_x, *corner_radius_y, point)
+            }, HitShape::Custom { bounds, contains } => bounds.is_float_inside(point) && contains(point)
+        }
+    }
+
+    /// Gets the smallest Region that contains this shape
+    pub fn get_bounds(&self) -> Region {
+        match self {
+            HitShape::Rect(region) => *region,
+            HitShape::Circle { center, radius_x, radius_y } => {
+                Region::new(center.0 - radius_x, center.1 - radius_y, center.0 + radius_x, center.1 + radius_y)
+            }, HitShape::Polygon(corners) => {
+                let mut min_x = std::f32::INFINITY;
+                let mut min_y = std::f32::INFINITY;
+                let mut max_x = std::f32::NEG_INFINITY;
+                let mut max_y = std::f32::NEG_INFINITY;
+                for corner in corners {
+                    min_x = min_x.min(corner.0);
+                    min_y = min_y.min(corner.1);
+                    max_x = max_x.max(corner.0);
+                    max_y = max_y.max(corner.1);
+                }
+                Region::new(min_x, min_y, max_x, max_y)
+            }, HitShape::RoundedRect { region, .. } => *region,
+            HitShape::Custom { bounds, .. } => *bounds
+        }
+    }
+
+    /// Checks if the bounds of this shape intersect with the bounds of the other shape. This is used to decide whether claims
+    /// overlap, so shapes whose bounds overlap are considered overlapping even if the shapes themselves don't touch.
+    pub fn intersects_with(&self, other: &HitShape) -> bool {
+        self.get_bounds().intersects_with(other.get_bounds())
+    }
+
+    /// Uses the even-odd rule: the point is inside if a ray from the point crosses the edges an odd number of times
+    fn polygon_contains(corners: &[(f32, f32)], point: (f32, f32)) -> bool {
+        let mut inside = false;
+        let mut previous = match corners.last() {
+            Some(last) => *last,
+            None => return false
+        };
+
+        for current in corners {
+            if (current.1 > point.1) != (previous.1 > point.1) {
+                let crossing_x = current.0 + (point.1 - current.1) * (previous.0 - current.0) / (previous.1 - current.1);
+                if point.0 < crossing_x {
+                    inside = !inside;
+                }
+            }
+            previous = *current;
+        }
+
+        inside
+    }
+}
+
+impl From<Region> for HitShape {
+
+    fn from(region: Region) -> HitShape {
+        HitShape::Rect(region)
+    }
+}
+
+impl From<RenderShape> for HitShape {
+
+    fn from(shape: RenderShape) -> HitShape {
+        match shape {
+            RenderShape::Rect(region) => HitShape::Rect(region),
+            RenderShape::Circle { center, radius_x, radius_y } => HitShape::circle(center, radius_x, radius_y),
+            RenderShape::RoundedRect { region, corner_radius_x, corner_radius_y } => {
+                HitShape::rounded_rect(region, corner_radius_x, corner_radius_y)
+            }
+        }
+    }
+}
+
+/// The exact shape of what a component drew, which can be given to a PassedRenderAction. Unlike HitShape, this is Copy, so only
+/// the shapes that don't need to own any data are supported.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum RenderShape {
+
+    Rect(Region),
+
+    /// An ellipse with the given center and radii, like HitShape::Circle
+    Circle {
+        center: (f32, f32),
+        radius_x: f32,
+        radius_y: f32
+    },
+
+    RoundedRect {
+        region: Region,
+        corner_radius_x: f32,
+        corner_radius_y: f32
+    }
+}
+
+impl Eq for RenderShape {}
+
+impl RenderShape {
+
+    /// Checks if the given point (in OpenGL coordinates) is inside this shape
+    pub fn contains(&self, point: (f32, f32)) -> bool {
+        match self {
+            RenderShape::Rect(region) => region.is_float_inside(point),
+            RenderShape::Circle { center, radius_x, radius_y } => ellipse_contains(*center, *radius_x, *radius_y, point),
+            RenderShape::RoundedRect { region, corner_radius_x, corner_radius_y } => {
+                rounded_rect_contains(*region, *corner_radius_x, *corner_radius_y, point)
+            }
+        }
+    }
+
+    /// Gets the smallest Region that contains this shape
+    pub fn get_bounds(&self) -> Region {
+        match self {
+            RenderShape::Rect(region) => *region,
+            RenderShape::Circle { center, radius_x, radius_y } => {
+                Region::new(center.0 - radius_x, center.1 - radius_y, center.0 + radius_x, center.1 + radius_y)
+            }, RenderShape::RoundedRect { region, .. } => *region
+        }
+    }
+}
+
+/// An ellipse with a radius that is not positive is empty
+fn ellipse_contains(center: (f32, f32), radius_x: f32, radius_y: f32, point: (f32, f32)) -> bool {
+    if radius_x <= 0.0 || radius_y <= 0.0 {
+        return false;
+    }
+
+    let dx = (point.0 - center.0) / radius_x;
+    let dy = (point.1 - center.1) / radius_y;
+    dx * dx + dy * dy <= 1.0
+}
+
+/// A rounded rect with a corner radius that is not positive is a plain rect
+fn rounded_rect_contains(region: Region, corner_radius_x: f32, corner_radius_y: f32, point: (f32, f32)) -> bool {
+    if !region.is_float_inside(point) {
+        return false;
+    }
+    if corner_radius_x <= 0.0 || corner_radius_y <= 0.0 {
+        return true;
+    }
+
+    // Only the corners are rounded, so find the center of the nearest corner circle and check the distance to it
+    let center_x = point.0.max(region.get_min_x() + corner_radius_x).min(region.get_max_x() - corner_radius_x);
+    let center_y = point.1.max(region.get_min_y() + corner_radius_y).min(region.get_max_y() - corner_radius_y);
+    let dx = (point.0 - center_x) / corner_radius_x;
+    let dy = (point.1 - center_y) / corner_radius_y;
+    dx * dx + dy * dy <= 1.0
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_rect() {
+        let shape = HitShape::from(Region::new(0.0, 0.0, 1.0, 0.5));
+        assert!(shape.contains((0.5, 0.25)));
+        assert!(!shape.contains((0.5, 0.75)));
+        assert_eq!(Region::new(0.0, 0.0, 1.0, 0.5), shape.get_bounds());
+    }
+
+    #[test]
+    fn test_circle() {
+        let shape = HitShape::circle((0.0, 0.0), 0.5, 0.25);
+        assert!(shape.contains((0.0, 0.0)));
+        assert!(shape.contains((0.45, 0.0)));
+        assert!(!shape.contains((0.0, 0.3)));
+        assert!(!shape.contains((0.4, 0.2)));
+        assert_eq!(Region::new(-0.5, -0.25, 0.5, 0.25), shape.get_bounds());
+    }
+
+    #[test]
+    fn test_empty_circle() {
+        let shape = HitShape::circle((0.0, 0.0), 0.0, 0.0);
+        assert!(!shape.contains((0.0, 0.0)));
+
+        let flat = HitShape::circle((0.0, 0.0), 0.5, 0.0);
+        assert!(!flat.contains((0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_polygon() {
+
+        // A square with a triangular notch at the top
+        let shape = HitShape::polygon(vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.5, 0.5), (0.0, 1.0)]);
+        assert!(shape.contains((0.5, 0.25)));
+        assert!(shape.contains((0.1, 0.8)));
+        assert!(!shape.contains((0.5, 0.8)));
+        assert!(!shape.contains((1.5, 0.5)));
+        assert_eq!(Region::new(0.0, 0.0, 1.0, 1.0), shape.get_bounds());
+
+        assert!(!HitShape::polygon(Vec::new()).contains((0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_rounded_rect() {
+        let shape = HitShape::rounded_rect(Region::new(0.0, 0.0, 1.0, 1.0), 0.2, 0.2);
+        assert!(shape.contains((0.5, 0.5)));
+        assert!(shape.contains((0.5, 0.01)));
+        assert!(shape.contains((0.1, 0.1)));
+        assert!(!shape.contains((0.01, 0.01)));
+        assert!(!shape.contains((0.99, 0.99)));
+    }
+
+    #[test]
+    fn test_rounded_rect_without_radius() {
+        let shape = HitShape::rounded_rect(Region::new(0.0, 0.0, 1.0, 1.0), 0.0, 0.0);
+        assert!(shape.contains((0.5, 0.5)));
+        assert!(shape.contains((0.01, 0.01)));
+        assert!(shape.contains((0.99, 0.99)));
+        assert!(!shape.contains((1.5, 0.5)));
+    }
+
+    #[test]
+    fn test_custom() {
+        let shape = HitShape::custom(Region::new(0.0, 0.0, 1.0, 1.0), |point| point.0 < 0.5);
+        assert!(shape.contains((0.25, 0.5)));
+        assert!(!shape.contains((0.75, 0.5)));
+
+        // Points outside the bounds are never inside
+        assert!(!shape.contains((-0.5, 0.5)));
+    }
+
+    #[test]
+    fn test_intersects() {
+        let left = HitShape::circle((0.0, 0.0), 0.5, 0.5);
+        let right = HitShape::circle((0.9, 0.0), 0.5, 0.5);
+        let far = HitShape::circle((2.0, 0.0), 0.5, 0.5);
+        assert!(left.intersects_with(&right));
+        assert!(!left.intersects_with(&far));
+    }
+
+    #[test]
+    fn test_render_shape() {
+        let circle = RenderShape::Circle { center: (0.0, 0.0), radius_x: 0.5, radius_y: 0.5 };
+        assert!(circle.contains((0.3, 0.3)));
+        assert!(!circle.contains((0.4, 0.4)));
+        assert_eq!(Region::new(-0.5, -0.5, 0.5, 0.5), circle.get_bounds());
+
+        let hit_shape = HitShape::from(circle);
+        assert!(hit_shape.contains((0.3, 0.3)));
+        assert!(!hit_shape.contains((0.4, 0.4)));
+    }
+}
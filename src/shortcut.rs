@@ -0,0 +1,595 @@
+use crate::*;
+
+use std::fmt::{
+    Display,
+    Formatter
+};
+
+/// A single key combination, like Ctrl+Shift+S.
+/// Single character keys are stored in lower case so that Shift+S matches the "S" key event as well as the "s" key event.
+#[derive(Clone,PartialEq,Eq,Hash,Debug)]
+pub struct KeyStroke {
+
+    key: String,
+    control: bool,
+    shift: bool,
+    alt: bool,
+    meta: bool
+}
+
+impl KeyStroke {
+
+    pub fn new(key: &str, control: bool, shift: bool, alt: bool, meta: bool) -> KeyStroke {
+        KeyStroke {
+            key: Self::normalize_key(key),
+            control,
+            shift,
+            alt,
+            meta
+        }
+    }
+
+    pub fn from_key_info(keys: &KeyInfo) -> KeyStroke {
+        Self::new(keys.get_key(), keys.is_control_down(), keys.is_shift_down(), keys.is_alt_down(), keys.is_meta_down())
+    }
+
+    /// Parses a key stroke like "Ctrl+Shift+S" or "Alt+F4". The modifiers are case insensitive and the key must come last.
+    pub fn parse(text: &str) -> Result<KeyStroke, ShortcutError> {
+        let parts: Vec<&str> = text.trim().split('+').map(|part| part.trim()).collect();
+
+        let mut control = false;
+        let mut shift = false;
+        let mut alt = false;
+        let mut meta = false;
+
+        // "Ctrl++" is a valid key stroke, so splitting on '+' can leave an empty last part
+        let (key, modifiers) = match parts.split_last() {
+            Some((last, rest)) if !last.is_empty() => (last.to_string(), rest),
+            Some((_empty, rest)) if rest.last() == Some(&"") => ("+".to_string(), &rest[0 .. rest.len() - 1]),
+            _ => return Err(ShortcutError::Parse(text.to_string()))
+        };
+
+        for modifier in modifiers {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => control = true,
+                "shift" => shift = true,
+                "alt" | "option" => alt = true,
+                "meta" | "cmd" | "command" | "super" => meta = true,
+                _ => return Err(ShortcutError::Parse(text.to_string()))
+            };
+        }
+
+        Ok(Self::new(&key, control, shift, alt, meta))
+    }
+
+    fn normalize_key(key: &str) -> String {
+        if key.eq_ignore_ascii_case("space") {
+            return " ".to_string();
+        }
+        if key.chars().count() == 1 {
+            return key.to_lowercase();
+        }
+        key.to_string()
+    }
+
+    /// Checks if this key stroke would only press a modifier key. Such strokes are ignored while matching chords.
+    pub fn is_modifier_only(&self) -> bool {
+        match self.key.as_str() {
+            "Control" | "Shift" | "Alt" | "Meta" | "AltGraph" => true,
+            _ => false
+        }
+    }
+
+    pub fn get_key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn is_control_down(&self) -> bool {
+        self.control
+    }
+
+    pub fn is_shift_down(&self) -> bool {
+        self.shift
+    }
+
+    pub fn is_alt_down(&self) -> bool {
+        self.alt
+    }
+
+    pub fn is_meta_down(&self) -> bool {
+        self.meta
+    }
+}
+
+impl Display for KeyStroke {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.control {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.meta {
+            write!(f, "Meta+")?;
+        }
+        if self.key == " " {
+            write!(f, "Space")
+        } else if self.key.chars().count() == 1 {
+            write!(f, "{}", self.key.to_uppercase())
+        } else {
+            write!(f, "{}", self.key)
+        }
+    }
+}
+
+/// A sequence of key strokes that need to be pressed after each other, like "Ctrl+K Ctrl+C".
+/// Most chords consist of only 1 key stroke.
+#[derive(Clone,PartialEq,Eq,Hash,Debug)]
+pub struct KeyChord {
+
+    strokes: Vec<KeyStroke>
+}
+
+impl KeyChord {
+
+    /// Creates a new KeyChord from the given strokes. This method will panic if strokes is empty.
+    pub fn new(strokes: Vec<KeyStroke>) -> KeyChord {
+        assert!(!strokes.is_empty(), "A KeyChord needs at least 1 key stroke");
+        KeyChord {
+            strokes
+        }
+    }
+
+    pub fn single(stroke: KeyStroke) -> KeyChord {
+        Self::new(vec![stroke])
+    }
+
+    /// Parses a chord like "Ctrl+Shift+S" or "Ctrl+K Ctrl+C". The key strokes of the chord are separated by whitespace.
+    pub fn parse(text: &str) -> Result<KeyChord, ShortcutError> {
+        let mut strokes = Vec::new();
+        for stroke_text in text.split_whitespace() {
+            strokes.push(KeyStroke::parse(stroke_text)?);
+        }
+
+        if strokes.is_empty() {
+            return Err(ShortcutError::Parse(text.to_string()));
+        }
+
+        Ok(Self::new(strokes))
+    }
+
+    pub fn get_strokes(&self) -> &[KeyStroke] {
+        &self.strokes
+    }
+
+    /// Checks if the given strokes are the first strokes of this chord (or all strokes of this chord)
+    pub fn starts_with(&self, strokes: &[KeyStroke]) -> bool {
+        self.strokes.starts_with(strokes)
+    }
+
+    /// Two chords conflict if they are equal or if one of them is a prefix of the other one: the longer chord could
+    /// never be completed because the shorter chord would be triggered first.
+    pub fn conflicts_with(&self, other: &KeyChord) -> bool {
+        self.starts_with(&other.strokes) || other.starts_with(&self.strokes)
+    }
+}
+
+impl Display for KeyChord {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, stroke) in self.strokes.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", stroke)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug)]
+pub enum ShortcutError {
+
+    /// The text could not be parsed as key stroke, chord or config line
+    Parse(String),
+
+    /// The chord conflicts with the chord of an existing binding
+    Conflict(ShortcutConflict),
+
+    /// There is no binding for the given action
+    UnknownAction(String)
+}
+
+impl Display for ShortcutError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShortcutError::Parse(text) => write!(f, "Can't parse shortcut '{}'", text),
+            ShortcutError::Conflict(conflict) => write!(f, "{}", conflict),
+            ShortcutError::UnknownAction(action) => write!(f, "There is no shortcut for action '{}'", action)
+        }
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug)]
+pub struct ShortcutConflict {
+
+    existing_action: String,
+    existing_chord: KeyChord
+}
+
+impl ShortcutConflict {
+
+    pub fn new(existing_action: String, existing_chord: KeyChord) -> ShortcutConflict {
+        ShortcutConflict {
+            existing_action,
+            existing_chord
+        }
+    }
+
+    pub fn get_existing_action(&self) -> &str {
+        &self.existing_action
+    }
+
+    pub fn get_existing_chord(&self) -> &KeyChord {
+        &self.existing_chord
+    }
+}
+
+impl Display for ShortcutConflict {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The shortcut conflicts with {} of action '{}'", self.existing_chord, self.existing_action)
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug)]
+pub struct ShortcutBinding {
+
+    action: String,
+    chord: KeyChord
+}
+
+impl ShortcutBinding {
+
+    pub fn get_action(&self) -> &str {
+        &self.action
+    }
+
+    pub fn get_chord(&self) -> &KeyChord {
+        &self.chord
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug)]
+pub enum ShortcutMatch {
+
+    /// The key stroke is not part of any binding
+    None,
+
+    /// The key stroke is the start of at least 1 chord, so the registry is waiting for the next stroke
+    Pending,
+
+    /// The key stroke completed the chord of the action with the given name
+    Triggered(String)
+}
+
+/// The ShortcutRegistry maps actions (identified by their name) to key chords and keeps track of partially typed chords.
+pub struct ShortcutRegistry {
+
+    bindings: Vec<ShortcutBinding>,
+
+    pending_strokes: Vec<KeyStroke>
+}
+
+impl ShortcutRegistry {
+
+    pub fn new() -> ShortcutRegistry {
+        ShortcutRegistry {
+            bindings: Vec::new(),
+            pending_strokes: Vec::new()
+        }
+    }
+
+    /// Finds the binding whose chord would conflict with the given chord, ignoring the binding of ignore_action
+    pub fn find_conflict(&self, chord: &KeyChord, ignore_action: Option<&str>) -> Option<ShortcutConflict> {
+        for binding in &self.bindings {
+            if Some(binding.action.as_str()) != ignore_action && binding.chord.conflicts_with(chord) {
+                return Some(ShortcutConflict {
+                    existing_action: binding.action.clone(),
+                    existing_chord: binding.chord.clone()
+                });
+            }
+        }
+
+        None
+    }
+
+    pub fn is_bound(&self, action: &str) -> bool {
+        self.get_chord(action).is_some()
+    }
+
+    pub fn get_chord(&self, action: &str) -> Option<&KeyChord> {
+        self.bindings.iter().find(|binding| binding.action == action).map(|binding| &binding.chord)
+    }
+
+    /// Checks whether bind would succeed for the given action and chord, without changing anything
+    pub fn check_bind(&self, action: &str, chord: &KeyChord) -> Result<(), ShortcutError> {
+        if let Some(existing_chord) = self.get_chord(action) {
+            return Err(ShortcutError::Conflict(ShortcutConflict {
+                existing_action: action.to_string(),
+                existing_chord: existing_chord.clone()
+            }));
+        }
+
+        if let Some(conflict) = self.find_conflict(chord, None) {
+            return Err(ShortcutError::Conflict(conflict));
+        }
+
+        Ok(())
+    }
+
+    /// Binds the given action to the given chord. If the action was already bound or the chord conflicts with another binding,
+    /// an error will be returned and nothing will change.
+    pub fn bind(&mut self, action: &str, chord: KeyChord) -> Result<(), ShortcutError> {
+        self.check_bind(action, &chord)?;
+
+        self.bindings.push(ShortcutBinding {
+            action: action.to_string(),
+            chord
+        });
+        Ok(())
+    }
+
+    /// Changes the chord of an existing binding. If the new chord conflicts with the chord of another binding, an error is returned
+    /// and the old chord will be kept.
+    pub fn rebind(&mut self, action: &str, chord: KeyChord) -> Result<(), ShortcutError> {
+        if let Some(conflict) = self.find_conflict(&chord, Some(action)) {
+            return Err(ShortcutError::Conflict(conflict));
+        }
+
+        match self.bindings.iter_mut().find(|binding| binding.action == action) {
+            Some(binding) => {
+                binding.chord = chord;
+                self.pending_strokes.clear();
+                Ok(())
+            }, None => Err(ShortcutError::UnknownAction(action.to_string()))
+        }
+    }
+
+    /// Removes the binding of the given action and returns its chord, or None if the action wasn't bound
+    pub fn unbind(&mut self, action: &str) -> Option<KeyChord> {
+        let index = self.bindings.iter().position(|binding| binding.action == action)?;
+        self.pending_strokes.clear();
+        Some(self.bindings.remove(index).chord)
+    }
+
+    /// Gets all bindings of this registry, in the order they were bound. This is handy for help screens.
+    pub fn get_bindings(&self) -> &[ShortcutBinding] {
+        &self.bindings
+    }
+
+    /// Checks if the registry is halfway a chord
+    pub fn is_pending(&self) -> bool {
+        !self.pending_strokes.is_empty()
+    }
+
+    /// Feeds the next key stroke to this registry and reports whether it completed, continued or broke a chord.
+    pub fn process(&mut self, keys: &KeyInfo) -> ShortcutMatch {
+        self.process_stroke(KeyStroke::from_key_info(keys))
+    }
+
+    /// Like process, but for a KeyStroke instead of a key event
+    pub fn process_stroke(&mut self, stroke: KeyStroke) -> ShortcutMatch {
+
+        // Pressing Ctrl before the K of Ctrl+K shouldn't break the chord
+        if stroke.is_modifier_only() {
+            return if self.is_pending() { ShortcutMatch::Pending } else { ShortcutMatch::None };
+        }
+
+        self.pending_strokes.push(stroke);
+        let result = self.match_pending();
+
+        // If the stroke broke a chord, it could still be the start of another chord
+        if result == ShortcutMatch::None && self.pending_strokes.len() > 1 {
+            let last_stroke = self.pending_strokes.pop().unwrap();
+            self.pending_strokes.clear();
+            self.pending_strokes.push(last_stroke);
+            let retry_result = self.match_pending();
+            if retry_result == ShortcutMatch::None {
+                self.pending_strokes.clear();
+            }
+            return retry_result;
+        }
+
+        if result == ShortcutMatch::None {
+            self.pending_strokes.clear();
+        }
+        result
+    }
+
+    /// Matches the pending strokes against the bindings. The pending strokes are only cleared when a chord was completed, so that
+    /// process_stroke can decide what to do with strokes that broke a chord.
+    fn match_pending(&mut self) -> ShortcutMatch {
+        let mut is_prefix = false;
+        for binding in &self.bindings {
+            if binding.chord.strokes == self.pending_strokes {
+                self.pending_strokes.clear();
+                return ShortcutMatch::Triggered(binding.action.clone());
+            }
+            if binding.chord.starts_with(&self.pending_strokes) {
+                is_prefix = true;
+            }
+        }
+
+        if is_prefix {
+            ShortcutMatch::Pending
+        } else {
+            ShortcutMatch::None
+        }
+    }
+
+    /// Converts the bindings of this registry to a config text with 1 'action = chord' line per binding
+    pub fn to_config(&self) -> String {
+        let mut config = String::new();
+        for binding in &self.bindings {
+            config.push_str(&format!("{} = {}\n", binding.action, binding.chord));
+        }
+        config
+    }
+
+    /// Loads a config text created by to_config(). Actions that are already bound will be rebound and the other actions will be
+    /// bound. Empty lines and lines starting with # are ignored. If any line is invalid or conflicting, nothing will be changed.
+    pub fn load_config(&mut self, config: &str) -> Result<(), ShortcutError> {
+        let mut new_bindings = self.bindings.clone();
+
+        for line in config.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let separator = line.find('=').ok_or_else(|| ShortcutError::Parse(line.to_string()))?;
+            let action = line[..separator].trim();
+            let chord = KeyChord::parse(&line[separator + 1 ..])?;
+
+            if action.is_empty() {
+                return Err(ShortcutError::Parse(line.to_string()));
+            }
+
+            new_bindings.retain(|binding| binding.action != action);
+            new_bindings.push(ShortcutBinding {
+                action: action.to_string(),
+                chord
+            });
+        }
+
+        for (index, binding) in new_bindings.iter().enumerate() {
+            for other in &new_bindings[index + 1 ..] {
+                if binding.chord.conflicts_with(&other.chord) {
+                    return Err(ShortcutError::Conflict(ShortcutConflict {
+                        existing_action: binding.action.clone(),
+                        existing_chord: binding.chord.clone()
+                    }));
+                }
+            }
+        }
+
+        self.bindings = new_bindings;
+        self.pending_strokes.clear();
+        Ok(())
+    }
+}
+
+/// Listens to the shortcuts that are bound in the ShortcutRegistry of the ContainerManager.
+/// The bindings of that registry are checked before the current container gets the key event.
+pub trait ShortcutListener {
+
+    fn on_shortcut(&self, manager: &ContainerManager, action: &str) -> EventResult;
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn stroke(text: &str) -> KeyStroke {
+        KeyStroke::parse(text).unwrap()
+    }
+
+    fn registry(bindings: &[(&str, &str)]) -> ShortcutRegistry {
+        let mut registry = ShortcutRegistry::new();
+        for (action, chord) in bindings {
+            registry.bind(action, KeyChord::parse(chord).unwrap()).unwrap();
+        }
+        registry
+    }
+
+    #[test]
+    fn test_parse_stroke() {
+        assert_eq!(KeyStroke::new("s", true, true, false, false), stroke("Ctrl+Shift+S"));
+        assert_eq!(KeyStroke::new("+", true, false, false, false), stroke("Ctrl++"));
+        assert_eq!(KeyStroke::new(" ", false, false, true, false), stroke("alt+space"));
+        assert!(KeyStroke::parse("Hyper+S").is_err());
+        assert!(KeyStroke::parse("Ctrl+").is_err());
+        assert_eq!("Ctrl+Shift+S", stroke("ctrl+shift+s").to_string());
+    }
+
+    #[test]
+    fn test_parse_chord() {
+        let chord = KeyChord::parse("Ctrl+K  Ctrl+C").unwrap();
+        assert_eq!(&[stroke("Ctrl+K"), stroke("Ctrl+C")], chord.get_strokes());
+        assert_eq!("Ctrl+K Ctrl+C", chord.to_string());
+        assert!(KeyChord::parse("   ").is_err());
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let mut registry = registry(&[("save", "Ctrl+S"), ("comment", "Ctrl+K Ctrl+C")]);
+        assert!(registry.bind("save_all", KeyChord::parse("Ctrl+S").unwrap()).is_err());
+        assert!(registry.bind("prefix", KeyChord::parse("Ctrl+K").unwrap()).is_err());
+        assert!(registry.bind("longer", KeyChord::parse("Ctrl+S Ctrl+A").unwrap()).is_err());
+        assert!(registry.bind("save", KeyChord::parse("Ctrl+Q").unwrap()).is_err());
+        assert!(registry.bind("uncomment", KeyChord::parse("Ctrl+K Ctrl+U").unwrap()).is_ok());
+        assert_eq!(Some(KeyChord::parse("Ctrl+S").unwrap()), registry.unbind("save"));
+        assert!(!registry.is_bound("save"));
+    }
+
+    #[test]
+    fn test_single_stroke() {
+        let mut registry = registry(&[("save", "Ctrl+S")]);
+        assert_eq!(ShortcutMatch::None, registry.process_stroke(stroke("S")));
+        assert_eq!(ShortcutMatch::Triggered("save".to_string()), registry.process_stroke(stroke("Ctrl+S")));
+        assert!(!registry.is_pending());
+    }
+
+    #[test]
+    fn test_chord() {
+        let mut registry = registry(&[("comment", "Ctrl+K Ctrl+C")]);
+        assert_eq!(ShortcutMatch::Pending, registry.process_stroke(stroke("Ctrl+K")));
+
+        // Pressing the Control key itself shouldn't break the chord
+        assert_eq!(ShortcutMatch::Pending, registry.process_stroke(KeyStroke::new("Control", true, false, false, false)));
+        assert_eq!(ShortcutMatch::Triggered("comment".to_string()), registry.process_stroke(stroke("Ctrl+C")));
+        assert!(!registry.is_pending());
+    }
+
+    #[test]
+    fn test_broken_chord_starts_other_chord() {
+        let mut registry = registry(&[("comment", "Ctrl+K Ctrl+C"), ("save", "Ctrl+S")]);
+        assert_eq!(ShortcutMatch::Pending, registry.process_stroke(stroke("Ctrl+K")));
+        assert_eq!(ShortcutMatch::Triggered("save".to_string()), registry.process_stroke(stroke("Ctrl+S")));
+        assert!(!registry.is_pending());
+
+        assert_eq!(ShortcutMatch::Pending, registry.process_stroke(stroke("Ctrl+K")));
+        assert_eq!(ShortcutMatch::Pending, registry.process_stroke(stroke("Ctrl+K")));
+        assert_eq!(ShortcutMatch::Triggered("comment".to_string()), registry.process_stroke(stroke("Ctrl+C")));
+    }
+
+    #[test]
+    fn test_broken_chord_without_other_chord() {
+        let mut registry = registry(&[("comment", "Ctrl+K Ctrl+C")]);
+        assert_eq!(ShortcutMatch::Pending, registry.process_stroke(stroke("Ctrl+K")));
+        assert_eq!(ShortcutMatch::None, registry.process_stroke(stroke("A")));
+        assert!(!registry.is_pending());
+        assert_eq!(ShortcutMatch::None, registry.process_stroke(stroke("Ctrl+C")));
+    }
+
+    #[test]
+    fn test_config() {
+        let registry = registry(&[("save", "Ctrl+S"), ("comment", "Ctrl+K Ctrl+C")]);
+        let mut loaded = ShortcutRegistry::new();
+        loaded.load_config(&format!("# comment line\n\n{}", registry.to_config())).unwrap();
+        assert_eq!(registry.get_bindings(), loaded.get_bindings());
+
+        // A conflicting config should be rejected as a whole
+        assert!(loaded.load_config("save = Ctrl+Q\nquit = Ctrl+K").is_err());
+        assert_eq!(Some(&KeyChord::parse("Ctrl+S").unwrap()), loaded.get_chord("save"));
+        assert!(loaded.load_config("missing separator").is_err());
+    }
+}
@@ -0,0 +1,371 @@
+use crate::*;
+
+use wasmuri_core::Region;
+
+/// Appends the given text to the JSON output as a string literal
+pub(crate) fn write_json_string(out: &mut String, text: &str) {
+    out.push('"');
+    for character in text.chars() {
+        match character {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            character if (character as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", character as u32)),
+            character => out.push(character)
+        };
+    }
+    out.push('"');
+}
+
+fn write_json_region(out: &mut String, region: Region) {
+    out.push_str(&format!("{{\"min_x\":{},\"min_y\":{},\"max_x\":{},\"max_y\":{}}}",
+            region.get_min_x(), region.get_min_y(), region.get_max_x(), region.get_max_y()));
+}
+
+/// Appends the elements as a JSON array, using write_element to write each element
+fn write_json_array<T, F: Fn(&mut String, &T)>(out: &mut String, elements: &[T], write_element: F) {
+    out.push('[');
+    for (index, element) in elements.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        write_element(out, element);
+    }
+    out.push(']');
+}
+
+/// The space a component claimed in the RenderManager of a SimpleLayer
+#[derive(Clone)]
+pub struct RenderClaimSnapshot {
+
+    region: Region,
+    trigger: RenderTrigger,
+    opacity: RenderOpacity,
+    phase: RenderPhase,
+    z: i32
+}
+
+impl RenderClaimSnapshot {
+
+    pub fn new(region: Region, trigger: RenderTrigger, opacity: RenderOpacity, phase: RenderPhase, z: i32) -> RenderClaimSnapshot {
+        RenderClaimSnapshot {
+            region,
+            trigger,
+            opacity,
+            phase,
+            z
+        }
+    }
+
+    pub fn get_region(&self) -> Region {
+        self.region
+    }
+
+    pub fn get_trigger(&self) -> RenderTrigger {
+        self.trigger
+    }
+
+    pub fn get_opacity(&self) -> RenderOpacity {
+        self.opacity
+    }
+
+    pub fn get_phase(&self) -> RenderPhase {
+        self.phase
+    }
+
+    pub fn get_z(&self) -> i32 {
+        self.z
+    }
+
+    fn write_json(&self, out: &mut String) {
+        out.push_str("{\"region\":");
+        write_json_region(out, self.region);
+        out.push_str(&format!(",\"trigger\":\"{:?}\",\"opacity\":\"{:?}\",\"phase\":{},\"z\":{}}}",
+                self.trigger, self.opacity, self.phase.get_order(), self.z));
+    }
+}
+
+/// A listener of a manager of a SimpleLayer. Listeners of the entire layer don't have a region and listeners of overlapping
+/// spaces don't have a priority.
+#[derive(Clone)]
+pub struct ListenerSnapshot {
+
+    /// The kind of events the listener receives, like "key_down" or "mouse_click"
+    kind: &'static str,
+
+    region: Option<Region>,
+    priority: Option<i8>,
+    z: Option<i32>,
+
+    /// Extra information about the listener, like the action of a shortcut
+    detail: Option<String>
+}
+
+impl ListenerSnapshot {
+
+    pub fn new(kind: &'static str) -> ListenerSnapshot {
+        ListenerSnapshot {
+            kind,
+            region: None,
+            priority: None,
+            z: None,
+            detail: None
+        }
+    }
+
+    pub fn with_region(mut self, region: Region) -> ListenerSnapshot {
+        self.region = Some(region);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: i8) -> ListenerSnapshot {
+        self.priority = Some(priority);
+        self
+    }
+
+    pub fn with_z(mut self, z: i32) -> ListenerSnapshot {
+        self.z = Some(z);
+        self
+    }
+
+    pub fn with_detail(mut self, detail: &str) -> ListenerSnapshot {
+        self.detail = Some(detail.to_string());
+        self
+    }
+
+    pub fn get_kind(&self) -> &'static str {
+        self.kind
+    }
+
+    pub fn get_region(&self) -> Option<Region> {
+        self.region
+    }
+
+    pub fn get_priority(&self) -> Option<i8> {
+        self.priority
+    }
+
+    pub fn get_z(&self) -> Option<i32> {
+        self.z
+    }
+
+    pub fn get_detail(&self) -> Option<&str> {
+        self.detail.as_ref().map(|detail| detail.as_str())
+    }
+
+    fn write_json(&self, out: &mut String) {
+        out.push_str("{\"kind\":");
+        write_json_string(out, self.kind);
+        if let Some(region) = self.region {
+            out.push_str(",\"region\":");
+            write_json_region(out, region);
+        }
+        if let Some(priority) = self.priority {
+            out.push_str(&format!(",\"priority\":{}", priority));
+        }
+        if let Some(z) = self.z {
+            out.push_str(&format!(",\"z\":{}", z));
+        }
+        if let Some(detail) = &self.detail {
+            out.push_str(",\"detail\":");
+            write_json_string(out, detail);
+        }
+        out.push('}');
+    }
+}
+
+/// A component of a SimpleLayer, together with its claims and the state of its agent
+#[derive(Clone)]
+pub struct ComponentSnapshot {
+
+    key: ComponentKey,
+
+    pending_render: bool,
+    has_animations: bool,
+    has_timers: bool,
+
+    render_claims: Vec<RenderClaimSnapshot>,
+    listeners: Vec<ListenerSnapshot>
+}
+
+impl ComponentSnapshot {
+
+    pub fn new(key: ComponentKey, pending_render: bool, has_animations: bool, has_timers: bool) -> ComponentSnapshot {
+        ComponentSnapshot {
+            key,
+            pending_render,
+            has_animations,
+            has_timers,
+            render_claims: Vec::new(),
+            listeners: Vec::new()
+        }
+    }
+
+    pub fn get_key(&self) -> ComponentKey {
+        self.key
+    }
+
+    /// Checks if the component requested a render that hasn't happened yet
+    pub fn has_pending_render(&self) -> bool {
+        self.pending_render
+    }
+
+    pub fn has_animations(&self) -> bool {
+        self.has_animations
+    }
+
+    pub fn has_timers(&self) -> bool {
+        self.has_timers
+    }
+
+    pub fn get_render_claims(&self) -> &[RenderClaimSnapshot] {
+        &self.render_claims
+    }
+
+    pub fn add_render_claim(&mut self, claim: RenderClaimSnapshot) {
+        self.render_claims.push(claim);
+    }
+
+    pub fn get_listeners(&self) -> &[ListenerSnapshot] {
+        &self.listeners
+    }
+
+    pub fn add_listener(&mut self, listener: ListenerSnapshot) {
+        self.listeners.push(listener);
+    }
+
+    fn write_json(&self, out: &mut String) {
+        out.push_str(&format!("{{\"key\":{},\"pending_render\":{},\"has_animations\":{},\"has_timers\":{},\"render_claims\":",
+                self.key.get_value(), self.pending_render, self.has_animations, self.has_timers));
+        write_json_array(out, &self.render_claims, |out, claim| claim.write_json(out));
+        out.push_str(",\"listeners\":");
+        write_json_array(out, &self.listeners, |out, listener| listener.write_json(out));
+        out.push('}');
+    }
+}
+
+/// A read-only description of a layer. Layers that wrap another layer (like CachedLayer) describe it as their inner layer.
+#[derive(Clone)]
+pub struct LayerSnapshot {
+
+    kind: String,
+
+    components: Vec<ComponentSnapshot>,
+
+    /// The listeners whose component couldn't be determined, for instance because it is being dropped
+    unowned_listeners: Vec<ListenerSnapshot>,
+
+    inner: Option<Box<LayerSnapshot>>
+}
+
+impl LayerSnapshot {
+
+    pub fn new(kind: &str) -> LayerSnapshot {
+        LayerSnapshot {
+            kind: kind.to_string(),
+            components: Vec::new(),
+            unowned_listeners: Vec::new(),
+            inner: None
+        }
+    }
+
+    pub fn with_inner(mut self, inner: LayerSnapshot) -> LayerSnapshot {
+        self.inner = Some(Box::new(inner));
+        self
+    }
+
+    pub fn get_kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn get_components(&self) -> &[ComponentSnapshot] {
+        &self.components
+    }
+
+    pub fn get_component(&self, key: ComponentKey) -> Option<&ComponentSnapshot> {
+        self.components.iter().find(|component| component.key == key)
+    }
+
+    pub fn get_component_mut(&mut self, key: ComponentKey) -> Option<&mut ComponentSnapshot> {
+        self.components.iter_mut().find(|component| component.key == key)
+    }
+
+    pub fn add_component(&mut self, component: ComponentSnapshot) {
+        self.components.push(component);
+    }
+
+    pub fn get_unowned_listeners(&self) -> &[ListenerSnapshot] {
+        &self.unowned_listeners
+    }
+
+    /// Gives the listener to the component with the given key, or adds it to the unowned listeners if this layer doesn't have it
+    pub fn add_listener(&mut self, key: Option<ComponentKey>, listener: ListenerSnapshot) {
+        match self.components.iter_mut().find(|component| Some(component.key) == key) {
+            Some(component) => component.add_listener(listener),
+            None => self.unowned_listeners.push(listener)
+        };
+    }
+
+    pub fn get_inner(&self) -> Option<&LayerSnapshot> {
+        self.inner.as_ref().map(|inner| &**inner)
+    }
+
+    fn write_json(&self, out: &mut String) {
+        out.push_str("{\"kind\":");
+        write_json_string(out, &self.kind);
+        out.push_str(",\"components\":");
+        write_json_array(out, &self.components, |out, component| component.write_json(out));
+        out.push_str(",\"unowned_listeners\":");
+        write_json_array(out, &self.unowned_listeners, |out, listener| listener.write_json(out));
+        if let Some(inner) = &self.inner {
+            out.push_str(",\"inner\":");
+            inner.write_json(out);
+        }
+        out.push('}');
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+}
+
+/// A read-only description of a container and its layers, from the back layer to the front layer. It can be obtained with
+/// ContainerManager::snapshot and converted to JSON to attach it to bug reports or compare it in tests.
+#[derive(Clone)]
+pub struct ContainerSnapshot {
+
+    kind: String,
+    layers: Vec<LayerSnapshot>
+}
+
+impl ContainerSnapshot {
+
+    pub fn new(kind: &str, layers: Vec<LayerSnapshot>) -> ContainerSnapshot {
+        ContainerSnapshot {
+            kind: kind.to_string(),
+            layers
+        }
+    }
+
+    pub fn get_kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn get_layers(&self) -> &[LayerSnapshot] {
+        &self.layers
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        out.push_str("{\"kind\":");
+        write_json_string(&mut out, &self.kind);
+        out.push_str(",\"layers\":");
+        write_json_array(&mut out, &self.layers, |out, layer| layer.write_json(out));
+        out.push('}');
+        out
+    }
+}
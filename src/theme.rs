@@ -0,0 +1,181 @@
+use wasmuri_core::Color;
+
+/// The interaction state of a component, which determines which WidgetStyle of the Theme it should be drawn with
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum WidgetState {
+
+    Normal,
+    Hover,
+    Pressed,
+    Disabled,
+    Focused
+}
+
+/// The colors a component should use in a specific WidgetState
+#[derive(Clone,Copy)]
+pub struct WidgetStyle {
+
+    pub background: Color,
+    pub text: Color,
+    pub border: Color,
+    pub accent: Color
+}
+
+#[derive(Clone,Copy)]
+pub struct StateStyles {
+
+    pub normal: WidgetStyle,
+    pub hover: WidgetStyle,
+    pub pressed: WidgetStyle,
+    pub disabled: WidgetStyle,
+    pub focused: WidgetStyle
+}
+
+impl StateStyles {
+
+    pub fn get(&self, state: WidgetState) -> &WidgetStyle {
+        match state {
+            WidgetState::Normal => &self.normal,
+            WidgetState::Hover => &self.hover,
+            WidgetState::Pressed => &self.pressed,
+            WidgetState::Disabled => &self.disabled,
+            WidgetState::Focused => &self.focused
+        }
+    }
+}
+
+/// The general colors of a Theme
+#[derive(Clone,Copy)]
+pub struct Palette {
+
+    /// The color of the layers that use the theme background
+    pub background: Color,
+
+    /// The color of surfaces on top of the background, like popups, tooltips and lists
+    pub surface: Color,
+
+    pub text: Color,
+    pub secondary_text: Color,
+    pub border: Color,
+    pub accent: Color
+}
+
+/// The text heights of a Theme, in OpenGL coordinates
+#[derive(Clone,Copy)]
+pub struct Fonts {
+
+    pub body_height: f32,
+    pub heading_height: f32,
+    pub small_height: f32
+}
+
+/// The spacing of a Theme, in OpenGL coordinates
+#[derive(Clone,Copy)]
+pub struct Spacing {
+
+    /// The space between the edge of a component and its content
+    pub padding: f32,
+
+    /// The space that should be kept between components
+    pub margin: f32
+}
+
+/// A Theme defines the colors, text heights and spacing that components should use. The current theme can be obtained from the
+/// ContainerManager and changing it will re-render the current container.
+#[derive(Clone)]
+pub struct Theme {
+
+    pub name: String,
+
+    pub palette: Palette,
+    pub fonts: Fonts,
+    pub spacing: Spacing,
+    pub styles: StateStyles
+}
+
+impl Theme {
+
+    /// Gets the style components should use in the given state
+    pub fn get_style(&self, state: WidgetState) -> &WidgetStyle {
+        self.styles.get(state)
+    }
+
+    pub fn light() -> Theme {
+        let palette = Palette {
+            background: Color::from_rgb(245, 245, 245),
+            surface: Color::from_rgb(255, 255, 255),
+            text: Color::from_rgb(20, 20, 20),
+            secondary_text: Color::from_rgb(110, 110, 110),
+            border: Color::from_rgb(160, 160, 160),
+            accent: Color::from_rgb(40, 110, 220)
+        };
+
+        let normal = WidgetStyle {
+            background: Color::from_rgb(225, 225, 225),
+            text: palette.text,
+            border: palette.border,
+            accent: palette.accent
+        };
+
+        Theme {
+            name: "light".to_string(),
+            palette,
+            fonts: Self::default_fonts(),
+            spacing: Self::default_spacing(),
+            styles: StateStyles {
+                normal,
+                hover: WidgetStyle { background: Color::from_rgb(238, 238, 238), ..normal },
+                pressed: WidgetStyle { background: Color::from_rgb(205, 205, 205), ..normal },
+                disabled: WidgetStyle { text: Color::from_rgb(160, 160, 160), border: Color::from_rgb(200, 200, 200), ..normal },
+                focused: WidgetStyle { background: palette.surface, border: palette.accent, ..normal }
+            }
+        }
+    }
+
+    pub fn dark() -> Theme {
+        let palette = Palette {
+            background: Color::from_rgb(30, 30, 32),
+            surface: Color::from_rgb(45, 45, 48),
+            text: Color::from_rgb(230, 230, 230),
+            secondary_text: Color::from_rgb(160, 160, 160),
+            border: Color::from_rgb(90, 90, 95),
+            accent: Color::from_rgb(80, 150, 250)
+        };
+
+        let normal = WidgetStyle {
+            background: Color::from_rgb(55, 55, 60),
+            text: palette.text,
+            border: palette.border,
+            accent: palette.accent
+        };
+
+        Theme {
+            name: "dark".to_string(),
+            palette,
+            fonts: Self::default_fonts(),
+            spacing: Self::default_spacing(),
+            styles: StateStyles {
+                normal,
+                hover: WidgetStyle { background: Color::from_rgb(70, 70, 76), ..normal },
+                pressed: WidgetStyle { background: Color::from_rgb(40, 40, 44), ..normal },
+                disabled: WidgetStyle { text: Color::from_rgb(110, 110, 110), border: Color::from_rgb(65, 65, 70), ..normal },
+                focused: WidgetStyle { background: palette.surface, border: palette.accent, ..normal }
+            }
+        }
+    }
+
+    fn default_fonts() -> Fonts {
+        Fonts {
+            body_height: 0.05,
+            heading_height: 0.08,
+            small_height: 0.035
+        }
+    }
+
+    fn default_spacing() -> Spacing {
+        Spacing {
+            padding: 0.01,
+            margin: 0.02
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::cell::{
+    Cell,
+    RefCell
+};
+use std::rc::Rc;
+
+/// Can be used to check whether a timer that was scheduled with a ComponentAgent is still pending, and to cancel it
+#[derive(Clone)]
+pub struct TimerHandle {
+
+    cancelled: Rc<Cell<bool>>,
+    finished: Rc<Cell<bool>>
+}
+
+impl TimerHandle {
+
+    fn new() -> TimerHandle {
+        TimerHandle {
+            cancelled: Rc::new(Cell::new(false)),
+            finished: Rc::new(Cell::new(false))
+        }
+    }
+
+    /// Prevents the callback of the timer from being called again
+    pub fn cancel(&self) {
+        self.cancelled.set(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.get()
+    }
+
+    /// Checks if the timer will still call its callback: it has not been cancelled and, if it is a one-shot timer, hasn't fired yet
+    pub fn is_pending(&self) -> bool {
+        !self.cancelled.get() && !self.finished.get()
+    }
+}
+
+/// A timer that was scheduled by a component. It is advanced during the updates of the layer of the component, so its precision is
+/// limited by the update rate.
+pub(crate) struct ScheduledTimer {
+
+    remaining: f64,
+
+    /// The time between 2 calls of a repeating timer, or None for a one-shot timer
+    interval: Option<f64>,
+
+    callback: Rc<RefCell<dyn FnMut()>>,
+    handle: TimerHandle
+}
+
+impl ScheduledTimer {
+
+    pub(crate) fn new(delay: f64, interval: Option<f64>, callback: Rc<RefCell<dyn FnMut()>>) -> (ScheduledTimer, TimerHandle) {
+        let handle = TimerHandle::new();
+        (ScheduledTimer {
+            remaining: delay,
+            interval,
+            callback,
+            handle: handle.clone()
+        }, handle)
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.handle.is_cancelled()
+    }
+
+    /// Cancels this timer, unless it is a one-shot timer that already fired
+    pub(crate) fn cancel(&self) {
+        if !self.is_finished() {
+            self.handle.cancel();
+        }
+    }
+
+    /// Advances this timer by the given time (in milliseconds) and returns its callback if it should be called. A repeating timer
+    /// is called at most once per update, even if more than 1 interval passed.
+    pub(crate) fn advance(&mut self, delta: f64) -> Option<Rc<RefCell<dyn FnMut()>>> {
+        self.remaining -= delta;
+        if self.remaining > 0.0 {
+            return None;
+        }
+
+        match self.interval {
+            Some(interval) => self.remaining = (self.remaining + interval).max(0.0),
+            None => self.handle.finished.set(true)
+        };
+        Some(Rc::clone(&self.callback))
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.handle.finished.get()
+    }
+}
+
+impl Drop for ScheduledTimer {
+
+    fn drop(&mut self) {
+
+        // Timers whose agent is gone will never fire, so their handles shouldn't claim they are pending
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn create_counter() -> (Rc<Cell<u32>>, Rc<RefCell<dyn FnMut()>>) {
+        let counter = Rc::new(Cell::new(0));
+        let callback_counter = Rc::clone(&counter);
+        let callback: Rc<RefCell<dyn FnMut()>> = Rc::new(RefCell::new(move || callback_counter.set(callback_counter.get() + 1)));
+        (counter, callback)
+    }
+
+    fn fire(timer: &mut ScheduledTimer, delta: f64) {
+        if let Some(callback) = timer.advance(delta) {
+            (callback.borrow_mut())();
+        }
+    }
+
+    #[test]
+    fn test_once() {
+        let (counter, callback) = create_counter();
+        let (mut timer, handle) = ScheduledTimer::new(100.0, None, callback);
+
+        fire(&mut timer, 60.0);
+        assert_eq!(0, counter.get());
+        assert!(handle.is_pending());
+
+        fire(&mut timer, 60.0);
+        assert_eq!(1, counter.get());
+        assert!(timer.is_finished());
+        assert!(!handle.is_pending());
+
+        // A finished timer that is dropped was not cancelled
+        drop(timer);
+        assert!(!handle.is_cancelled());
+    }
+
+    #[test]
+    fn test_repeating() {
+        let (counter, callback) = create_counter();
+        let (mut timer, handle) = ScheduledTimer::new(100.0, Some(100.0), callback);
+
+        fire(&mut timer, 100.0);
+        fire(&mut timer, 50.0);
+        assert_eq!(1, counter.get());
+        fire(&mut timer, 50.0);
+        assert_eq!(2, counter.get());
+
+        // The timer should fire only once per update, even if multiple intervals passed
+        fire(&mut timer, 350.0);
+        assert_eq!(3, counter.get());
+        assert!(!timer.is_finished());
+        assert!(handle.is_pending());
+
+        handle.cancel();
+        assert!(timer.is_cancelled());
+        assert!(!handle.is_pending());
+    }
+
+    #[test]
+    fn test_drop() {
+        let (_, callback) = create_counter();
+        let (timer, handle) = ScheduledTimer::new(100.0, Some(100.0), callback);
+        drop(timer);
+        assert!(handle.is_cancelled());
+        assert!(!handle.is_pending());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::*;
+
+use std::rc::Rc;
+
+use wasmuri_core::Region;
+
+pub enum Tooltip {
+
+    /// A tooltip that simply shows the text in a box that uses the colors of the current theme
+    Text(String),
+
+    /// A tooltip with the given size (in OpenGL coordinates) that is drawn by the given function. The function will get the region
+    /// the tooltip should be drawn in.
+    Custom {
+        width: f32,
+        height: f32,
+        render: Box<dyn Fn(&mut RenderParams, Region)>
+    }
+}
+
+impl Tooltip {
+
+    pub fn text(text: &str) -> Rc<Tooltip> {
+        Rc::new(Tooltip::Text(text.to_string()))
+    }
+
+    pub fn custom(width: f32, height: f32, render: Box<dyn Fn(&mut RenderParams, Region)>) -> Rc<Tooltip> {
+        Rc::new(Tooltip::Custom {
+            width,
+            height,
+            render
+        })
+    }
+}
+
+struct HoveredTooltip {
+
+    tooltip: Rc<Tooltip>,
+    start_time: f64,
+    mouse_pos: (f32, f32)
+}
+
+/// Keeps track of the tooltip the mouse is currently hovering over. The layers report which tooltip is hovered and the TooltipLayer
+/// shows it once the mouse has been hovering over it for the dwell time.
+pub struct TooltipState {
+
+    clock: Rc<dyn Clock>,
+
+    dwell_time: f64,
+
+    hovered: Option<HoveredTooltip>,
+
+    /// Set when the user clicks or presses a key. The tooltip will stay hidden until the mouse leaves it.
+    dismissed: bool
+}
+
+impl TooltipState {
+
+    pub fn new() -> TooltipState {
+        Self::with_clock(Rc::new(PerformanceClock::new()))
+    }
+
+    pub fn with_clock(clock: Rc<dyn Clock>) -> TooltipState {
+        TooltipState {
+            clock,
+            dwell_time: 500.0,
+            hovered: None,
+            dismissed: false
+        }
+    }
+
+    /// Sets the Clock that is used to measure how long the mouse has been hovering. The ContainerManager gives its own Clock to
+    /// its TooltipState.
+    pub fn set_clock(&mut self, clock: Rc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Gets the time (in milliseconds) the mouse needs to hover over a component before its tooltip is shown
+    pub fn get_dwell_time(&self) -> f64 {
+        self.dwell_time
+    }
+
+    pub fn set_dwell_time(&mut self, dwell_time: f64) {
+        self.dwell_time = dwell_time;
+    }
+
+    /// Should be called by layers when the mouse moves while it is hovering over the given tooltip
+    pub fn hover(&mut self, tooltip: &Rc<Tooltip>, mouse_pos: (f32, f32)) {
+        match &mut self.hovered {
+            Some(hovered) if Rc::ptr_eq(&hovered.tooltip, tooltip) => {
+
+                // The tooltip shouldn't follow the cursor once it is visible
+                if self.clock.now() - hovered.start_time < self.dwell_time {
+                    hovered.start_time = self.clock.now();
+                    hovered.mouse_pos = mouse_pos;
+                }
+            }, _ => {
+                self.hovered = Some(HoveredTooltip {
+                    tooltip: Rc::clone(tooltip),
+                    start_time: self.clock.now(),
+                    mouse_pos
+                });
+                self.dismissed = false;
+            }
+        };
+    }
+
+    /// Should be called by layers when the mouse is no longer hovering over the given tooltip
+    pub fn leave(&mut self, tooltip: &Rc<Tooltip>) {
+        let is_hovered = match &self.hovered {
+            Some(hovered) => Rc::ptr_eq(&hovered.tooltip, tooltip),
+            None => false
+        };
+
+        if is_hovered {
+            self.hovered = None;
+            self.dismissed = false;
+        }
+    }
+
+    /// Hides the current tooltip until the mouse leaves it. This is called when the user clicks or presses a key.
+    pub fn dismiss(&mut self) {
+        if self.hovered.is_some() {
+            self.dismissed = true;
+        }
+    }
+
+    /// Checks if the mouse is hovering over a tooltip that will be shown once the dwell time has passed
+    pub fn is_waiting(&self) -> bool {
+        match &self.hovered {
+            Some(hovered) => !self.dismissed && self.clock.now() - hovered.start_time < self.dwell_time,
+            None => false
+        }
+    }
+
+    /// Gets the tooltip that should currently be shown and the mouse position it should be shown near, if any
+    pub fn get_visible(&self) -> Option<(Rc<Tooltip>, (f32, f32))> {
+        match &self.hovered {
+            Some(hovered) => {
+                if !self.dismissed && self.clock.now() - hovered.start_time >= self.dwell_time {
+                    Some((Rc::clone(&hovered.tooltip), hovered.mouse_pos))
+                } else {
+                    None
+                }
+            }, None => None
+        }
+    }
+}
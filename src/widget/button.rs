@@ -0,0 +1,169 @@
+use crate::*;
+
+use super::{
+    request_render,
+    get_widget_style
+};
+
+use std::cell::RefCell;
+use std::rc::*;
+
+use wasmuri_core::Region;
+
+pub struct Button {
+
+    behavior: Rc<RefCell<ButtonBehavior>>
+}
+
+impl Button {
+
+    pub fn new(region: Region, text: &str, on_click: Box<dyn FnMut(&mut MouseClickParams)>) -> Button {
+        Button {
+            behavior: Rc::new(RefCell::new(ButtonBehavior {
+                agent: Weak::new(),
+                region,
+                text: text.to_string(),
+                colors: None,
+                enabled: true,
+                hovered: false,
+                on_click
+            }))
+        }
+    }
+
+    pub fn celled(region: Region, text: &str, on_click: Box<dyn FnMut(&mut MouseClickParams)>) -> Rc<RefCell<Button>> {
+        Rc::new(RefCell::new(Self::new(region, text, on_click)))
+    }
+
+    /// Gets the behavior of this button, which can be used to change the text or colors after the button was added to a layer
+    pub fn get_behavior(&self) -> &Rc<RefCell<ButtonBehavior>> {
+        &self.behavior
+    }
+}
+
+impl Component for Button {
+
+    fn create_behaviors(&mut self) -> Vec<Rc<RefCell<dyn ComponentBehavior>>> {
+        let behavior: Rc<RefCell<dyn ComponentBehavior>> = self.behavior.clone();
+        vec![behavior]
+    }
+}
+
+pub struct ButtonBehavior {
+
+    agent: Weak<RefCell<ComponentAgent>>,
+
+    region: Region,
+    text: String,
+    colors: Option<WidgetColors>,
+
+    enabled: bool,
+    hovered: bool,
+
+    on_click: Box<dyn FnMut(&mut MouseClickParams)>
+}
+
+impl ButtonBehavior {
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        request_render(&self.agent);
+    }
+
+    /// Gives this button its own colors instead of the colors of the current theme
+    pub fn set_colors(&mut self, colors: WidgetColors) {
+        self.colors = Some(colors);
+        request_render(&self.agent);
+    }
+
+    /// Lets this button use the colors of the current theme again
+    pub fn clear_colors(&mut self) {
+        self.colors = None;
+        request_render(&self.agent);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Disabled buttons are drawn with the disabled text color and ignore clicks
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        request_render(&self.agent);
+    }
+}
+
+impl ComponentBehavior for ButtonBehavior {
+
+    fn attach(&mut self, agent: &mut dyn LayerAgent) {
+        agent.claim_render_space(self.region, RenderTrigger::MouseInOut, RenderOpacity::Solid, RenderPhase::Text)
+                .expect("Button render space should be free");
+        agent.claim_mouse_click_space(self.region).expect("Button click space should be free");
+        agent.claim_mouse_in_out_space(self.region);
+    }
+
+    fn set_agent(&mut self, agent: Weak<RefCell<ComponentAgent>>) {
+        self.agent = agent;
+    }
+
+    fn get_agent(&self) -> &Weak<RefCell<ComponentAgent>> {
+        &self.agent
+    }
+
+    fn get_access_node(&self) -> Option<AccessNode> {
+        Some(AccessNode::new(AccessRole::Button, &self.text, self.region).with_disabled(!self.enabled))
+    }
+
+    fn access_action(&mut self, params: &mut AccessActionParams) {
+        if params.action == AccessAction::Activate && self.enabled {
+
+            // Assistive technology doesn't click at a position, so pretend the user clicked in the middle of the button
+            let center = ((self.region.get_min_x() + self.region.get_max_x()) * 0.5, (self.region.get_min_y() + self.region.get_max_y()) * 0.5);
+            (self.on_click)(&mut MouseClickParams::new(center, ClickInfo::new(0, false, false, false, false), params.manager));
+        }
+    }
+
+    fn mouse_click_inside(&mut self, params: &mut MouseClickParams) {
+        if self.enabled {
+            (self.on_click)(params);
+        }
+    }
+
+    fn mouse_move(&mut self, params: &mut MouseMoveParams) {
+        self.hovered = params.new_mouse_pos.is_some() && self.region.is_float_inside(params.new_mouse_pos.unwrap());
+    }
+
+    fn render(&mut self, params: &mut RenderParams) -> BehaviorRenderResult {
+        let theme = params.manager.get_theme();
+        let state = if !self.enabled {
+            WidgetState::Disabled
+        } else if self.hovered {
+            WidgetState::Hover
+        } else {
+            WidgetState::Normal
+        };
+        let style = get_widget_style(&theme, &self.colors, state);
+
+        fill_region(params, self.region, style.background);
+        draw_outline(params, self.region, style.border);
+        let text_region = center_text_region(inset_region(self.region, theme.spacing.padding, theme.spacing.padding), theme.fonts.body_height);
+        draw_text(params, &self.text, text_region, style.text);
+
+        BehaviorRenderResult::with_cursor(self.get_cursor_value(), vec![PassedRenderAction::new(self.region)])
+    }
+
+    fn get_cursor(&mut self, _params: &mut CursorParams) -> Option<Cursor> {
+        Some(self.get_cursor_value())
+    }
+}
+
+impl ButtonBehavior {
+
+    fn get_cursor_value(&self) -> Cursor {
+        if self.enabled { Cursor::POINTER } else { Cursor::NOT_ALLOWED }
+    }
+}
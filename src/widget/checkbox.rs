@@ -0,0 +1,153 @@
+use crate::*;
+
+use super::{
+    request_render,
+    get_widget_style,
+    get_widget_palette
+};
+
+use std::cell::RefCell;
+use std::rc::*;
+
+use wasmuri_core::Region;
+
+pub struct Checkbox {
+
+    behavior: Rc<RefCell<CheckboxBehavior>>
+}
+
+impl Checkbox {
+
+    pub fn new(region: Region, text: &str, checked: bool, on_change: Box<dyn FnMut(bool)>) -> Checkbox {
+        Checkbox {
+            behavior: Rc::new(RefCell::new(CheckboxBehavior {
+                agent: Weak::new(),
+                region,
+                text: text.to_string(),
+                colors: None,
+                checked,
+                on_change
+            }))
+        }
+    }
+
+    pub fn celled(region: Region, text: &str, checked: bool, on_change: Box<dyn FnMut(bool)>) -> Rc<RefCell<Checkbox>> {
+        Rc::new(RefCell::new(Self::new(region, text, checked, on_change)))
+    }
+
+    pub fn get_behavior(&self) -> &Rc<RefCell<CheckboxBehavior>> {
+        &self.behavior
+    }
+}
+
+impl Component for Checkbox {
+
+    fn create_behaviors(&mut self) -> Vec<Rc<RefCell<dyn ComponentBehavior>>> {
+        let behavior: Rc<RefCell<dyn ComponentBehavior>> = self.behavior.clone();
+        vec![behavior]
+    }
+}
+
+pub struct CheckboxBehavior {
+
+    agent: Weak<RefCell<ComponentAgent>>,
+
+    region: Region,
+    text: String,
+    colors: Option<WidgetColors>,
+
+    checked: bool,
+    on_change: Box<dyn FnMut(bool)>
+}
+
+impl CheckboxBehavior {
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    /// Changes the checked state without calling the on_change callback
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+        request_render(&self.agent);
+    }
+
+    /// Gives this checkbox its own colors instead of the colors of the current theme
+    pub fn set_colors(&mut self, colors: WidgetColors) {
+        self.colors = Some(colors);
+        request_render(&self.agent);
+    }
+
+    /// Lets this checkbox use the colors of the current theme again
+    pub fn clear_colors(&mut self) {
+        self.colors = None;
+        request_render(&self.agent);
+    }
+
+    /// The box is a square at the left side of the region, the text is drawn right of it
+    fn get_box_region(&self) -> Region {
+        let height = self.region.get_max_y() - self.region.get_min_y();
+        inset_region(Region::new(self.region.get_min_x(), self.region.get_min_y(), self.region.get_min_x() + height, self.region.get_max_y()),
+                height * 0.15, height * 0.15)
+    }
+}
+
+impl ComponentBehavior for CheckboxBehavior {
+
+    fn attach(&mut self, agent: &mut dyn LayerAgent) {
+        agent.claim_render_space(self.region, RenderTrigger::Request, RenderOpacity::Solid, RenderPhase::Text)
+                .expect("Checkbox render space should be free");
+        agent.claim_mouse_click_space(self.region).expect("Checkbox click space should be free");
+    }
+
+    fn set_agent(&mut self, agent: Weak<RefCell<ComponentAgent>>) {
+        self.agent = agent;
+    }
+
+    fn get_agent(&self) -> &Weak<RefCell<ComponentAgent>> {
+        &self.agent
+    }
+
+    fn get_access_node(&self) -> Option<AccessNode> {
+        Some(AccessNode::new(AccessRole::Checkbox, &self.text, self.region).with_checked(self.checked))
+    }
+
+    fn access_action(&mut self, params: &mut AccessActionParams) {
+        if params.action == AccessAction::Activate {
+            self.checked = !self.checked;
+            (self.on_change)(self.checked);
+            request_render(&self.agent);
+        }
+    }
+
+    fn mouse_click_inside(&mut self, _params: &mut MouseClickParams) {
+        self.checked = !self.checked;
+        (self.on_change)(self.checked);
+        request_render(&self.agent);
+    }
+
+    fn render(&mut self, params: &mut RenderParams) -> BehaviorRenderResult {
+        let theme = params.manager.get_theme();
+        let palette = get_widget_palette(&theme, &self.colors);
+        let style = get_widget_style(&theme, &self.colors, WidgetState::Normal);
+
+        let box_region = self.get_box_region();
+        let box_size = box_region.get_max_y() - box_region.get_min_y();
+
+        fill_region(params, self.region, palette.background);
+        fill_region(params, box_region, style.background);
+        draw_outline(params, box_region, style.border);
+        if self.checked {
+            fill_region(params, inset_region(box_region, box_size * 0.2, box_size * 0.2), style.accent);
+        }
+
+        let text_region = Region::new(box_region.get_max_x() + theme.spacing.padding, self.region.get_min_y(), self.region.get_max_x(), self.region.get_max_y());
+        draw_text(params, &self.text, center_text_region(text_region, theme.fonts.body_height), style.text);
+
+        BehaviorRenderResult::with_cursor(Cursor::POINTER, vec![PassedRenderAction::new(self.region)])
+    }
+
+    fn get_cursor(&mut self, _params: &mut CursorParams) -> Option<Cursor> {
+        Some(Cursor::POINTER)
+    }
+}
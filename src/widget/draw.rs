@@ -0,0 +1,106 @@
+use crate::*;
+
+use wasmuri_core::*;
+
+/// Converts the given region (in OpenGL coordinates) to a pixel rectangle (x, y, width, height) that can be passed to gl.scissor
+pub fn to_pixel_rect(manager: &ContainerManager, region: Region) -> (i32, i32, i32, i32) {
+    let canvas_width = manager.get_canvas().width() as f32;
+    let canvas_height = manager.get_canvas().height() as f32;
+
+    let min_x = ((region.get_min_x() + 1.0) * 0.5 * canvas_width).round() as i32;
+    let min_y = ((region.get_min_y() + 1.0) * 0.5 * canvas_height).round() as i32;
+    let max_x = ((region.get_max_x() + 1.0) * 0.5 * canvas_width).round() as i32;
+    let max_y = ((region.get_max_y() + 1.0) * 0.5 * canvas_height).round() as i32;
+
+    (min_x, min_y, max_x - min_x, max_y - min_y)
+}
+
+/// Fills the given region with the given color. The color is written directly, so transparent colors will not be blended with
+/// whatever was drawn in the region before.
+pub fn fill_region(params: &mut RenderParams, region: Region, color: Color) {
+    params.clear(region, color);
+}
+
+/// Fills the given region with the given color, blended with whatever was drawn in the region before. Unlike fill_region, the quad
+/// is batched with the quads of the other behaviors.
+pub fn blend_region(params: &mut RenderParams, region: Region, color: Color) {
+    params.fill(region, color);
+}
+
+/// Draws a border with a thickness of 1 pixel along the edges of the given region
+pub fn draw_outline(params: &mut RenderParams, region: Region, color: Color) {
+    let pixel_width = 2.0 / params.manager.get_canvas().width() as f32;
+    let pixel_height = 2.0 / params.manager.get_canvas().height() as f32;
+
+    let min_x = region.get_min_x();
+    let min_y = region.get_min_y();
+    let max_x = region.get_max_x();
+    let max_y = region.get_max_y();
+
+    fill_region(params, Region::new(min_x, min_y, max_x, min_y + pixel_height), color);
+    fill_region(params, Region::new(min_x, max_y - pixel_height, max_x, max_y), color);
+    fill_region(params, Region::new(min_x, min_y, min_x + pixel_width, max_y), color);
+    fill_region(params, Region::new(max_x - pixel_width, min_y, max_x, max_y), color);
+}
+
+/// Draws the given text inside the given region with the TextRenderer of the manager and returns the region the text was drawn in.
+/// The pending quads are drawn first, so the text will be drawn on top of them.
+pub fn draw_text(params: &mut RenderParams, text: &str, region: Region, color: Color) -> Region {
+    if text.is_empty() {
+        return Region::new(region.get_min_x(), region.get_min_y(), region.get_min_x(), region.get_max_y());
+    }
+
+    let mut text_renderer = params.manager.get_text_renderer().borrow_mut();
+    params.get_render_context().borrow_mut().begin_text(&mut text_renderer);
+    text_renderer.draw_text(text, region, color)
+}
+
+/// Returns a region that is smaller than the given region: dx is removed from both horizontal sides and dy is removed from
+/// both vertical sides.
+pub fn inset_region(region: Region, dx: f32, dy: f32) -> Region {
+    Region::new(region.get_min_x() + dx, region.get_min_y() + dy, region.get_max_x() - dx, region.get_max_y() - dy)
+}
+
+/// Returns the region where the given regions overlap, or None if they don't overlap
+pub fn intersect_regions(a: Region, b: Region) -> Option<Region> {
+    let min_x = a.get_min_x().max(b.get_min_x());
+    let min_y = a.get_min_y().max(b.get_min_y());
+    let max_x = a.get_max_x().min(b.get_max_x());
+    let max_y = a.get_max_y().min(b.get_max_y());
+
+    if min_x < max_x && min_y < max_y {
+        Some(Region::new(min_x, min_y, max_x, max_y))
+    } else {
+        None
+    }
+}
+
+/// Returns a region with the same horizontal bounds as the given region, but with the given text height, vertically centered in
+/// the given region. If the region is smaller than the text height, the region itself is returned.
+pub fn center_text_region(region: Region, text_height: f32) -> Region {
+    let height = region.get_max_y() - region.get_min_y();
+    if height <= text_height {
+        return region;
+    }
+
+    let margin = (height - text_height) * 0.5;
+    Region::new(region.get_min_x(), region.get_min_y() + margin, region.get_max_x(), region.get_max_y() - margin)
+}
+
+/// Splits the given region into num_rows rows of equal height and returns the row with the given index, where row 0 is the top row
+pub fn get_row_region(region: Region, num_rows: usize, row_index: usize) -> Region {
+    let row_height = (region.get_max_y() - region.get_min_y()) / num_rows as f32;
+    let max_y = region.get_max_y() - row_index as f32 * row_height;
+    Region::new(region.get_min_x(), max_y - row_height, region.get_max_x(), max_y)
+}
+
+/// Finds the index of the row (as defined by get_row_region) that contains the given point, if any
+pub fn get_row_index(region: Region, num_rows: usize, point: (f32, f32)) -> Option<usize> {
+    if num_rows == 0 || !region.is_float_inside(point) {
+        return None;
+    }
+
+    let row_height = (region.get_max_y() - region.get_min_y()) / num_rows as f32;
+    let row_index = ((region.get_max_y() - point.1) / row_height) as usize;
+    Some(row_index.min(num_rows - 1))
+}
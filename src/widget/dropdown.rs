@@ -0,0 +1,195 @@
+use crate::*;
+
+use super::{
+    request_render,
+    get_widget_style,
+    get_widget_palette
+};
+
+use std::cell::RefCell;
+use std::rc::*;
+
+use wasmuri_core::Region;
+
+/// A box that shows the selected option and expands a list with all options below it when it is clicked.
+/// The dropdown claims the region of the expanded list as well, but only draws it while it is expanded, so it uses
+/// RenderOpacity::DynamicSolidOrNothing. To make sure whatever is behind the list is drawn again when the list collapses,
+/// the dropdown should be added to a layer in front of the components behind the list.
+pub struct Dropdown {
+
+    behavior: Rc<RefCell<DropdownBehavior>>
+}
+
+impl Dropdown {
+
+    pub fn new(region: Region, options: Vec<String>, selected: usize, on_change: Box<dyn FnMut(usize)>) -> Dropdown {
+        Dropdown {
+            behavior: Rc::new(RefCell::new(DropdownBehavior {
+                agent: Weak::new(),
+                region,
+                colors: None,
+                options,
+                selected,
+                expanded: false,
+                on_change
+            }))
+        }
+    }
+
+    pub fn celled(region: Region, options: Vec<String>, selected: usize, on_change: Box<dyn FnMut(usize)>) -> Rc<RefCell<Dropdown>> {
+        Rc::new(RefCell::new(Self::new(region, options, selected, on_change)))
+    }
+
+    pub fn get_behavior(&self) -> &Rc<RefCell<DropdownBehavior>> {
+        &self.behavior
+    }
+}
+
+impl Component for Dropdown {
+
+    fn create_behaviors(&mut self) -> Vec<Rc<RefCell<dyn ComponentBehavior>>> {
+        let behavior: Rc<RefCell<dyn ComponentBehavior>> = self.behavior.clone();
+        vec![behavior]
+    }
+}
+
+pub struct DropdownBehavior {
+
+    agent: Weak<RefCell<ComponentAgent>>,
+
+    /// The region of the collapsed box
+    region: Region,
+    colors: Option<WidgetColors>,
+
+    options: Vec<String>,
+    selected: usize,
+    expanded: bool,
+
+    on_change: Box<dyn FnMut(usize)>
+}
+
+impl DropdownBehavior {
+
+    pub fn get_options(&self) -> &[String] {
+        &self.options
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Changes the selected option without calling the on_change callback
+    pub fn set_selected(&mut self, selected: usize) {
+        self.selected = selected;
+        request_render(&self.agent);
+    }
+
+    /// Gives this dropdown its own colors instead of the colors of the current theme
+    pub fn set_colors(&mut self, colors: WidgetColors) {
+        self.colors = Some(colors);
+        request_render(&self.agent);
+    }
+
+    /// Lets this dropdown use the colors of the current theme again
+    pub fn clear_colors(&mut self) {
+        self.colors = None;
+        request_render(&self.agent);
+    }
+
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+
+    /// The region of the option list, which is right below the box
+    fn get_list_region(&self) -> Region {
+        let row_height = self.region.get_max_y() - self.region.get_min_y();
+        Region::new(self.region.get_min_x(), self.region.get_min_y() - row_height * self.options.len() as f32, 
+                self.region.get_max_x(), self.region.get_min_y())
+    }
+
+    /// The region of the box and the expanded list together
+    fn get_full_region(&self) -> Region {
+        Region::new(self.region.get_min_x(), self.get_list_region().get_min_y(), self.region.get_max_x(), self.region.get_max_y())
+    }
+
+    fn set_expanded(&mut self, expanded: bool) {
+        if self.expanded != expanded {
+            self.expanded = expanded;
+            request_render(&self.agent);
+        }
+    }
+}
+
+impl ComponentBehavior for DropdownBehavior {
+
+    fn attach(&mut self, agent: &mut dyn LayerAgent) {
+        let full_region = self.get_full_region();
+        agent.claim_render_space(full_region, RenderTrigger::Request, RenderOpacity::DynamicSolidOrNothing, RenderPhase::Text)
+                .expect("Dropdown render space should be free");
+        agent.claim_mouse_click_space(full_region).expect("Dropdown click space should be free");
+    }
+
+    fn set_agent(&mut self, agent: Weak<RefCell<ComponentAgent>>) {
+        self.agent = agent;
+    }
+
+    fn get_agent(&self) -> &Weak<RefCell<ComponentAgent>> {
+        &self.agent
+    }
+
+    fn mouse_click_inside(&mut self, params: &mut MouseClickParams) {
+        if self.region.is_float_inside(params.mouse_pos) {
+            let expanded = !self.expanded;
+            self.set_expanded(expanded);
+        } else if self.expanded {
+            if let Some(index) = get_row_index(self.get_list_region(), self.options.len(), params.mouse_pos) {
+                if index != self.selected {
+                    self.selected = index;
+                    (self.on_change)(index);
+                }
+            }
+            self.set_expanded(false);
+        }
+    }
+
+    fn mouse_click_outside(&mut self, _params: &mut MouseClickOutParams) {
+        self.set_expanded(false);
+    }
+
+    fn render(&mut self, params: &mut RenderParams) -> BehaviorRenderResult {
+        let theme = params.manager.get_theme();
+        let palette = get_widget_palette(&theme, &self.colors);
+        let style = get_widget_style(&theme, &self.colors, if self.expanded { WidgetState::Focused } else { WidgetState::Normal });
+        let padding = theme.spacing.padding;
+
+        fill_region(params, self.region, style.background);
+        draw_outline(params, self.region, style.border);
+        if let Some(selected_text) = self.options.get(self.selected) {
+            draw_text(params, selected_text, center_text_region(inset_region(self.region, padding, padding), theme.fonts.body_height), style.text);
+        }
+
+        let mut render_actions = vec![PassedRenderAction::new(self.region)];
+
+        if self.expanded {
+            let list_region = self.get_list_region();
+            fill_region(params, list_region, palette.surface);
+            draw_outline(params, list_region, style.border);
+
+            for (index, option) in self.options.iter().enumerate() {
+                let row = get_row_region(list_region, self.options.len(), index);
+                if index == self.selected {
+                    fill_region(params, inset_region(row, padding, padding * 0.5), get_widget_style(&theme, &self.colors, WidgetState::Hover).background);
+                }
+                draw_text(params, option, center_text_region(inset_region(row, padding, padding), theme.fonts.body_height), palette.text);
+            }
+
+            render_actions.push(PassedRenderAction::new(list_region));
+        }
+
+        BehaviorRenderResult::with_cursor(Cursor::POINTER, render_actions)
+    }
+
+    fn get_cursor(&mut self, _params: &mut CursorParams) -> Option<Cursor> {
+        Some(Cursor::POINTER)
+    }
+}
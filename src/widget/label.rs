@@ -0,0 +1,109 @@
+use crate::*;
+
+use super::{
+    request_render,
+    get_widget_palette
+};
+
+use std::cell::RefCell;
+use std::rc::*;
+
+use wasmuri_core::Region;
+
+pub struct Label {
+
+    behavior: Rc<RefCell<LabelBehavior>>
+}
+
+impl Label {
+
+    pub fn new(region: Region, text: &str) -> Label {
+        Label {
+            behavior: Rc::new(RefCell::new(LabelBehavior {
+                agent: Weak::new(),
+                region,
+                text: text.to_string(),
+                colors: None
+            }))
+        }
+    }
+
+    pub fn celled(region: Region, text: &str) -> Rc<RefCell<Label>> {
+        Rc::new(RefCell::new(Self::new(region, text)))
+    }
+
+    pub fn get_behavior(&self) -> &Rc<RefCell<LabelBehavior>> {
+        &self.behavior
+    }
+}
+
+impl Component for Label {
+
+    fn create_behaviors(&mut self) -> Vec<Rc<RefCell<dyn ComponentBehavior>>> {
+        let behavior: Rc<RefCell<dyn ComponentBehavior>> = self.behavior.clone();
+        vec![behavior]
+    }
+}
+
+pub struct LabelBehavior {
+
+    agent: Weak<RefCell<ComponentAgent>>,
+
+    region: Region,
+    text: String,
+    colors: Option<WidgetColors>
+}
+
+impl LabelBehavior {
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        request_render(&self.agent);
+    }
+
+    /// Gives this label its own colors instead of the colors of the current theme
+    pub fn set_colors(&mut self, colors: WidgetColors) {
+        self.colors = Some(colors);
+        request_render(&self.agent);
+    }
+
+    /// Lets this label use the colors of the current theme again
+    pub fn clear_colors(&mut self) {
+        self.colors = None;
+        request_render(&self.agent);
+    }
+}
+
+impl ComponentBehavior for LabelBehavior {
+
+    fn attach(&mut self, agent: &mut dyn LayerAgent) {
+        agent.claim_render_space(self.region, RenderTrigger::Request, RenderOpacity::Solid, RenderPhase::Text)
+                .expect("Label render space should be free");
+    }
+
+    fn set_agent(&mut self, agent: Weak<RefCell<ComponentAgent>>) {
+        self.agent = agent;
+    }
+
+    fn get_agent(&self) -> &Weak<RefCell<ComponentAgent>> {
+        &self.agent
+    }
+
+    fn get_access_node(&self) -> Option<AccessNode> {
+        Some(AccessNode::new(AccessRole::Label, &self.text, self.region))
+    }
+
+    fn render(&mut self, params: &mut RenderParams) -> BehaviorRenderResult {
+        let theme = params.manager.get_theme();
+        let palette = get_widget_palette(&theme, &self.colors);
+
+        fill_region(params, self.region, palette.background);
+        draw_text(params, &self.text, center_text_region(self.region, theme.fonts.body_height), palette.text);
+
+        BehaviorRenderResult::without_cursor(vec![PassedRenderAction::new(self.region)])
+    }
+}
@@ -0,0 +1,181 @@
+use crate::*;
+
+use super::{
+    request_render,
+    get_widget_palette
+};
+
+use std::cell::RefCell;
+use std::rc::*;
+
+use wasmuri_core::Region;
+
+/// A scrollable list of items where the user can select 1 item. Only visible_rows items fit in the region at the same time,
+/// the others can be reached by scrolling.
+pub struct ListView {
+
+    behavior: Rc<RefCell<ListViewBehavior>>
+}
+
+impl ListView {
+
+    pub fn new(region: Region, items: Vec<String>, visible_rows: usize, on_select: Box<dyn FnMut(usize)>) -> ListView {
+        ListView {
+            behavior: Rc::new(RefCell::new(ListViewBehavior {
+                agent: Weak::new(),
+                region,
+                colors: None,
+                items,
+                visible_rows: visible_rows.max(1),
+                scroll_offset: 0,
+                selected: None,
+                on_select
+            }))
+        }
+    }
+
+    pub fn celled(region: Region, items: Vec<String>, visible_rows: usize, on_select: Box<dyn FnMut(usize)>) -> Rc<RefCell<ListView>> {
+        Rc::new(RefCell::new(Self::new(region, items, visible_rows, on_select)))
+    }
+
+    pub fn get_behavior(&self) -> &Rc<RefCell<ListViewBehavior>> {
+        &self.behavior
+    }
+}
+
+impl Component for ListView {
+
+    fn create_behaviors(&mut self) -> Vec<Rc<RefCell<dyn ComponentBehavior>>> {
+        let behavior: Rc<RefCell<dyn ComponentBehavior>> = self.behavior.clone();
+        vec![behavior]
+    }
+}
+
+pub struct ListViewBehavior {
+
+    agent: Weak<RefCell<ComponentAgent>>,
+
+    region: Region,
+    colors: Option<WidgetColors>,
+
+    items: Vec<String>,
+    visible_rows: usize,
+
+    /// The index of the item in the top row
+    scroll_offset: usize,
+    selected: Option<usize>,
+
+    on_select: Box<dyn FnMut(usize)>
+}
+
+impl ListViewBehavior {
+
+    pub fn get_items(&self) -> &[String] {
+        &self.items
+    }
+
+    pub fn set_items(&mut self, items: Vec<String>) {
+        self.items = items;
+        self.scroll_offset = self.scroll_offset.min(self.get_max_scroll_offset());
+        if self.selected.is_some() && self.selected.unwrap() >= self.items.len() {
+            self.selected = None;
+        }
+        request_render(&self.agent);
+    }
+
+    pub fn get_selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Changes the selected item without calling the on_select callback
+    pub fn set_selected(&mut self, selected: Option<usize>) {
+        self.selected = selected;
+        request_render(&self.agent);
+    }
+
+    /// Gives this list view its own colors instead of the colors of the current theme
+    pub fn set_colors(&mut self, colors: WidgetColors) {
+        self.colors = Some(colors);
+        request_render(&self.agent);
+    }
+
+    /// Lets this list view use the colors of the current theme again
+    pub fn clear_colors(&mut self) {
+        self.colors = None;
+        request_render(&self.agent);
+    }
+
+    fn get_max_scroll_offset(&self) -> usize {
+        self.items.len().saturating_sub(self.visible_rows)
+    }
+}
+
+impl ComponentBehavior for ListViewBehavior {
+
+    fn attach(&mut self, agent: &mut dyn LayerAgent) {
+        agent.claim_render_space(self.region, RenderTrigger::Request, RenderOpacity::Solid, RenderPhase::Text)
+                .expect("ListView render space should be free");
+        agent.claim_mouse_click_space(self.region).expect("ListView click space should be free");
+        agent.claim_mouse_scroll_space(self.region).expect("ListView scroll space should be free");
+    }
+
+    fn set_agent(&mut self, agent: Weak<RefCell<ComponentAgent>>) {
+        self.agent = agent;
+    }
+
+    fn get_agent(&self) -> &Weak<RefCell<ComponentAgent>> {
+        &self.agent
+    }
+
+    fn mouse_click_inside(&mut self, params: &mut MouseClickParams) {
+        if let Some(row_index) = get_row_index(self.region, self.visible_rows, params.mouse_pos) {
+            let item_index = self.scroll_offset + row_index;
+            if item_index < self.items.len() && self.selected != Some(item_index) {
+                self.selected = Some(item_index);
+                (self.on_select)(item_index);
+                request_render(&self.agent);
+            }
+        }
+    }
+
+    fn mouse_scroll(&mut self, params: &mut MouseScrollParams) -> bool {
+        let new_offset = if params.delta > 0.0 {
+            (self.scroll_offset + 1).min(self.get_max_scroll_offset())
+        } else if params.delta < 0.0 {
+            self.scroll_offset.saturating_sub(1)
+        } else {
+            self.scroll_offset
+        };
+
+        if new_offset != self.scroll_offset {
+            self.scroll_offset = new_offset;
+            request_render(&self.agent);
+        }
+        true
+    }
+
+    fn render(&mut self, params: &mut RenderParams) -> BehaviorRenderResult {
+        let theme = params.manager.get_theme();
+        let palette = get_widget_palette(&theme, &self.colors);
+        let padding = theme.spacing.padding;
+
+        fill_region(params, self.region, palette.surface);
+        draw_outline(params, self.region, palette.border);
+
+        for row_index in 0 .. self.visible_rows {
+            let item_index = self.scroll_offset + row_index;
+            if item_index >= self.items.len() {
+                break;
+            }
+
+            let row = get_row_region(self.region, self.visible_rows, row_index);
+            if self.selected == Some(item_index) {
+                fill_region(params, inset_region(row, padding, padding * 0.5), palette.accent);
+            }
+            let text_region = center_text_region(inset_region(row, padding, padding), theme.fonts.body_height);
+            draw_text(params, &self.items[item_index], text_region, palette.text);
+        }
+
+        BehaviorRenderResult::without_cursor(vec![PassedRenderAction::new(self.region)])
+    }
+}
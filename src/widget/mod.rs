@@ -0,0 +1,102 @@
+use crate::*;
+
+mod draw;
+mod button;
+mod label;
+mod checkbox;
+mod radio;
+mod slider;
+mod textfield;
+mod dropdown;
+mod listview;
+mod progressbar;
+
+use std::cell::RefCell;
+use std::rc::Weak;
+
+use wasmuri_core::Color;
+
+pub use draw::*;
+pub use button::*;
+pub use label::*;
+pub use checkbox::*;
+pub use radio::*;
+pub use slider::*;
+pub use textfield::*;
+pub use dropdown::*;
+pub use listview::*;
+pub use progressbar::*;
+
+/// Colors that can be given to a built-in widget to draw it with instead of the colors of the current theme
+#[derive(Clone,Copy)]
+pub struct WidgetColors {
+
+    pub background: Color,
+    pub hover_background: Color,
+    pub border: Color,
+    pub text: Color,
+    pub disabled_text: Color,
+    pub accent: Color
+}
+
+impl WidgetColors {
+
+    pub fn new() -> WidgetColors {
+        WidgetColors {
+            background: Color::from_rgb(225, 225, 225),
+            hover_background: Color::from_rgb(240, 240, 240),
+            border: Color::from_rgb(120, 120, 120),
+            text: Color::from_rgb(20, 20, 20),
+            disabled_text: Color::from_rgb(150, 150, 150),
+            accent: Color::from_rgb(40, 110, 220)
+        }
+    }
+
+    /// Converts these colors to the WidgetStyle for the given state
+    pub fn to_style(&self, state: WidgetState) -> WidgetStyle {
+        WidgetStyle {
+            background: match state {
+                WidgetState::Hover | WidgetState::Pressed => self.hover_background,
+                _ => self.background
+            },
+            text: if state == WidgetState::Disabled { self.disabled_text } else { self.text },
+            border: self.border,
+            accent: self.accent
+        }
+    }
+
+    /// Converts these colors to a Palette. The surface is the hover background and the secondary text is the disabled text.
+    pub fn to_palette(&self) -> Palette {
+        Palette {
+            background: self.background,
+            surface: self.hover_background,
+            text: self.text,
+            secondary_text: self.disabled_text,
+            border: self.border,
+            accent: self.accent
+        }
+    }
+}
+
+/// Gets the style a widget with the given colors should use in the given state. Widgets without colors use the style of the theme.
+fn get_widget_style(theme: &Theme, colors: &Option<WidgetColors>, state: WidgetState) -> WidgetStyle {
+    match colors {
+        Some(colors) => colors.to_style(state),
+        None => *theme.get_style(state)
+    }
+}
+
+/// Gets the palette a widget with the given colors should use. Widgets without colors use the palette of the theme.
+fn get_widget_palette(theme: &Theme, colors: &Option<WidgetColors>) -> Palette {
+    match colors {
+        Some(colors) => colors.to_palette(),
+        None => theme.palette
+    }
+}
+
+/// Requests the component of the given agent to re-render. The built-in widgets use this when their state is changed from outside.
+fn request_render(agent: &Weak<RefCell<ComponentAgent>>) {
+    if let Some(agent_cell) = agent.upgrade() {
+        agent_cell.borrow_mut().request_render();
+    }
+}
@@ -0,0 +1,130 @@
+use crate::*;
+
+use super::{
+    request_render,
+    get_widget_style
+};
+
+use std::cell::RefCell;
+use std::rc::*;
+
+use wasmuri_core::Region;
+
+pub struct ProgressBar {
+
+    behavior: Rc<RefCell<ProgressBarBehavior>>
+}
+
+impl ProgressBar {
+
+    pub fn new(region: Region, progress: f32) -> ProgressBar {
+        ProgressBar {
+            behavior: Rc::new(RefCell::new(ProgressBarBehavior {
+                agent: Weak::new(),
+                region,
+                colors: None,
+                access_name: "Progress".to_string(),
+                progress: progress.max(0.0).min(1.0)
+            }))
+        }
+    }
+
+    pub fn celled(region: Region, progress: f32) -> Rc<RefCell<ProgressBar>> {
+        Rc::new(RefCell::new(Self::new(region, progress)))
+    }
+
+    pub fn get_behavior(&self) -> &Rc<RefCell<ProgressBarBehavior>> {
+        &self.behavior
+    }
+}
+
+impl Component for ProgressBar {
+
+    fn create_behaviors(&mut self) -> Vec<Rc<RefCell<dyn ComponentBehavior>>> {
+        let behavior: Rc<RefCell<dyn ComponentBehavior>> = self.behavior.clone();
+        vec![behavior]
+    }
+}
+
+pub struct ProgressBarBehavior {
+
+    agent: Weak<RefCell<ComponentAgent>>,
+
+    region: Region,
+    colors: Option<WidgetColors>,
+
+    /// The name assistive technology uses for this progress bar
+    access_name: String,
+
+    /// The progress as a fraction between 0 and 1
+    progress: f32
+}
+
+impl ProgressBarBehavior {
+
+    pub fn get_progress(&self) -> f32 {
+        self.progress
+    }
+
+    /// Sets the progress to the given fraction, which will be clamped between 0 and 1
+    pub fn set_progress(&mut self, progress: f32) {
+        let progress = progress.max(0.0).min(1.0);
+        if progress != self.progress {
+            self.progress = progress;
+            request_render(&self.agent);
+        }
+    }
+
+    /// Gives this progress bar its own colors instead of the colors of the current theme
+    pub fn set_colors(&mut self, colors: WidgetColors) {
+        self.colors = Some(colors);
+        request_render(&self.agent);
+    }
+
+    /// Lets this progress bar use the colors of the current theme again
+    pub fn clear_colors(&mut self) {
+        self.colors = None;
+        request_render(&self.agent);
+    }
+    /// Sets the name that assistive technology uses for this progress bar, which is "Progress" by default
+    pub fn set_access_name(&mut self, name: &str) {
+        self.access_name = name.to_string();
+    }
+}
+
+impl ComponentBehavior for ProgressBarBehavior {
+
+    fn attach(&mut self, agent: &mut dyn LayerAgent) {
+        agent.claim_render_space(self.region, RenderTrigger::Request, RenderOpacity::Solid, RenderPhase::Start)
+                .expect("ProgressBar render space should be free");
+    }
+
+    fn set_agent(&mut self, agent: Weak<RefCell<ComponentAgent>>) {
+        self.agent = agent;
+    }
+
+    fn get_agent(&self) -> &Weak<RefCell<ComponentAgent>> {
+        &self.agent
+    }
+
+    fn get_access_node(&self) -> Option<AccessNode> {
+        Some(AccessNode::new(AccessRole::ProgressBar, &self.access_name, self.region).with_value(&format!("{}%", (self.progress * 100.0).round())))
+    }
+
+    fn render(&mut self, params: &mut RenderParams) -> BehaviorRenderResult {
+        let width = self.region.get_max_x() - self.region.get_min_x();
+
+        let theme = params.manager.get_theme();
+        let style = get_widget_style(&theme, &self.colors, WidgetState::Normal);
+
+        fill_region(params, self.region, style.background);
+        if self.progress > 0.0 {
+            let filled = Region::new(self.region.get_min_x(), self.region.get_min_y(), 
+                    self.region.get_min_x() + width * self.progress, self.region.get_max_y());
+            fill_region(params, filled, style.accent);
+        }
+        draw_outline(params, self.region, style.border);
+
+        BehaviorRenderResult::without_cursor(vec![PassedRenderAction::new(self.region)])
+    }
+}
@@ -0,0 +1,193 @@
+use crate::*;
+
+use super::{
+    request_render,
+    get_widget_style,
+    get_widget_palette
+};
+
+use std::cell::RefCell;
+use std::rc::*;
+
+use wasmuri_core::Region;
+
+/// A group of options where at most 1 option can be selected. The options are stacked vertically inside the region of the group.
+pub struct RadioGroup {
+
+    behavior: Rc<RefCell<RadioGroupBehavior>>
+}
+
+impl RadioGroup {
+
+    pub fn new(region: Region, options: Vec<String>, selected: Option<usize>, on_change: Box<dyn FnMut(usize)>) -> RadioGroup {
+        RadioGroup {
+            behavior: Rc::new(RefCell::new(RadioGroupBehavior {
+                agent: Weak::new(),
+                region,
+                options,
+                colors: None,
+                access_name: "Options".to_string(),
+                selected,
+                on_change
+            }))
+        }
+    }
+
+    pub fn celled(region: Region, options: Vec<String>, selected: Option<usize>, on_change: Box<dyn FnMut(usize)>) -> Rc<RefCell<RadioGroup>> {
+        Rc::new(RefCell::new(Self::new(region, options, selected, on_change)))
+    }
+
+    pub fn get_behavior(&self) -> &Rc<RefCell<RadioGroupBehavior>> {
+        &self.behavior
+    }
+}
+
+impl Component for RadioGroup {
+
+    fn create_behaviors(&mut self) -> Vec<Rc<RefCell<dyn ComponentBehavior>>> {
+        let behavior: Rc<RefCell<dyn ComponentBehavior>> = self.behavior.clone();
+        vec![behavior]
+    }
+}
+
+pub struct RadioGroupBehavior {
+
+    agent: Weak<RefCell<ComponentAgent>>,
+
+    region: Region,
+    options: Vec<String>,
+    colors: Option<WidgetColors>,
+
+    /// The name assistive technology uses for this radio group
+    access_name: String,
+
+    selected: Option<usize>,
+    on_change: Box<dyn FnMut(usize)>
+}
+
+impl RadioGroupBehavior {
+
+    pub fn get_options(&self) -> &[String] {
+        &self.options
+    }
+
+    pub fn get_selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Changes the selected option without calling the on_change callback
+    pub fn set_selected(&mut self, selected: Option<usize>) {
+        self.selected = selected;
+        request_render(&self.agent);
+    }
+
+    /// Gives this radio group its own colors instead of the colors of the current theme
+    pub fn set_colors(&mut self, colors: WidgetColors) {
+        self.colors = Some(colors);
+        request_render(&self.agent);
+    }
+
+    /// Lets this radio group use the colors of the current theme again
+    pub fn clear_colors(&mut self) {
+        self.colors = None;
+        request_render(&self.agent);
+    }
+
+    /// Sets the name that assistive technology uses for this radio group, which is "Options" by default
+    pub fn set_access_name(&mut self, name: &str) {
+        self.access_name = name.to_string();
+    }
+
+    /// Selects the option with the given index and calls the on_change callback, unless it was already selected
+    fn select(&mut self, index: usize) {
+        if self.selected != Some(index) {
+            self.selected = Some(index);
+            (self.on_change)(index);
+            request_render(&self.agent);
+        }
+    }
+}
+
+impl ComponentBehavior for RadioGroupBehavior {
+
+    fn attach(&mut self, agent: &mut dyn LayerAgent) {
+        agent.claim_render_space(self.region, RenderTrigger::Request, RenderOpacity::Solid, RenderPhase::Text)
+                .expect("RadioGroup render space should be free");
+        agent.claim_mouse_click_space(self.region).expect("RadioGroup click space should be free");
+    }
+
+    fn set_agent(&mut self, agent: Weak<RefCell<ComponentAgent>>) {
+        self.agent = agent;
+    }
+
+    fn get_agent(&self) -> &Weak<RefCell<ComponentAgent>> {
+        &self.agent
+    }
+
+    fn get_access_node(&self) -> Option<AccessNode> {
+        let mut node = AccessNode::new(AccessRole::RadioGroup, &self.access_name, self.region);
+        if let Some(selected) = self.selected {
+            node = node.with_value(&self.options[selected]);
+        }
+        for (index, option) in self.options.iter().enumerate() {
+            let row = get_row_region(self.region, self.options.len(), index);
+            node = node.with_child(AccessNode::new(AccessRole::Radio, option, row).with_checked(self.selected == Some(index)));
+        }
+        Some(node)
+    }
+
+    fn access_action(&mut self, params: &mut AccessActionParams) {
+        let num_options = self.options.len();
+        if num_options == 0 {
+            return;
+        }
+
+        match params.action {
+            AccessAction::ActivateChild(index) if index < num_options => self.select(index),
+            AccessAction::Activate => {
+                if self.selected.is_none() {
+                    self.select(0);
+                }
+            }, AccessAction::Increment => self.select(self.selected.map_or(0, |selected| (selected + 1) % num_options)),
+            AccessAction::Decrement => self.select(self.selected.map_or(num_options - 1, |selected| (selected + num_options - 1) % num_options)),
+            _ => {}
+        };
+    }
+
+    fn mouse_click_inside(&mut self, params: &mut MouseClickParams) {
+        if let Some(index) = get_row_index(self.region, self.options.len(), params.mouse_pos) {
+            self.select(index);
+        }
+    }
+
+    fn render(&mut self, params: &mut RenderParams) -> BehaviorRenderResult {
+        let theme = params.manager.get_theme();
+        let palette = get_widget_palette(&theme, &self.colors);
+        let style = get_widget_style(&theme, &self.colors, WidgetState::Normal);
+
+        fill_region(params, self.region, palette.background);
+
+        for (index, option) in self.options.iter().enumerate() {
+            let row = get_row_region(self.region, self.options.len(), index);
+            let row_height = row.get_max_y() - row.get_min_y();
+
+            let mark_region = inset_region(Region::new(row.get_min_x(), row.get_min_y(), row.get_min_x() + row_height, row.get_max_y()), 
+                    row_height * 0.25, row_height * 0.25);
+            fill_region(params, mark_region, style.background);
+            draw_outline(params, mark_region, style.border);
+            if self.selected == Some(index) {
+                let mark_size = mark_region.get_max_y() - mark_region.get_min_y();
+                fill_region(params, inset_region(mark_region, mark_size * 0.25, mark_size * 0.25), style.accent);
+            }
+
+            let text_region = Region::new(row.get_min_x() + row_height + theme.spacing.padding, row.get_min_y(), row.get_max_x(), row.get_max_y());
+            draw_text(params, option, center_text_region(text_region, theme.fonts.body_height), style.text);
+        }
+
+        BehaviorRenderResult::with_cursor(Cursor::POINTER, vec![PassedRenderAction::new(self.region)])
+    }
+
+    fn get_cursor(&mut self, _params: &mut CursorParams) -> Option<Cursor> {
+        Some(Cursor::POINTER)
+    }
+}
@@ -0,0 +1,197 @@
+use crate::*;
+
+use super::{
+    request_render,
+    get_widget_style,
+    get_widget_palette
+};
+
+use std::cell::RefCell;
+use std::rc::*;
+
+use wasmuri_core::Region;
+
+/// A horizontal slider to choose a value between a minimum and maximum. The value can be changed by clicking on the track or by
+/// scrolling while the mouse is above the slider.
+pub struct Slider {
+
+    behavior: Rc<RefCell<SliderBehavior>>
+}
+
+impl Slider {
+
+    pub fn new(region: Region, min_value: f32, max_value: f32, value: f32, step: f32, on_change: Box<dyn FnMut(f32)>) -> Slider {
+        Slider {
+            behavior: Rc::new(RefCell::new(SliderBehavior {
+                agent: Weak::new(),
+                region,
+                colors: None,
+                access_name: "Slider".to_string(),
+                min_value,
+                max_value,
+                value: value.max(min_value).min(max_value),
+                step,
+                on_change
+            }))
+        }
+    }
+
+    pub fn celled(region: Region, min_value: f32, max_value: f32, value: f32, step: f32, on_change: Box<dyn FnMut(f32)>) -> Rc<RefCell<Slider>> {
+        Rc::new(RefCell::new(Self::new(region, min_value, max_value, value, step, on_change)))
+    }
+
+    pub fn get_behavior(&self) -> &Rc<RefCell<SliderBehavior>> {
+        &self.behavior
+    }
+}
+
+impl Component for Slider {
+
+    fn create_behaviors(&mut self) -> Vec<Rc<RefCell<dyn ComponentBehavior>>> {
+        let behavior: Rc<RefCell<dyn ComponentBehavior>> = self.behavior.clone();
+        vec![behavior]
+    }
+}
+
+pub struct SliderBehavior {
+
+    agent: Weak<RefCell<ComponentAgent>>,
+
+    region: Region,
+    colors: Option<WidgetColors>,
+
+    /// The name assistive technology uses for this slider
+    access_name: String,
+
+    min_value: f32,
+    max_value: f32,
+    value: f32,
+    step: f32,
+
+    on_change: Box<dyn FnMut(f32)>
+}
+
+impl SliderBehavior {
+
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+
+    /// Changes the value without calling the on_change callback
+    pub fn set_value(&mut self, value: f32) {
+        self.value = value.max(self.min_value).min(self.max_value);
+        request_render(&self.agent);
+    }
+
+    /// Gives this slider its own colors instead of the colors of the current theme
+    pub fn set_colors(&mut self, colors: WidgetColors) {
+        self.colors = Some(colors);
+        request_render(&self.agent);
+    }
+
+    /// Lets this slider use the colors of the current theme again
+    pub fn clear_colors(&mut self) {
+        self.colors = None;
+        request_render(&self.agent);
+    }
+
+    /// Sets the name that assistive technology uses for this slider, which is "Slider" by default
+    pub fn set_access_name(&mut self, name: &str) {
+        self.access_name = name.to_string();
+    }
+
+    fn change_value(&mut self, new_value: f32) {
+        let new_value = new_value.max(self.min_value).min(self.max_value);
+        if new_value != self.value {
+            self.value = new_value;
+            (self.on_change)(new_value);
+            request_render(&self.agent);
+        }
+    }
+
+    fn get_fraction(&self) -> f32 {
+        if self.max_value > self.min_value {
+            (self.value - self.min_value) / (self.max_value - self.min_value)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl ComponentBehavior for SliderBehavior {
+
+    fn attach(&mut self, agent: &mut dyn LayerAgent) {
+        agent.claim_render_space(self.region, RenderTrigger::Request, RenderOpacity::Solid, RenderPhase::Start)
+                .expect("Slider render space should be free");
+        agent.claim_mouse_click_space(self.region).expect("Slider click space should be free");
+        agent.claim_mouse_scroll_space(self.region).expect("Slider scroll space should be free");
+    }
+
+    fn set_agent(&mut self, agent: Weak<RefCell<ComponentAgent>>) {
+        self.agent = agent;
+    }
+
+    fn get_agent(&self) -> &Weak<RefCell<ComponentAgent>> {
+        &self.agent
+    }
+
+    fn get_access_node(&self) -> Option<AccessNode> {
+        Some(AccessNode::new(AccessRole::Slider, &self.access_name, self.region).with_value(&self.value.to_string()))
+    }
+
+    fn access_action(&mut self, params: &mut AccessActionParams) {
+        match params.action {
+            AccessAction::Increment => self.change_value(self.value + self.step),
+            AccessAction::Decrement => self.change_value(self.value - self.step),
+            _ => {}
+        };
+    }
+
+    fn mouse_click_inside(&mut self, params: &mut MouseClickParams) {
+        let width = self.region.get_max_x() - self.region.get_min_x();
+        let fraction = (params.mouse_pos.0 - self.region.get_min_x()) / width;
+        let mut new_value = self.min_value + fraction * (self.max_value - self.min_value);
+
+        // Snap the value to the nearest step
+        if self.step > 0.0 {
+            new_value = self.min_value + ((new_value - self.min_value) / self.step).round() * self.step;
+        }
+
+        self.change_value(new_value);
+    }
+
+    fn mouse_scroll(&mut self, params: &mut MouseScrollParams) -> bool {
+        if params.delta < 0.0 {
+            self.change_value(self.value + self.step);
+        } else if params.delta > 0.0 {
+            self.change_value(self.value - self.step);
+        }
+        true
+    }
+
+    fn render(&mut self, params: &mut RenderParams) -> BehaviorRenderResult {
+        let height = self.region.get_max_y() - self.region.get_min_y();
+        let width = self.region.get_max_x() - self.region.get_min_x();
+        let center_y = self.region.get_min_y() + height * 0.5;
+
+        let theme = params.manager.get_theme();
+        let palette = get_widget_palette(&theme, &self.colors);
+        let style = get_widget_style(&theme, &self.colors, WidgetState::Normal);
+
+        fill_region(params, self.region, palette.background);
+
+        let track = Region::new(self.region.get_min_x(), center_y - height * 0.1, self.region.get_max_x(), center_y + height * 0.1);
+        fill_region(params, track, style.border);
+
+        let knob_width = (width * 0.05).min(height * 0.5);
+        let knob_x = self.region.get_min_x() + self.get_fraction() * (width - knob_width);
+        let knob = Region::new(knob_x, self.region.get_min_y(), knob_x + knob_width, self.region.get_max_y());
+        fill_region(params, knob, style.accent);
+
+        BehaviorRenderResult::with_cursor(Cursor::POINTER, vec![PassedRenderAction::new(self.region)])
+    }
+
+    fn get_cursor(&mut self, _params: &mut CursorParams) -> Option<Cursor> {
+        Some(Cursor::POINTER)
+    }
+}
@@ -0,0 +1,460 @@
+use crate::*;
+
+use super::{
+    request_render,
+    get_widget_style
+};
+
+use std::cell::RefCell;
+use std::rc::*;
+
+use wasmuri_core::{
+    Color,
+    Region
+};
+
+/// A single line text input. It gets the keyboard focus when it is clicked and loses it when the user clicks elsewhere.
+/// Text that is composed with an input method editor is received through the ImeBridge of the ContainerManager. The caret can
+/// be moved with the arrow keys, Home and End, and text can be selected by holding shift or with Ctrl+A. Copy and cut only use
+/// the selected text.
+pub struct TextField {
+
+    behavior: Rc<RefCell<TextFieldBehavior>>
+}
+
+impl TextField {
+
+    pub fn new(region: Region, text: &str, on_change: Box<dyn FnMut(&str)>) -> TextField {
+        TextField {
+            behavior: Rc::new(RefCell::new(TextFieldBehavior {
+                agent: Weak::new(),
+                region,
+                colors: None,
+                access_name: "Text field".to_string(),
+                text: text.to_string(),
+                caret: text.chars().count(),
+                selection_anchor: None,
+                composing_text: String::new(),
+                focused: false,
+                on_change,
+                on_submit: None
+            }))
+        }
+    }
+
+    pub fn celled(region: Region, text: &str, on_change: Box<dyn FnMut(&str)>) -> Rc<RefCell<TextField>> {
+        Rc::new(RefCell::new(Self::new(region, text, on_change)))
+    }
+
+    pub fn get_behavior(&self) -> &Rc<RefCell<TextFieldBehavior>> {
+        &self.behavior
+    }
+}
+
+impl Component for TextField {
+
+    fn create_behaviors(&mut self) -> Vec<Rc<RefCell<dyn ComponentBehavior>>> {
+        let behavior: Rc<RefCell<dyn ComponentBehavior>> = self.behavior.clone();
+        vec![behavior]
+    }
+}
+
+pub struct TextFieldBehavior {
+
+    agent: Weak<RefCell<ComponentAgent>>,
+
+    region: Region,
+    colors: Option<WidgetColors>,
+
+    /// The name assistive technology uses for this text field
+    access_name: String,
+
+    text: String,
+
+    /// The position of the caret, as the number of characters before it
+    caret: usize,
+
+    /// The position (in characters) where the selection started, or None if no text is selected. The selection is the text between
+    /// the anchor and the caret.
+    selection_anchor: Option<usize>,
+
+    /// The text that is currently being composed with an input method editor, but is not yet part of the text
+    composing_text: String,
+
+    focused: bool,
+
+    on_change: Box<dyn FnMut(&str)>,
+    on_submit: Option<Box<dyn FnMut(&str)>>
+}
+
+impl TextFieldBehavior {
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Changes the text without calling the on_change callback. The caret is moved to the end of the text.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.caret = self.text.chars().count();
+        self.selection_anchor = None;
+        request_render(&self.agent);
+    }
+
+    /// Gets the selected text, which is empty if nothing is selected
+    pub fn get_selected_text(&self) -> &str {
+        let (start, end) = self.get_selection_range();
+        &self.text[self.to_byte_index(start) .. self.to_byte_index(end)]
+    }
+
+    /// Selects the entire text
+    pub fn select_all(&mut self) {
+        self.selection_anchor = Some(0);
+        self.caret = self.text.chars().count();
+        request_render(&self.agent);
+    }
+
+    /// Sets the callback that will be called when the user presses Enter while this text field has the focus
+    pub fn set_on_submit(&mut self, on_submit: Option<Box<dyn FnMut(&str)>>) {
+        self.on_submit = on_submit;
+    }
+
+    /// Gives this text field its own colors instead of the colors of the current theme
+    pub fn set_colors(&mut self, colors: WidgetColors) {
+        self.colors = Some(colors);
+        request_render(&self.agent);
+    }
+
+    /// Lets this text field use the colors of the current theme again
+    pub fn clear_colors(&mut self) {
+        self.colors = None;
+        request_render(&self.agent);
+    }
+
+    /// Sets the name that assistive technology uses for this text field, which is "Text field" by default
+    pub fn set_access_name(&mut self, name: &str) {
+        self.access_name = name.to_string();
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn focus(&mut self, manager: &ContainerManager) {
+        if !self.focused {
+            self.focused = true;
+            if let Some(key) = self.get_key() {
+                manager.get_ime().focus(key, self.region);
+            }
+            request_render(&self.agent);
+        }
+    }
+
+    fn blur(&mut self, manager: &ContainerManager) {
+        if self.focused {
+            self.focused = false;
+            self.composing_text.clear();
+            if let Some(key) = self.get_key() {
+                manager.get_ime().blur(key);
+            }
+            request_render(&self.agent);
+        }
+    }
+
+    fn get_key(&self) -> Option<ComponentKey> {
+        let agent = self.agent.upgrade()?;
+        let agent = agent.try_borrow().ok()?;
+        Some(agent.get_key())
+    }
+
+    /// Gets the character positions of the start and end of the selection. They are both equal to the caret if nothing is selected.
+    fn get_selection_range(&self) -> (usize, usize) {
+        match self.selection_anchor {
+            Some(anchor) => (anchor.min(self.caret), anchor.max(self.caret)),
+            None => (self.caret, self.caret)
+        }
+    }
+
+    /// Converts a position in characters to a position in bytes of the text
+    fn to_byte_index(&self, char_index: usize) -> usize {
+        self.text.char_indices().nth(char_index).map_or(self.text.len(), |(byte_index, _character)| byte_index)
+    }
+
+    /// Removes the characters between the given character positions, moves the caret to the start and clears the selection. This
+    /// doesn't call the on_change callback.
+    fn remove_range(&mut self, start: usize, end: usize) {
+        let byte_range = self.to_byte_index(start) .. self.to_byte_index(end);
+        self.text.replace_range(byte_range, "");
+        self.caret = start;
+        self.selection_anchor = None;
+    }
+
+    /// Removes the selected text and returns true, or returns false if nothing was selected
+    fn remove_selection(&mut self) -> bool {
+        let (start, end) = self.get_selection_range();
+        self.selection_anchor = None;
+        if start == end {
+            return false;
+        }
+        self.remove_range(start, end);
+        true
+    }
+
+    /// Moves the caret to the given position. If extend_selection is true, the text between the old and new position is added to
+    /// the selection. Otherwise, the selection is cleared.
+    fn move_caret(&mut self, new_caret: usize, extend_selection: bool) {
+        if extend_selection {
+            if self.selection_anchor.is_none() {
+                self.selection_anchor = Some(self.caret);
+            }
+        } else {
+            self.selection_anchor = None;
+        }
+        self.caret = new_caret.min(self.text.chars().count());
+        request_render(&self.agent);
+    }
+
+    /// Replaces the selected text with the given text, or inserts it at the caret if nothing is selected
+    fn insert_text(&mut self, text: &str) {
+        let filtered: String = text.chars().filter(|character| *character != '\n' && *character != '\r').collect();
+        let removed = self.remove_selection();
+        if !filtered.is_empty() {
+            let byte_index = self.to_byte_index(self.caret);
+            self.text.insert_str(byte_index, &filtered);
+            self.caret += filtered.chars().count();
+        }
+        if removed || !filtered.is_empty() {
+            (self.on_change)(&self.text);
+        }
+        request_render(&self.agent);
+    }
+
+    /// Removes the selected text, or the character before (backward) or after the caret if nothing is selected
+    fn delete(&mut self, backward: bool) {
+        let changed = if self.remove_selection() {
+            true
+        } else if backward && self.caret > 0 {
+            self.remove_range(self.caret - 1, self.caret);
+            true
+        } else if !backward && self.caret < self.text.chars().count() {
+            self.remove_range(self.caret, self.caret + 1);
+            true
+        } else {
+            false
+        };
+
+        if changed {
+            (self.on_change)(&self.text);
+            request_render(&self.agent);
+        }
+    }
+}
+
+impl ComponentBehavior for TextFieldBehavior {
+
+    fn attach(&mut self, agent: &mut dyn LayerAgent) {
+        agent.claim_render_space(self.region, RenderTrigger::Request, RenderOpacity::Solid, RenderPhase::Text)
+                .expect("TextField render space should be free");
+        agent.claim_mouse_click_space(self.region).expect("TextField click space should be free");
+        agent.make_key_down_listener(0);
+        agent.make_composition_listener(0);
+        agent.make_copy_listener(0);
+        agent.make_paste_listener(0);
+        agent.make_cut_listener(0);
+    }
+
+    fn set_agent(&mut self, agent: Weak<RefCell<ComponentAgent>>) {
+        self.agent = agent;
+    }
+
+    fn get_agent(&self) -> &Weak<RefCell<ComponentAgent>> {
+        &self.agent
+    }
+
+    fn get_access_node(&self) -> Option<AccessNode> {
+        Some(AccessNode::new(AccessRole::TextField, &self.access_name, self.region).with_value(&self.text))
+    }
+
+    fn access_action(&mut self, params: &mut AccessActionParams) {
+        match params.action {
+            AccessAction::Focus | AccessAction::Activate => self.focus(params.manager),
+            AccessAction::Blur => self.blur(params.manager),
+            _ => {}
+        };
+    }
+
+    fn mouse_click_inside(&mut self, params: &mut MouseClickParams) {
+        self.focus(params.manager);
+    }
+
+    fn mouse_click_outside(&mut self, params: &mut MouseClickOutParams) {
+        self.blur(params.manager);
+    }
+
+    fn key_down(&mut self, params: &mut KeyDownParams) -> bool {
+        if !self.focused {
+            return false;
+        }
+
+        // The composed text will be passed to the composition method instead
+        if params.keys.is_composing() {
+            return true;
+        }
+
+        let key = params.keys.get_key();
+        let shift = params.keys.is_shift_down();
+        let command = params.keys.is_control_down() || params.keys.is_meta_down();
+        let (selection_start, selection_end) = self.get_selection_range();
+        if key == "Backspace" {
+            self.delete(true);
+            true
+        } else if key == "Delete" {
+            self.delete(false);
+            true
+        } else if key == "ArrowLeft" {
+
+            // Without shift, the left arrow only collapses the selection to its start
+            let new_caret = if !shift && selection_start != selection_end { selection_start } else { self.caret.saturating_sub(1) };
+            self.move_caret(new_caret, shift);
+            true
+        } else if key == "ArrowRight" {
+            let new_caret = if !shift && selection_start != selection_end { selection_end } else { self.caret + 1 };
+            self.move_caret(new_caret, shift);
+            true
+        } else if key == "Home" {
+            self.move_caret(0, shift);
+            true
+        } else if key == "End" {
+            self.move_caret(self.text.chars().count(), shift);
+            true
+        } else if command && (key == "a" || key == "A") {
+            self.select_all();
+            true
+        } else if key == "Enter" {
+            if let Some(on_submit) = &mut self.on_submit {
+                on_submit(&self.text);
+            }
+            true
+        } else if key.chars().count() == 1 && !command {
+            let key = key.to_string();
+            self.insert_text(&key);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn composition(&mut self, params: &mut CompositionParams) -> bool {
+        if !self.focused {
+            return false;
+        }
+
+        match params.composition.get_phase() {
+            CompositionPhase::Start => {
+
+                // Like typed text, the composed text replaces the selected text
+                if self.remove_selection() {
+                    (self.on_change)(&self.text);
+                }
+                self.composing_text = params.composition.get_data().to_string();
+                request_render(&self.agent);
+            }, CompositionPhase::Update => {
+                self.composing_text = params.composition.get_data().to_string();
+                request_render(&self.agent);
+            }, CompositionPhase::End => {
+                self.composing_text.clear();
+                self.insert_text(params.composition.get_data());
+            }
+        };
+        true
+    }
+
+    fn on_copy(&mut self) -> Option<ClipboardData> {
+        let selected = self.get_selected_text();
+        if self.focused && !selected.is_empty() {
+            Some(ClipboardData::Text(selected.to_string()))
+        } else {
+            None
+        }
+    }
+
+    fn on_paste(&mut self, clipboard: &ClipboardData) -> bool {
+        if !self.focused {
+            return false;
+        }
+
+        match clipboard {
+            ClipboardData::Text(text) => self.insert_text(text)
+        };
+        true
+    }
+
+    fn on_cut(&mut self) -> Option<ClipboardData> {
+        if !self.focused {
+            return None;
+        }
+
+        let cut_text = self.get_selected_text().to_string();
+        if self.remove_selection() {
+            (self.on_change)(&self.text);
+            request_render(&self.agent);
+            Some(ClipboardData::Text(cut_text))
+        } else {
+            None
+        }
+    }
+
+    fn render(&mut self, params: &mut RenderParams) -> BehaviorRenderResult {
+        let theme = params.manager.get_theme();
+        let style = get_widget_style(&theme, &self.colors, if self.focused { WidgetState::Focused } else { WidgetState::Normal });
+
+        let height = self.region.get_max_y() - self.region.get_min_y();
+        let text_region = center_text_region(inset_region(self.region, theme.spacing.padding, theme.spacing.padding), theme.fonts.body_height);
+
+        fill_region(params, self.region, style.background);
+        draw_outline(params, self.region, style.border);
+
+        // The text is drawn in 3 parts: the text before the selection, the selected text and the text after the selection
+        let (selection_start, selection_end) = self.get_selection_range();
+        let start_byte = self.to_byte_index(selection_start);
+        let end_byte = self.to_byte_index(selection_end);
+        let draw_part = |params: &mut RenderParams, part: &str, min_x: f32, color: Color| {
+            let part_region = Region::new(min_x, text_region.get_min_y(), text_region.get_max_x(), text_region.get_max_y());
+            draw_text(params, part, part_region, color).get_max_x()
+        };
+
+        let selection_min_x = draw_part(params, &self.text[.. start_byte], text_region.get_min_x(), style.text);
+        let selection_max_x = draw_part(params, &self.text[start_byte .. end_byte], selection_min_x, style.text);
+        if selection_end > selection_start {
+            let accent = style.accent;
+            let highlight = Color::from_rgba(accent.get_red(), accent.get_green(), accent.get_blue(), 90);
+            blend_region(params, Region::new(selection_min_x, text_region.get_min_y(), selection_max_x, text_region.get_max_y()), highlight);
+        }
+        let mut caret_x = if self.caret == selection_start { selection_min_x } else { selection_max_x };
+
+        // Nothing is selected while text is being composed, so the composed text is drawn at the caret
+        let mut after_min_x = selection_max_x;
+        if !self.composing_text.is_empty() {
+            let composed_max_x = draw_part(params, &self.composing_text, caret_x, style.accent);
+
+            // Underline the text that is being composed, like native text inputs do
+            let underline = Region::new(caret_x, text_region.get_min_y(), composed_max_x, text_region.get_min_y() + height * 0.05);
+            fill_region(params, underline, style.accent);
+            caret_x = composed_max_x;
+            after_min_x = composed_max_x;
+        }
+        draw_part(params, &self.text[end_byte ..], after_min_x, style.text);
+
+        if self.focused {
+            let caret = Region::new(caret_x, text_region.get_min_y(), caret_x + height * 0.05, text_region.get_max_y());
+            fill_region(params, caret, style.text);
+            params.manager.get_ime().set_caret(caret);
+        }
+
+        BehaviorRenderResult::with_cursor(Cursor::TEXT, vec![PassedRenderAction::new(self.region)])
+    }
+
+    fn get_cursor(&mut self, _params: &mut CursorParams) -> Option<Cursor> {
+        Some(Cursor::TEXT)
+    }
+}